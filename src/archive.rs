@@ -0,0 +1,214 @@
+//! Downloading and unpacking published package archives
+//!
+//! Registry artifacts (`.crate` files, sdists and wheels, npm tarballs, gems,
+//! Go module zips) are fetched whole and unpacked into a cache entry. Entries
+//! whose paths are absolute or contain `..` are skipped, and so are tarball
+//! entries other than regular files and directories: a symlink followed by an
+//! entry below it could write outside the cache entry.
+
+use flate2::read::GzDecoder;
+use std::io::Cursor;
+use std::path::{Component, Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ArchiveError {
+    #[error("Failed to fetch {url}: {message}")]
+    Fetch { url: String, message: String },
+
+    #[error("Failed to unpack {url}: {source}")]
+    Unpack { url: String, source: std::io::Error },
+}
+
+/// GET a URL and read the whole body
+pub fn fetch(url: &str) -> Result<Vec<u8>, ArchiveError> {
+    crate::network::get_bytes(url).map_err(|e| ArchiveError::Fetch {
+        url: url.to_string(),
+        message: e.to_string(),
+    })
+}
+
+/// Unpack a gzipped tarball into `dest`
+///
+/// The first `strip_components` path components of every entry are dropped,
+/// as with `tar --strip-components`: 1 for a top-level directory such as
+/// `<name>-<version>/` or `package/`. Returns the number of entries unpacked.
+pub fn unpack_tar_gz(body: &[u8], strip_components: usize, dest: &Path) -> std::io::Result<usize> {
+    std::fs::create_dir_all(dest)?;
+    let mut archive = tar::Archive::new(GzDecoder::new(body));
+    let mut unpacked = 0;
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !matches!(
+            entry.header().entry_type(),
+            tar::EntryType::Regular | tar::EntryType::Directory
+        ) {
+            continue;
+        }
+        let path = entry.path()?.into_owned();
+        let Some(relative) = safe_relative_path(&path, strip_components) else {
+            continue;
+        };
+        let target = dest.join(relative);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        entry.unpack(target)?;
+        unpacked += 1;
+    }
+
+    Ok(unpacked)
+}
+
+/// Unpack a zip file into `dest`, dropping the first `strip_components` path components
+///
+/// Returns the number of entries unpacked.
+pub fn unpack_zip(body: &[u8], strip_components: usize, dest: &Path) -> std::io::Result<usize> {
+    std::fs::create_dir_all(dest)?;
+    let mut archive = zip::ZipArchive::new(Cursor::new(body)).map_err(std::io::Error::other)?;
+    let mut unpacked = 0;
+
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i).map_err(std::io::Error::other)?;
+        let Some(relative) = safe_relative_path(Path::new(file.name()), strip_components) else {
+            continue;
+        };
+        let target = dest.join(relative);
+        unpacked += 1;
+        if file.is_dir() {
+            std::fs::create_dir_all(&target)?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut out = std::fs::File::create(&target)?;
+        std::io::copy(&mut file, &mut out)?;
+    }
+
+    Ok(unpacked)
+}
+
+/// Path of an archive entry inside the destination, without its first `strip_components` components
+///
+/// Ignores `.` components; rejects absolute paths and `..`.
+pub fn safe_relative_path(path: &Path, strip_components: usize) -> Option<PathBuf> {
+    let mut components = path
+        .components()
+        .filter(|c| !matches!(c, Component::CurDir));
+    for _ in 0..strip_components {
+        components.next()?;
+    }
+    let relative: PathBuf = components
+        .map(|c| match c {
+            Component::Normal(name) => Some(name),
+            _ => None,
+        })
+        .collect::<Option<_>>()?;
+    if relative.as_os_str().is_empty() {
+        return None;
+    }
+    Some(relative)
+}
+
+/// Archives for tests
+#[cfg(test)]
+pub mod fixtures {
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+
+    /// Add a regular file to a tarball being built
+    pub fn append_file(builder: &mut tar::Builder<impl Write>, path: &str, content: &[u8]) {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, path, content).unwrap();
+    }
+
+    /// Gzipped tarball of `files`, each below `root/` (or at the top if `root` is empty)
+    pub fn tar_gz(root: &str, files: &[(&str, &str)]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        for (name, content) in files {
+            let path = if root.is_empty() {
+                name.to_string()
+            } else {
+                format!("{}/{}", root, name)
+            };
+            append_file(&mut builder, &path, content.as_bytes());
+        }
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+
+    #[test]
+    fn test_safe_relative_path() {
+        assert_eq!(
+            safe_relative_path(Path::new("pkg-1.0/src/pkg/__init__.py"), 1),
+            Some(PathBuf::from("src/pkg/__init__.py"))
+        );
+        assert_eq!(
+            safe_relative_path(Path::new("./lib/rack.rb"), 0),
+            Some(PathBuf::from("lib/rack.rb"))
+        );
+        assert_eq!(safe_relative_path(Path::new("pkg-1.0"), 1), None);
+        assert_eq!(safe_relative_path(Path::new("pkg-1.0/../x"), 1), None);
+        assert_eq!(
+            safe_relative_path(Path::new("example.com/mod@v1.0.0/go.mod"), 2),
+            Some(PathBuf::from("go.mod"))
+        );
+        assert_eq!(safe_relative_path(Path::new("../escape"), 0), None);
+        assert_eq!(safe_relative_path(Path::new("/etc/passwd"), 0), None);
+    }
+
+    #[test]
+    fn test_unpack_tar_gz() {
+        let body = fixtures::tar_gz(
+            "package",
+            &[("index.js", "module.exports = 1;\n"), ("lib/a.js", "")],
+        );
+        let dest = std::env::temp_dir().join("dotdeps_archive_unpack_test");
+        let _ = std::fs::remove_dir_all(&dest);
+
+        assert_eq!(unpack_tar_gz(&body, 1, &dest).unwrap(), 2);
+        assert_eq!(
+            std::fs::read_to_string(dest.join("index.js")).unwrap(),
+            "module.exports = 1;\n"
+        );
+        assert!(dest.join("lib").join("a.js").exists());
+
+        let _ = std::fs::remove_dir_all(&dest);
+    }
+
+    #[test]
+    fn test_unpack_tar_gz_skips_symlinks() {
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        let mut link = tar::Header::new_gnu();
+        link.set_entry_type(tar::EntryType::Symlink);
+        link.set_size(0);
+        link.set_mode(0o777);
+        builder
+            .append_link(&mut link, "evil-1.0.0/link", "../../outside")
+            .unwrap();
+        fixtures::append_file(&mut builder, "evil-1.0.0/link/pwned.txt", b"pwned");
+        let body = builder.into_inner().unwrap().finish().unwrap();
+
+        let base = std::env::temp_dir().join("dotdeps_archive_symlink_test");
+        let _ = std::fs::remove_dir_all(&base);
+        let dest = base.join("cache").join("entry");
+
+        unpack_tar_gz(&body, 1, &dest).unwrap();
+        assert!(!dest.join("link").is_symlink());
+        assert!(!base.join("outside").join("pwned.txt").exists());
+
+        let _ = std::fs::remove_dir_all(&base);
+    }
+}
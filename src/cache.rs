@@ -7,16 +7,22 @@
 //! - `~/.cache/dotdeps/node/@org/pkg/4.17.21/`
 //! - `~/.cache/dotdeps/go/github.com/org/repo/v2/1.0.0/`
 //!
+//! Each entry records where it came from in `.dotdeps-meta.json` (see [`CacheMeta`]).
+//!
 //! Cache eviction uses LRU (least recently used) strategy based on filesystem
 //! access time (atime). When cache exceeds the configured limit, oldest entries
 //! are removed first.
 
 use crate::cli::Ecosystem;
 use crate::lock::{self, CacheLock, LockError};
+use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use thiserror::Error;
 
+/// Provenance metadata filename inside each cache entry
+pub const META_FILE: &str = ".dotdeps-meta.json";
+
 #[derive(Error, Debug)]
 pub enum CacheError {
     #[error("Cannot determine cache directory. HOME environment variable not set.")]
@@ -57,17 +63,118 @@ pub enum CacheError {
         to: PathBuf,
         source: std::io::Error,
     },
+
+    #[error("Failed to write cache metadata {path}: {source}")]
+    WriteMeta {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Failed to write project registry {path}: {source}")]
+    WriteRegistry {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+/// Where a cache entry came from, written to `.dotdeps-meta.json` at populate time
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheMeta {
+    /// How the entry was fetched
+    #[serde(default)]
+    pub strategy: FetchStrategy,
+    /// Repository the entry was cloned from (or the proxy it was downloaded from)
+    pub repo_url: String,
+    /// The ref that was checked out (tag name, commit, or "default branch")
+    pub resolved_ref: String,
+    /// Full commit SHA of the checkout
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// Whether no tag matched and the default branch was cloned instead
+    #[serde(default)]
+    pub used_default_branch: bool,
+    /// Directory within the checkout that holds the package (linked from .deps/)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subdir: Option<String>,
+    /// SHA-256 of the downloaded artifact (registry artifacts only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
+    /// Unix timestamp (seconds) of the fetch
+    pub fetched_at: u64,
+    /// dotdeps version that populated the entry
+    pub dotdeps_version: String,
+}
+
+impl CacheMeta {
+    pub fn new(repo_url: &str, resolved_ref: &str, used_default_branch: bool) -> Self {
+        Self {
+            strategy: FetchStrategy::Git,
+            repo_url: repo_url.to_string(),
+            resolved_ref: resolved_ref.to_string(),
+            commit: None,
+            used_default_branch,
+            subdir: None,
+            checksum: None,
+            fetched_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or(Duration::ZERO)
+                .as_secs(),
+            dotdeps_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+
+    pub fn with_commit(mut self, commit: Option<String>) -> Self {
+        self.commit = commit;
+        self
+    }
+
+    pub fn with_subdir(mut self, subdir: Option<String>) -> Self {
+        self.subdir = subdir;
+        self
+    }
+
+    pub fn with_strategy(mut self, strategy: FetchStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    pub fn with_checksum(mut self, checksum: Option<String>) -> Self {
+        self.checksum = checksum;
+        self
+    }
 }
 
-/// Information about a cached package for eviction purposes
-#[derive(Debug)]
+/// How a cache entry's sources were obtained
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FetchStrategy {
+    /// Git clone (the entry has a `.git` directory)
+    #[default]
+    Git,
+    /// Module zip from a Go module proxy (no `.git` directory)
+    GoProxy,
+    /// Package file published to the registry, e.g. a `.crate` (no `.git` directory)
+    RegistryArtifact,
+    /// Copied from a package manager's installed sources, e.g. `node_modules` (no `.git` directory)
+    Installed,
+}
+
+/// Information about a cached package version
+#[derive(Debug, Clone)]
 pub struct CacheEntry {
     /// Full path to the cached package version directory
     pub path: PathBuf,
+    /// Ecosystem directory the entry lives under (e.g., "python")
+    pub ecosystem: String,
+    /// Package name, possibly nested (e.g., "@org/pkg")
+    pub package: String,
+    /// Version directory name
+    pub version: String,
     /// Size in bytes of all files in the directory
     pub size: u64,
     /// Last access time (atime) of the directory
     pub accessed: SystemTime,
+    /// Provenance metadata, if the entry has any (entries from older versions don't)
+    pub meta: Option<CacheMeta>,
 }
 
 /// Returns the base cache directory: `~/.cache/dotdeps`
@@ -106,8 +213,30 @@ pub fn package_dir(
 /// Check if the cache directory exists and contains a valid clone
 pub fn exists(ecosystem: Ecosystem, package: &str, version: &str) -> Result<bool, CacheError> {
     let path = package_dir(ecosystem, package, version)?;
-    // Check for .git directory as indicator of a valid clone
-    Ok(path.join(".git").is_dir())
+    Ok(is_entry(&path))
+}
+
+/// Check if a directory is a populated cache entry
+///
+/// Clones are recognised by their `.git` directory; downloaded archives have
+/// no `.git`, so their metadata file marks them instead.
+pub fn is_entry(dir: &Path) -> bool {
+    dir.join(".git").is_dir() || dir.join(META_FILE).is_file()
+}
+
+/// Write provenance metadata into a cache entry directory
+pub fn write_meta(dir: &Path, meta: &CacheMeta) -> Result<(), CacheError> {
+    let path = dir.join(META_FILE);
+    let json = serde_json::to_string_pretty(meta).expect("cache metadata is always serializable");
+    std::fs::write(&path, json + "\n").map_err(|source| CacheError::WriteMeta { path, source })
+}
+
+/// Read provenance metadata from a cache entry directory
+///
+/// Returns None if the entry has no metadata or it can't be parsed.
+pub fn read_meta(dir: &Path) -> Option<CacheMeta> {
+    let content = std::fs::read_to_string(dir.join(META_FILE)).ok()?;
+    serde_json::from_str(&content).ok()
 }
 
 /// Ensure the cache base directory exists and is writable
@@ -226,6 +355,8 @@ where
 }
 
 /// List all cached packages with their size and access time
+///
+/// Entries are sorted by ecosystem, package and version.
 pub fn list_entries() -> Result<Vec<CacheEntry>, CacheError> {
     let base = base_dir()?;
     if !base.exists() {
@@ -233,7 +364,8 @@ pub fn list_entries() -> Result<Vec<CacheEntry>, CacheError> {
     }
 
     let mut entries = Vec::new();
-    collect_cache_entries(&base, &mut entries)?;
+    collect_cache_entries(&base, &base, &mut entries)?;
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
     Ok(entries)
 }
 
@@ -259,17 +391,35 @@ pub fn evict_to_limit(
     limit_bytes: u64,
     exclude: Option<&PathBuf>,
 ) -> Result<Vec<PathBuf>, CacheError> {
-    let mut entries = list_entries()?;
+    let entries = list_entries()?;
+    let mut evicted = Vec::new();
+
+    for entry in plan_eviction(entries, limit_bytes, exclude) {
+        remove_entry(&entry.path)?;
+        evicted.push(entry.path);
+    }
+
+    Ok(evicted)
+}
+
+/// Choose the least recently accessed entries to remove to get under the limit
+///
+/// If `exclude` is provided, that path is never chosen. Nothing is deleted.
+pub fn plan_eviction(
+    mut entries: Vec<CacheEntry>,
+    limit_bytes: u64,
+    exclude: Option<&PathBuf>,
+) -> Vec<CacheEntry> {
     let mut current_size: u64 = entries.iter().map(|e| e.size).sum();
 
     if current_size <= limit_bytes {
-        return Ok(Vec::new());
+        return Vec::new();
     }
 
     // Sort by access time, oldest first (LRU)
-    entries.sort_by(|a, b| a.accessed.cmp(&b.accessed));
+    entries.sort_by_key(|e| e.accessed);
 
-    let mut evicted = Vec::new();
+    let mut planned = Vec::new();
 
     for entry in entries {
         if current_size <= limit_bytes {
@@ -283,24 +433,53 @@ pub fn evict_to_limit(
             continue;
         }
 
-        // Delete the directory
-        std::fs::remove_dir_all(&entry.path).map_err(|source| CacheError::DeleteDir {
-            path: entry.path.clone(),
-            source,
-        })?;
-
         current_size = current_size.saturating_sub(entry.size);
-        evicted.push(entry.path);
-
-        // Clean up empty parent directories
-        cleanup_empty_parents(evicted.last().unwrap())?;
+        planned.push(entry);
     }
 
-    Ok(evicted)
+    planned
+}
+
+/// Select entries not accessed since `cutoff`
+pub fn entries_accessed_before(entries: Vec<CacheEntry>, cutoff: SystemTime) -> Vec<CacheEntry> {
+    entries
+        .into_iter()
+        .filter(|e| e.accessed < cutoff)
+        .collect()
+}
+
+/// Select the entries of a package, optionally limited to a single version
+pub fn entries_for_package(
+    entries: Vec<CacheEntry>,
+    ecosystem: Ecosystem,
+    package: &str,
+    version: Option<&str>,
+) -> Vec<CacheEntry> {
+    entries
+        .into_iter()
+        .filter(|e| {
+            e.ecosystem == ecosystem.to_string()
+                && e.package == package
+                && version.is_none_or(|v| e.version == v)
+        })
+        .collect()
+}
+
+/// Delete a cache entry directory and any parent directories left empty
+pub fn remove_entry(path: &Path) -> Result<(), CacheError> {
+    std::fs::remove_dir_all(path).map_err(|source| CacheError::DeleteDir {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    cleanup_empty_parents(path)
 }
 
 /// Recursively collect cache entries (version directories containing .git)
-fn collect_cache_entries(dir: &PathBuf, entries: &mut Vec<CacheEntry>) -> Result<(), CacheError> {
+fn collect_cache_entries(
+    base: &Path,
+    dir: &PathBuf,
+    entries: &mut Vec<CacheEntry>,
+) -> Result<(), CacheError> {
     let read_dir = match std::fs::read_dir(dir) {
         Ok(rd) => rd,
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
@@ -323,31 +502,66 @@ fn collect_cache_entries(dir: &PathBuf, entries: &mut Vec<CacheEntry>) -> Result
             continue;
         }
 
-        // Check if this is a version directory (has .git)
-        if path.join(".git").is_dir() {
+        // Check if this is a version directory
+        if is_entry(&path) {
+            let Some((ecosystem, package, version)) = split_entry_path(base, &path) else {
+                continue;
+            };
             let (size, accessed) = get_dir_stats(&path);
+            let meta = read_meta(&path);
             entries.push(CacheEntry {
                 path,
+                ecosystem,
+                package,
+                version,
                 size,
                 accessed,
+                meta,
             });
         } else {
             // Recurse into subdirectories
-            collect_cache_entries(&path, entries)?;
+            collect_cache_entries(base, &path, entries)?;
         }
     }
 
     Ok(())
 }
 
+/// Split a cache entry path into (ecosystem, package, version)
+///
+/// `<base>/<ecosystem>/<package...>/<version>` - the package may span several
+/// directories (e.g., `node/@org/pkg/1.0.0`).
+fn split_entry_path(base: &Path, path: &Path) -> Option<(String, String, String)> {
+    let relative = path.strip_prefix(base).ok()?;
+    let parts: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+
+    if parts.len() < 3 {
+        return None;
+    }
+
+    let ecosystem = parts[0].clone();
+    let version = parts[parts.len() - 1].clone();
+    let package = parts[1..parts.len() - 1].join("/");
+    Some((ecosystem, package, version))
+}
+
 /// Get directory size and last access time
 fn get_dir_stats(dir: &PathBuf) -> (u64, SystemTime) {
     let mut size = 0u64;
     let mut latest_access = SystemTime::UNIX_EPOCH;
 
     // Use the .git directory's access time as a proxy for cache entry access
-    // This is more reliable than trying to track access to every file
-    if let Ok(metadata) = std::fs::metadata(dir.join(".git"))
+    // This is more reliable than trying to track access to every file.
+    // Entries without .git fall back to their metadata file.
+    let marker = if dir.join(".git").exists() {
+        dir.join(".git")
+    } else {
+        dir.join(META_FILE)
+    };
+    if let Ok(metadata) = std::fs::metadata(marker)
         && let Ok(accessed) = metadata.accessed()
     {
         latest_access = accessed;
@@ -449,4 +663,150 @@ mod tests {
         let path_str = path.to_string_lossy();
         assert!(path_str.contains("dotdeps/go/github.com/org/repo/v2/1.0.0"));
     }
+
+    #[test]
+    fn test_write_and_read_meta() {
+        let dir = std::env::temp_dir().join("dotdeps_cache_meta_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let meta = CacheMeta::new(
+            "https://github.com/psf/requests.git",
+            "default branch",
+            true,
+        )
+        .with_commit(Some("0e322af87745eff34caffe4df68456ebc20d9068".to_string()));
+        write_meta(&dir, &meta).unwrap();
+
+        let read = read_meta(&dir).unwrap();
+        assert_eq!(read, meta);
+        assert!(read.used_default_branch);
+        assert_eq!(read.dotdeps_version, env!("CARGO_PKG_VERSION"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_read_meta_missing_or_invalid() {
+        let dir = std::env::temp_dir().join("dotdeps_cache_meta_invalid_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        assert!(read_meta(&dir).is_none());
+        std::fs::write(dir.join(META_FILE), "not json").unwrap();
+        assert!(read_meta(&dir).is_none());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_is_entry_for_downloaded_archive() {
+        let dir = std::env::temp_dir().join("dotdeps_cache_archive_entry_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        assert!(!is_entry(&dir));
+        let meta = CacheMeta::new("https://proxy.golang.org", "v1.27.0", false)
+            .with_strategy(FetchStrategy::GoProxy);
+        write_meta(&dir, &meta).unwrap();
+        assert!(is_entry(&dir));
+
+        let content = std::fs::read_to_string(dir.join(META_FILE)).unwrap();
+        assert!(content.contains("\"strategy\": \"go-proxy\""));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_meta_for_registry_artifact() {
+        let meta = CacheMeta::new(
+            "https://static.crates.io/crates",
+            "serde-1.0.200.crate",
+            false,
+        )
+        .with_strategy(FetchStrategy::RegistryArtifact)
+        .with_checksum(Some("abc123".to_string()));
+
+        let json = serde_json::to_string(&meta).unwrap();
+        assert!(json.contains("\"strategy\":\"registry-artifact\""));
+        assert!(json.contains("\"checksum\":\"abc123\""));
+        assert_eq!(serde_json::from_str::<CacheMeta>(&json).unwrap(), meta);
+    }
+
+    #[test]
+    fn test_meta_without_strategy_is_git() {
+        let json = r#"{"repo_url": "https://github.com/psf/requests.git", "resolved_ref": "v2.31.0", "fetched_at": 0, "dotdeps_version": "0.1.0"}"#;
+        let meta: CacheMeta = serde_json::from_str(json).unwrap();
+        assert_eq!(meta.strategy, FetchStrategy::Git);
+    }
+
+    fn entry(path: &str, size: u64, accessed_secs: u64) -> CacheEntry {
+        let (ecosystem, package, version) =
+            split_entry_path(Path::new("/cache"), Path::new(path)).unwrap();
+        CacheEntry {
+            path: PathBuf::from(path),
+            ecosystem,
+            package,
+            version,
+            size,
+            accessed: UNIX_EPOCH + Duration::from_secs(accessed_secs),
+            meta: None,
+        }
+    }
+
+    #[test]
+    fn test_split_entry_path() {
+        let base = Path::new("/cache");
+        assert_eq!(
+            split_entry_path(base, Path::new("/cache/node/@org/pkg/4.17.21")),
+            Some((
+                "node".to_string(),
+                "@org/pkg".to_string(),
+                "4.17.21".to_string()
+            ))
+        );
+        assert_eq!(
+            split_entry_path(base, Path::new("/cache/python/2.31.0")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_plan_eviction_oldest_first() {
+        let entries = vec![
+            entry("/cache/python/new/1.0.0", 100, 300),
+            entry("/cache/python/old/1.0.0", 100, 100),
+            entry("/cache/python/mid/1.0.0", 100, 200),
+        ];
+
+        let planned = plan_eviction(entries.clone(), 150, None);
+        let packages: Vec<&str> = planned.iter().map(|e| e.package.as_str()).collect();
+        assert_eq!(packages, vec!["old", "mid"]);
+
+        let excluded = PathBuf::from("/cache/python/old/1.0.0");
+        let planned = plan_eviction(entries.clone(), 150, Some(&excluded));
+        let packages: Vec<&str> = planned.iter().map(|e| e.package.as_str()).collect();
+        assert_eq!(packages, vec!["mid", "new"]);
+
+        assert!(plan_eviction(entries, 300, None).is_empty());
+    }
+
+    #[test]
+    fn test_entries_accessed_before_and_for_package() {
+        let entries = vec![
+            entry("/cache/python/requests/2.30.0", 10, 100),
+            entry("/cache/python/requests/2.31.0", 10, 300),
+            entry("/cache/node/requests/1.0.0", 10, 100),
+        ];
+
+        let old = entries_accessed_before(entries.clone(), UNIX_EPOCH + Duration::from_secs(200));
+        assert_eq!(old.len(), 2);
+
+        let all = entries_for_package(entries.clone(), Ecosystem::Python, "requests", None);
+        assert_eq!(all.len(), 2);
+
+        let one = entries_for_package(entries, Ecosystem::Python, "requests", Some("2.31.0"));
+        assert_eq!(one.len(), 1);
+        assert_eq!(one[0].version, "2.31.0");
+    }
 }
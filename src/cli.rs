@@ -1,6 +1,7 @@
 use clap::{Parser, Subcommand};
 use std::fmt;
 use std::str::FromStr;
+use std::time::Duration;
 
 /// CLI tool that fetches dependency source code for LLM context
 #[derive(Parser, Debug)]
@@ -17,6 +18,14 @@ pub struct Cli {
     /// Preview actions without making changes
     #[arg(long, global = true)]
     pub dry_run: bool,
+
+    /// Never access the network: use cached and locally installed sources only
+    #[arg(long, global = true)]
+    pub offline: bool,
+
+    /// Ask package registries again instead of using cached registry metadata
+    #[arg(long, global = true)]
+    pub refresh: bool,
 }
 
 #[derive(Subcommand, Debug)]
@@ -47,6 +56,18 @@ pub enum Command {
     Context,
     /// Remove all .deps/ in current directory
     Clean,
+    /// Make .deps/ match the project manifest (.deps.toml)
+    Sync,
+    /// Inspect and manage the global cache (~/.cache/dotdeps)
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommand,
+    },
+    /// Inspect the configuration
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
     /// Update dotdeps to the latest version
     Update {
         /// Check for updates without installing
@@ -55,6 +76,72 @@ pub enum Command {
     },
 }
 
+#[derive(Subcommand, Debug)]
+pub enum CacheCommand {
+    /// List cache entries with size, last access, and the projects linking to them
+    Ls,
+    /// Show the total cache size
+    Size,
+    /// Remove cache entries not accessed recently
+    Prune {
+        /// Remove entries not accessed within this duration (e.g. 30d, 12h, 2w)
+        #[arg(long, value_parser = parse_duration)]
+        older_than: Duration,
+    },
+    /// Remove cached versions of a package (all versions unless @<version> is given)
+    Rm {
+        /// Dependency specification: <ecosystem>:<package>[@<version>]
+        spec: DepSpec,
+    },
+    /// Evict least recently used entries until the cache is under cache_limit_gb
+    Gc,
+    /// Check cache entries against their recorded metadata
+    Verify,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// Show the configuration in effect: global config merged with the project's .dotdeps.json
+    Show {
+        /// List each value with the layer (default, global, project or env) it came from
+        #[arg(long)]
+        resolved: bool,
+    },
+}
+
+/// Parse a duration like `30d`, `12h`, `45m`, `2w` or `90s`
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (amount, unit) = s.split_at(split);
+
+    let amount: u64 = amount.parse().map_err(|_| {
+        format!(
+            "Invalid duration '{}'. Expected a number followed by s, m, h, d or w (e.g. 30d)",
+            s
+        )
+    })?;
+
+    let unit_secs = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" | "" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => {
+            return Err(format!(
+                "Invalid duration unit '{}'. Use s, m, h, d or w (e.g. 30d)",
+                unit
+            ));
+        }
+    };
+
+    let secs = amount
+        .checked_mul(unit_secs)
+        .ok_or_else(|| format!("Duration '{}' is too large", s))?;
+    Ok(Duration::from_secs(secs))
+}
+
 /// Supported package ecosystems
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Ecosystem {
@@ -70,6 +157,8 @@ pub enum Ecosystem {
 ///
 /// Represents the different ways a dependency can be specified in lockfiles:
 /// - Regular version from a package registry
+/// - Registry version that pins an exact commit (e.g., Go pseudo-versions)
+/// - Registry version of a replacement package (e.g., Go `replace` directives)
 /// - Git dependency with URL and commit hash
 /// - Local path dependency (should be skipped)
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -77,6 +166,20 @@ pub enum VersionInfo {
     /// Regular version string (e.g., "2.31.0")
     Version(String),
 
+    /// Registry version that encodes the exact commit to check out
+    /// (e.g., Go pseudo-version `0.0.0-20231215123456-abc123def456`).
+    /// The repository URL is detected like a regular version; the cache path uses the version.
+    Revision { version: String, commit: String },
+
+    /// Registry version of another package that replaces this one
+    /// (e.g., Go `replace github.com/a/b => github.com/us/b v1.2.3`).
+    /// Fetched and cached as the replacement package, linked under this package's name.
+    Replaced {
+        package: String,
+        version: String,
+        commit: Option<String>,
+    },
+
     /// Git dependency with URL and commit hash
     /// Cache path uses the commit hash as version: ~/.cache/dotdeps/<ecosystem>/<package>/<commit>/
     Git { url: String, commit: String },
@@ -85,6 +188,26 @@ pub enum VersionInfo {
     LocalPath { path: String },
 }
 
+impl VersionInfo {
+    /// The version used for the cache path and `.deps/` listing
+    ///
+    /// Git commits are truncated to 12 characters. Returns `None` for local
+    /// path dependencies, which are never cached.
+    pub fn cache_version(&self) -> Option<&str> {
+        match self {
+            VersionInfo::Version(version)
+            | VersionInfo::Revision { version, .. }
+            | VersionInfo::Replaced { version, .. } => Some(version),
+            VersionInfo::Git { commit, .. } => Some(if commit.len() > 12 {
+                &commit[..12]
+            } else {
+                commit
+            }),
+            VersionInfo::LocalPath { .. } => None,
+        }
+    }
+}
+
 impl fmt::Display for Ecosystem {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -274,4 +397,16 @@ mod tests {
         let result = "python:".parse::<DepSpec>();
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30d"), Ok(Duration::from_secs(30 * 86400)));
+        assert_eq!(parse_duration("12h"), Ok(Duration::from_secs(12 * 3600)));
+        assert_eq!(parse_duration("2w"), Ok(Duration::from_secs(14 * 86400)));
+        assert_eq!(parse_duration("45m"), Ok(Duration::from_secs(45 * 60)));
+        assert_eq!(parse_duration("7"), Ok(Duration::from_secs(7 * 86400)));
+        assert!(parse_duration("d").is_err());
+        assert!(parse_duration("30y").is_err());
+        assert!(parse_duration("99999999999999999w").is_err());
+    }
 }
@@ -1,14 +1,52 @@
 //! Configuration file support for dotdeps
 //!
-//! Reads configuration from `~/.config/dotdeps/config.json`:
+//! Reads the global configuration from `~/.config/dotdeps/config.json`, and a
+//! project configuration from `.dotdeps.json` in the current directory or the
+//! nearest parent up to the git root. The project file is meant to be committed
+//! (e.g. repository overrides for internal packages) and is layered over the
+//! global one: objects such as `overrides` merge key by key, so a project can
+//! add a `tag` to a package whose `repo` is set globally; any other value set
+//! in the project file replaces the global value. Precedence, highest first:
+//! command-line flags, `DOTDEPS_*` environment variables, project config,
+//! global config, defaults. `dotdeps config show --resolved` lists each value
+//! with the layer it came from.
 //!
 //! ```json
 //! {
 //!   "cache_limit_gb": 5,
+//!   "metadata_ttl_hours": 168,
+//!   "offline": false,
+//!   "go_proxy": "https://proxy.golang.org",
+//!   "crate_download_url": "https://static.crates.io/crates",
+//!   "gem_source": "https://rubygems.org",
+//!   "registry_url": {
+//!     "node": "https://npm.example.com"
+//!   },
+//!   "git_rewrites": {
+//!     "https://github.com/": "https://git.example.com/github/"
+//!   },
+//!   "strategy": {
+//!     "python": "registry-artifact"
+//!   },
 //!   "overrides": {
 //!     "python": {
 //!       "some-obscure-lib": {
 //!         "repo": "https://github.com/someone/some-obscure-lib"
+//!       },
+//!       "some-moved-lib": {
+//!         "repo": "https://github.com/new-org/some-moved-lib",
+//!         "versions": {
+//!           "<2.0": { "repo": "https://github.com/old-org/some-moved-lib" }
+//!         }
+//!       }
+//!     },
+//!     "node": {
+//!       "@types/node": {
+//!         "strategy": "registry-artifact"
+//!       },
+//!       "some-monorepo-pkg": {
+//!         "tag": "release-{version}",
+//!         "subdir": "packages/core"
 //!       }
 //!     }
 //!   }
@@ -16,14 +54,30 @@
 //! ```
 
 use crate::cli::Ecosystem;
-use serde::Deserialize;
-use std::collections::HashMap;
-use std::path::PathBuf;
+use crate::git::{RepoLocation, normalize_subdir};
+use crate::version_req::VersionReq;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 /// Default cache limit in GB
 const DEFAULT_CACHE_LIMIT_GB: f64 = 5.0;
 
+/// Default lifetime of cached registry metadata in hours (one week)
+const DEFAULT_METADATA_TTL_HOURS: f64 = 168.0;
+
+/// Project config filename, looked up from the current directory up to the git root
+pub const PROJECT_CONFIG_FILE: &str = ".dotdeps.json";
+
+/// Ecosystems whose registry URL can be set with `DOTDEPS_<ECOSYSTEM>_REGISTRY_URL`
+const REGISTRY_ECOSYSTEMS: [Ecosystem; 4] = [
+    Ecosystem::Python,
+    Ecosystem::Node,
+    Ecosystem::Rust,
+    Ecosystem::Ruby,
+];
+
 #[derive(Error, Debug)]
 pub enum ConfigError {
     #[error("Cannot determine config directory. HOME environment variable not set.")]
@@ -42,22 +96,224 @@ pub enum ConfigError {
     },
 }
 
+/// Check the version constraints of `versions` while parsing, so a typo is
+/// reported with the file it is in
+fn deserialize_versions<'de, D>(
+    deserializer: D,
+) -> Result<BTreeMap<String, PackageOverride>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let versions = BTreeMap::<String, PackageOverride>::deserialize(deserializer)?;
+    for constraint in versions.keys() {
+        constraint
+            .parse::<VersionReq>()
+            .map_err(serde::de::Error::custom)?;
+    }
+    Ok(versions)
+}
+
+/// Deserialize an override's `subdir`, rejecting paths that leave the repository
+fn deserialize_subdir<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let subdir = Option::<String>::deserialize(deserializer)?;
+    if let Some(dir) = &subdir
+        && normalize_subdir(dir).is_none()
+    {
+        return Err(serde::de::Error::custom(format!(
+            "Invalid subdir '{}': must be a directory inside the repository",
+            dir
+        )));
+    }
+    Ok(subdir)
+}
+
 /// Package-specific override configuration
-#[derive(Debug, Clone, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct PackageOverride {
     /// Custom repository URL for this package
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub repo: Option<String>,
+    /// Fetch strategy for this package (overrides the ecosystem's)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<Strategy>,
+    /// Release tag pattern tried before the generic ones, with `{version}` as
+    /// placeholder (e.g. `release-{version}`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    /// Directory within the repository that holds the package (linked from .deps/)
+    #[serde(
+        default,
+        deserialize_with = "deserialize_subdir",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub subdir: Option<String>,
+    /// Branch, tag or commit to check out instead of the release tag
+    #[serde(rename = "ref", skip_serializing_if = "Option::is_none")]
+    pub git_ref: Option<String>,
+    /// Overrides for versions matching a constraint (e.g. `"<2.0"`), applied
+    /// over the fields above, for packages that moved repositories
+    #[serde(
+        default,
+        deserialize_with = "deserialize_versions",
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pub versions: BTreeMap<String, PackageOverride>,
+}
+
+impl PackageOverride {
+    /// Fill the fields this override leaves unset from `fallback`
+    fn or(self, fallback: &PackageOverride) -> Self {
+        Self {
+            repo: self.repo.or_else(|| fallback.repo.clone()),
+            strategy: self.strategy.or(fallback.strategy),
+            tag: self.tag.or_else(|| fallback.tag.clone()),
+            subdir: self.subdir.or_else(|| fallback.subdir.clone()),
+            git_ref: self.git_ref.or_else(|| fallback.git_ref.clone()),
+            versions: BTreeMap::new(),
+        }
+    }
+
+    /// This override with the entries of `versions` that match `version` applied
+    ///
+    /// Without a version (git dependencies), version-scoped entries are ignored.
+    /// When several match, the most specific wins (`==1.9.0` over `>=1.5, <2.0`
+    /// over `<2.0`), with wider ones filling in what it leaves unset.
+    fn for_version(&self, ecosystem: Ecosystem, version: Option<&str>) -> Self {
+        let Some(version) = version else {
+            return PackageOverride::default().or(self);
+        };
+        let mut matching: Vec<(VersionReq, &PackageOverride)> = self
+            .versions
+            .iter()
+            .filter_map(|(constraint, scoped)| {
+                let req = constraint.parse::<VersionReq>().ok()?;
+                req.matches(ecosystem, version).then_some((req, scoped))
+            })
+            .collect();
+        matching.sort_by(|(a, _), (b, _)| b.cmp_specificity(a, ecosystem));
+        matching
+            .into_iter()
+            .fold(PackageOverride::default(), |merged, (_, scoped)| {
+                merged.or(&scoped.for_version(ecosystem, Some(version)))
+            })
+            .or(self)
+    }
+
+    /// Fill in `{name}`, `{scope}` and `{basename}` with parts of the package name
+    ///
+    /// For `@acme/ui` these are `@acme/ui`, `acme` and `ui`; for
+    /// `github.com/acme/tool` they are the module path, `github.com/acme` and
+    /// `tool`. `{scope}` is empty for unscoped names. `{version}` in `tag` is
+    /// left for tag resolution.
+    fn expand(self, package: &str) -> Self {
+        let (scope, basename) = match package.rsplit_once('/') {
+            Some((scope, basename)) => (scope.trim_start_matches('@'), basename),
+            None => ("", package),
+        };
+        let fill = |template: Option<String>| {
+            template.map(|t| {
+                t.replace("{name}", package)
+                    .replace("{scope}", scope)
+                    .replace("{basename}", basename)
+            })
+        };
+        Self {
+            repo: fill(self.repo),
+            strategy: self.strategy,
+            tag: fill(self.tag),
+            subdir: fill(self.subdir),
+            git_ref: fill(self.git_ref),
+            versions: self.versions,
+        }
+    }
+
+    /// Whether this override changes where or how the package is fetched
+    pub fn is_set(&self) -> bool {
+        self.repo.is_some()
+            || self.strategy.is_some()
+            || self.tag.is_some()
+            || self.subdir.is_some()
+            || self.git_ref.is_some()
+    }
+
+    /// Apply the tag, subdirectory and ref of this override to a detected location
+    pub fn apply_to(&self, location: &mut RepoLocation) {
+        if let Some(tag) = &self.tag {
+            location.tag_templates.insert(0, tag.clone());
+        }
+        // Checked again after templates are filled in
+        if let Some(subdir) = self.subdir.as_deref().and_then(normalize_subdir) {
+            location.subdir = Some(subdir);
+        }
+        if let Some(git_ref) = &self.git_ref {
+            location.git_ref = Some(git_ref.clone());
+        }
+    }
+}
+
+/// How package sources are fetched
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Strategy {
+    /// Clone the repository at the release tag (falling back to the
+    /// published package if there is no tag)
+    #[default]
+    Git,
+    /// Download the package file published to the registry
+    RegistryArtifact,
 }
 
 /// Top-level configuration structure
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     /// Maximum cache size in GB (default: 5)
     #[serde(default = "default_cache_limit")]
     pub cache_limit_gb: f64,
 
+    /// How long registry metadata (repository URLs) is cached, in hours (default: 168)
+    #[serde(default = "default_metadata_ttl")]
+    pub metadata_ttl_hours: f64,
+
+    /// Never access the network, as with `--offline` (default: false)
+    #[serde(default)]
+    pub offline: bool,
+
+    /// Go module proxy used when a module has no git repository
+    /// (default: `GOPROXY`, then https://proxy.golang.org)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub go_proxy: Option<String>,
+
+    /// Where published `.crate` files are downloaded from
+    /// (default: https://static.crates.io/crates)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crate_download_url: Option<String>,
+
+    /// Gem source published `.gem` files are downloaded from
+    /// (default: https://rubygems.org)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gem_source: Option<String>,
+
+    /// Registry base URL per ecosystem, for mirrors (e.g. Artifactory, Verdaccio, devpi)
+    /// Structure: { "ecosystem": "url" }
+    /// `DOTDEPS_<ECOSYSTEM>_REGISTRY_URL` takes precedence.
+    #[serde(default)]
+    pub registry_url: HashMap<String, String>,
+
+    /// Git URL rewrites applied before cloning, like git's `url.<base>.insteadOf`
+    /// Structure: { "prefix": "replacement" }
+    #[serde(default)]
+    pub git_rewrites: HashMap<String, String>,
+
+    /// Preferred fetch strategy per ecosystem
+    /// Structure: { "ecosystem": "git" | "registry-artifact" }
+    #[serde(default)]
+    pub strategy: HashMap<String, Strategy>,
+
     /// Per-ecosystem, per-package overrides
-    /// Structure: { "ecosystem": { "package": { "repo": "url" } } }
+    /// Structure: { "ecosystem": { "package": { "repo": "url", "tag": "...", "subdir": "...", "ref": "...", "strategy": "...", "versions": { "<2.0": { ... } } } } }
     #[serde(default)]
     pub overrides: HashMap<String, HashMap<String, PackageOverride>>,
 }
@@ -66,30 +322,121 @@ fn default_cache_limit() -> f64 {
     DEFAULT_CACHE_LIMIT_GB
 }
 
+fn default_metadata_ttl() -> f64 {
+    DEFAULT_METADATA_TTL_HOURS
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
             cache_limit_gb: DEFAULT_CACHE_LIMIT_GB,
+            metadata_ttl_hours: DEFAULT_METADATA_TTL_HOURS,
+            offline: false,
+            go_proxy: None,
+            crate_download_url: None,
+            gem_source: None,
+            registry_url: HashMap::new(),
+            git_rewrites: HashMap::new(),
+            strategy: HashMap::new(),
             overrides: HashMap::new(),
         }
     }
 }
 
+/// Where a configuration value came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    Default,
+    /// The global config file
+    Global(PathBuf),
+    /// The project's `.dotdeps.json`
+    Project(PathBuf),
+    /// An environment variable
+    Env(String),
+}
+
+impl ConfigSource {
+    /// Short name of the layer ("default", "global", "project" or "env")
+    pub fn layer(&self) -> &'static str {
+        match self {
+            ConfigSource::Default => "default",
+            ConfigSource::Global(_) => "global",
+            ConfigSource::Project(_) => "project",
+            ConfigSource::Env(_) => "env",
+        }
+    }
+
+    /// The file or environment variable that set the value, if any
+    pub fn origin(&self) -> Option<String> {
+        match self {
+            ConfigSource::Default => None,
+            ConfigSource::Global(path) | ConfigSource::Project(path) => {
+                Some(path.display().to_string())
+            }
+            ConfigSource::Env(var) => Some(var.clone()),
+        }
+    }
+}
+
+/// A configuration value, by dotted key (e.g. `overrides.python.requests.repo`),
+/// and where it came from
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedValue {
+    pub key: String,
+    pub value: serde_json::Value,
+    pub source: ConfigSource,
+}
+
+/// A config file's contents
+type Layer = (ConfigSource, serde_json::Value);
+
 impl Config {
-    /// Load configuration from the default path or return defaults if not found
+    /// Load the global and project configuration, or return defaults if neither exists
     pub fn load() -> Result<Self, ConfigError> {
-        let path = config_path()?;
+        Self::from_layers(&load_layers()?)
+    }
 
-        if !path.exists() {
-            return Ok(Config::default());
+    /// Load configuration like `load`, listing each value with the layer that set it
+    ///
+    /// Values without a setting are omitted unless they have a default.
+    pub fn load_resolved() -> Result<(Self, Vec<ResolvedValue>), ConfigError> {
+        let layers = load_layers()?;
+        let config = Self::from_layers(&layers)?;
+
+        let env_registry_urls = REGISTRY_ECOSYSTEMS.iter().filter_map(|ecosystem| {
+            let var = registry_url_env_var(*ecosystem);
+            let url = std::env::var(&var).ok().filter(|url| !url.is_empty())?;
+            Some((ecosystem.to_string(), url, var))
+        });
+        let mut values = resolve_sources(&config, &layers);
+        for (ecosystem, url, var) in env_registry_urls {
+            let key = format!("registry_url.{}", ecosystem);
+            values.retain(|value| value.key != key);
+            values.push(ResolvedValue {
+                key,
+                value: serde_json::Value::String(url),
+                source: ConfigSource::Env(var),
+            });
         }
+        values.sort_by(|a, b| a.key.cmp(&b.key));
 
-        let content = std::fs::read_to_string(&path).map_err(|source| ConfigError::ReadError {
-            path: path.clone(),
-            source,
-        })?;
+        Ok((config, values))
+    }
 
-        serde_json::from_str(&content).map_err(|source| ConfigError::ParseError { path, source })
+    /// Merge config files, later ones taking precedence
+    fn from_layers(layers: &[Layer]) -> Result<Self, ConfigError> {
+        let mut merged = serde_json::Value::Object(serde_json::Map::new());
+        for (_, value) in layers {
+            merge_json(&mut merged, value);
+        }
+        serde_json::from_value(merged).map_err(|source| ConfigError::ParseError {
+            path: layers
+                .iter()
+                .rev()
+                .find_map(|(source, _)| source.origin().map(PathBuf::from))
+                .unwrap_or_default(),
+            source,
+        })
     }
 
     /// Get the cache limit in bytes
@@ -97,19 +444,257 @@ impl Config {
         (self.cache_limit_gb * 1024.0 * 1024.0 * 1024.0) as u64
     }
 
-    /// Look up a custom repository URL override for an ecosystem/package pair
-    pub fn repo_override(&self, ecosystem: Ecosystem, package: &str) -> Option<&str> {
-        let ecosystem_key = ecosystem.to_string();
+    /// How long cached registry metadata stays fresh (0 disables the cache)
+    pub fn metadata_ttl(&self) -> std::time::Duration {
+        std::time::Duration::from_secs_f64(self.metadata_ttl_hours.max(0.0) * 60.0 * 60.0)
+    }
+
+    /// Registry base URL for an ecosystem: `DOTDEPS_<ECOSYSTEM>_REGISTRY_URL`,
+    /// then `registry_url` in the config, then `default`
+    pub fn registry_url_or(&self, ecosystem: Ecosystem, default: &str) -> String {
+        let env_var = registry_url_env_var(ecosystem);
+        self.resolve_registry_url(ecosystem, std::env::var(env_var).ok(), default)
+    }
+
+    fn resolve_registry_url(
+        &self,
+        ecosystem: Ecosystem,
+        from_env: Option<String>,
+        default: &str,
+    ) -> String {
+        from_env
+            .filter(|url| !url.is_empty())
+            .or_else(|| self.registry_url.get(&ecosystem.to_string()).cloned())
+            .as_deref()
+            .unwrap_or(default)
+            .trim_end_matches('/')
+            .to_string()
+    }
+
+    /// Apply `git_rewrites` to a repository URL
+    ///
+    /// Like git's `insteadOf`, the longest matching prefix wins.
+    pub fn rewrite_git_url(&self, url: &str) -> String {
+        self.git_rewrites
+            .iter()
+            .filter(|(prefix, _)| url.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(prefix, replacement)| format!("{}{}", replacement, &url[prefix.len()..]))
+            .unwrap_or_else(|| url.to_string())
+    }
+
+    /// Preferred fetch strategy for a package: its override, then the
+    /// ecosystem's setting, then git
+    pub fn preferred_strategy(
+        &self,
+        ecosystem: Ecosystem,
+        package: &str,
+        version: Option<&str>,
+    ) -> Strategy {
+        self.package_override(ecosystem, package, version)
+            .and_then(|override_cfg| override_cfg.strategy)
+            .or_else(|| self.strategy.get(&ecosystem.to_string()).copied())
+            .unwrap_or_default()
+    }
+
+    /// Look up the override for an ecosystem/package pair
+    ///
+    /// Keys may be patterns with `*` (`@acme/*`, `acme-*`). All entries matching
+    /// the package are merged field by field, the exact key first, then
+    /// patterns with more literal characters; templates in their values are
+    /// filled in for the package (see `PackageOverride::expand`). Within each
+    /// entry, `versions` entries matching `version` take precedence.
+    pub fn package_override(
+        &self,
+        ecosystem: Ecosystem,
+        package: &str,
+        version: Option<&str>,
+    ) -> Option<PackageOverride> {
+        let packages = self.overrides.get(&ecosystem.to_string())?;
         // Normalize package name for lookup (lowercase)
-        let package_lower = package.to_lowercase();
+        let name = package.to_lowercase();
+
+        let mut matching: Vec<(usize, &str, &PackageOverride)> = packages
+            .iter()
+            .filter_map(|(key, override_cfg)| {
+                let key_lower = key.to_lowercase();
+                let specificity = if key_lower == name {
+                    usize::MAX
+                } else if key_lower.contains('*') && glob_matches(&key_lower, &name) {
+                    key_lower.chars().filter(|c| *c != '*').count()
+                } else {
+                    return None;
+                };
+                Some((specificity, key.as_str(), override_cfg))
+            })
+            .collect();
+        if matching.is_empty() {
+            return None;
+        }
+        matching.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(b.1)));
 
-        self.overrides
-            .get(&ecosystem_key)
-            .and_then(|packages| packages.get(&package_lower))
-            .and_then(|override_cfg| override_cfg.repo.as_deref())
+        let merged = matching.into_iter().fold(
+            PackageOverride::default(),
+            |merged, (_, _, override_cfg)| merged.or(&override_cfg.for_version(ecosystem, version)),
+        );
+        Some(merged.expand(package))
+    }
+
+    /// Look up a custom repository URL override for an ecosystem/package pair
+    pub fn repo_override(
+        &self,
+        ecosystem: Ecosystem,
+        package: &str,
+        version: Option<&str>,
+    ) -> Option<String> {
+        self.package_override(ecosystem, package, version)
+            .and_then(|override_cfg| override_cfg.repo)
     }
 }
 
+/// `DOTDEPS_<ECOSYSTEM>_REGISTRY_URL`
+fn registry_url_env_var(ecosystem: Ecosystem) -> String {
+    format!(
+        "DOTDEPS_{}_REGISTRY_URL",
+        ecosystem.to_string().to_uppercase()
+    )
+}
+
+/// The global config and the project config, whichever exist, global first
+fn load_layers() -> Result<Vec<Layer>, ConfigError> {
+    let mut layers = Vec::new();
+
+    let global = config_path()?;
+    if global.exists() {
+        let value = read_layer(&global)?;
+        layers.push((ConfigSource::Global(global), value));
+    }
+
+    let project = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| find_project_config(&cwd));
+    if let Some(project) = project {
+        let value = read_layer(&project)?;
+        layers.push((ConfigSource::Project(project), value));
+    }
+
+    Ok(layers)
+}
+
+/// Read a config file, checking it on its own so errors name the file at fault
+fn read_layer(path: &Path) -> Result<serde_json::Value, ConfigError> {
+    let parse_error = |source| ConfigError::ParseError {
+        path: path.to_path_buf(),
+        source,
+    };
+    let content = std::fs::read_to_string(path).map_err(|source| ConfigError::ReadError {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    let value: serde_json::Value = serde_json::from_str(&content).map_err(parse_error)?;
+    serde_json::from_value::<Config>(value.clone()).map_err(parse_error)?;
+    Ok(value)
+}
+
+/// Find `.dotdeps.json` in `start` or its parents, not looking above the git root
+pub fn find_project_config(start: &Path) -> Option<PathBuf> {
+    for dir in start.ancestors() {
+        let path = dir.join(PROJECT_CONFIG_FILE);
+        if path.is_file() {
+            return Some(path);
+        }
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+    None
+}
+
+/// Merge `overlay` into `base`: objects key by key, anything else replaced
+fn merge_json(base: &mut serde_json::Value, overlay: &serde_json::Value) {
+    match (base, overlay) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(key) {
+                    Some(existing) => merge_json(existing, value),
+                    None => {
+                        base.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay.clone(),
+    }
+}
+
+/// List the values of `config` with the last layer that set each one
+fn resolve_sources(config: &Config, layers: &[Layer]) -> Vec<ResolvedValue> {
+    let mut leaves = Vec::new();
+    let resolved = serde_json::to_value(config).expect("config is always serializable");
+    collect_leaves(&resolved, &mut Vec::new(), &mut leaves);
+
+    leaves
+        .into_iter()
+        .map(|(path, value)| {
+            let source = layers
+                .iter()
+                .rev()
+                .find(|(_, layer)| lookup_json(layer, &path).is_some())
+                .map(|(source, _)| source.clone())
+                .unwrap_or(ConfigSource::Default);
+            ResolvedValue {
+                key: path.join("."),
+                value,
+                source,
+            }
+        })
+        .collect()
+}
+
+/// Collect the non-object values of `value` with their key paths
+fn collect_leaves(
+    value: &serde_json::Value,
+    path: &mut Vec<String>,
+    leaves: &mut Vec<(Vec<String>, serde_json::Value)>,
+) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, child) in map {
+                path.push(key.clone());
+                collect_leaves(child, path, leaves);
+                path.pop();
+            }
+        }
+        leaf => leaves.push((path.clone(), leaf.clone())),
+    }
+}
+
+fn lookup_json<'a>(value: &'a serde_json::Value, path: &[String]) -> Option<&'a serde_json::Value> {
+    path.iter().try_fold(value, |value, key| value.get(key))
+}
+
+/// Whether `text` matches `pattern`, where `*` matches any run of characters
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = parts.collect();
+    // Without a `*`, the pattern had to match exactly
+    let Some(last) = parts.pop() else {
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
 /// Returns the config file path: `~/.config/dotdeps/config.json`
 pub fn config_path() -> Result<PathBuf, ConfigError> {
     // Use XDG_CONFIG_HOME if set, otherwise fall back to ~/.config
@@ -144,6 +729,11 @@ mod tests {
         let json = r#"{}"#;
         let config: Config = serde_json::from_str(json).unwrap();
         assert_eq!(config.cache_limit_gb, DEFAULT_CACHE_LIMIT_GB);
+        assert_eq!(
+            config.metadata_ttl(),
+            std::time::Duration::from_secs(7 * 24 * 60 * 60)
+        );
+        assert!(!config.offline);
         assert!(config.overrides.is_empty());
     }
 
@@ -151,6 +741,9 @@ mod tests {
     fn test_parse_full_config() {
         let json = r#"{
             "cache_limit_gb": 10,
+            "metadata_ttl_hours": 0,
+            "offline": true,
+            "go_proxy": "https://goproxy.example.com",
             "overrides": {
                 "python": {
                     "obscure-lib": {
@@ -167,6 +760,12 @@ mod tests {
 
         let config: Config = serde_json::from_str(json).unwrap();
         assert_eq!(config.cache_limit_gb, 10.0);
+        assert!(config.offline);
+        assert_eq!(
+            config.go_proxy.as_deref(),
+            Some("https://goproxy.example.com")
+        );
+        assert_eq!(config.metadata_ttl(), std::time::Duration::ZERO);
 
         let python_overrides = config.overrides.get("python").unwrap();
         assert_eq!(
@@ -181,10 +780,98 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_registry_url() {
+        let json = r#"{"registry_url": {"node": "https://npm.example.com/"}}"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            config.resolve_registry_url(Ecosystem::Node, None, "https://registry.npmjs.org"),
+            "https://npm.example.com"
+        );
+        assert_eq!(
+            config.resolve_registry_url(
+                Ecosystem::Node,
+                Some("http://127.0.0.1:4873".to_string()),
+                "https://registry.npmjs.org"
+            ),
+            "http://127.0.0.1:4873"
+        );
+        assert_eq!(
+            config.resolve_registry_url(Ecosystem::Python, Some(String::new()), "https://pypi.org"),
+            "https://pypi.org"
+        );
+    }
+
+    #[test]
+    fn test_rewrite_git_url() {
+        let json = r#"{"git_rewrites": {
+            "https://github.com/": "https://git.example.com/github/",
+            "https://github.com/myorg/": "file:///srv/git/myorg/"
+        }}"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            config.rewrite_git_url("https://github.com/psf/requests.git"),
+            "https://git.example.com/github/psf/requests.git"
+        );
+        assert_eq!(
+            config.rewrite_git_url("https://github.com/myorg/lib.git"),
+            "file:///srv/git/myorg/lib.git"
+        );
+        assert_eq!(
+            config.rewrite_git_url("https://gitlab.com/a/b.git"),
+            "https://gitlab.com/a/b.git"
+        );
+    }
+
+    #[test]
+    fn test_preferred_strategy() {
+        let json = r#"{
+            "strategy": {"python": "registry-artifact", "rust": "git"},
+            "overrides": {
+                "node": {"@types/node": {"strategy": "registry-artifact"}},
+                "python": {"django": {"strategy": "git"}}
+            }
+        }"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            config.preferred_strategy(Ecosystem::Python, "requests", None),
+            Strategy::RegistryArtifact
+        );
+        assert_eq!(
+            config.preferred_strategy(Ecosystem::Python, "Django", None),
+            Strategy::Git
+        );
+        assert_eq!(
+            config.preferred_strategy(Ecosystem::Rust, "serde", None),
+            Strategy::Git
+        );
+        assert_eq!(
+            config.preferred_strategy(Ecosystem::Node, "@types/node", None),
+            Strategy::RegistryArtifact
+        );
+        assert_eq!(
+            config.preferred_strategy(Ecosystem::Node, "lodash", None),
+            Strategy::Git
+        );
+
+        let invalid = r#"{"strategy": {"python": "tarball"}}"#;
+        assert!(serde_json::from_str::<Config>(invalid).is_err());
+    }
+
     #[test]
     fn test_cache_limit_bytes() {
         let config = Config {
             cache_limit_gb: 5.0,
+            metadata_ttl_hours: DEFAULT_METADATA_TTL_HOURS,
+            offline: false,
+            go_proxy: None,
+            crate_download_url: None,
+            gem_source: None,
+            registry_url: HashMap::new(),
+            git_rewrites: HashMap::new(),
+            strategy: HashMap::new(),
             overrides: HashMap::new(),
         };
         // 5 GB = 5 * 1024 * 1024 * 1024 = 5368709120 bytes
@@ -207,19 +894,377 @@ mod tests {
 
         // Exact match
         assert_eq!(
-            config.repo_override(Ecosystem::Python, "obscure-lib"),
+            config
+                .repo_override(Ecosystem::Python, "obscure-lib", None)
+                .as_deref(),
             Some("https://github.com/someone/obscure-lib")
         );
 
         // Case insensitive lookup
         assert_eq!(
-            config.repo_override(Ecosystem::Python, "Obscure-Lib"),
+            config
+                .repo_override(Ecosystem::Python, "Obscure-Lib", None)
+                .as_deref(),
             Some("https://github.com/someone/obscure-lib")
         );
 
         // Not found
-        assert_eq!(config.repo_override(Ecosystem::Python, "other-lib"), None);
-        assert_eq!(config.repo_override(Ecosystem::Node, "obscure-lib"), None);
+        assert_eq!(
+            config
+                .repo_override(Ecosystem::Python, "other-lib", None)
+                .as_deref(),
+            None
+        );
+        assert_eq!(
+            config
+                .repo_override(Ecosystem::Node, "obscure-lib", None)
+                .as_deref(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_override_recipe() {
+        let json = r#"{
+            "overrides": {
+                "node": {
+                    "odd-release": {
+                        "tag": "release-{version}",
+                        "subdir": "/packages/core/"
+                    },
+                    "unreleased": {
+                        "ref": "main"
+                    }
+                }
+            }
+        }"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+
+        let mut location = RepoLocation {
+            tag_templates: vec!["odd-release@{version}".to_string()],
+            ..RepoLocation::root("https://github.com/org/monorepo.git")
+        };
+        config
+            .package_override(Ecosystem::Node, "odd-release", None)
+            .unwrap()
+            .apply_to(&mut location);
+        assert_eq!(
+            location.tag_templates,
+            vec!["release-{version}", "odd-release@{version}"]
+        );
+        assert_eq!(location.subdir.as_deref(), Some("packages/core"));
+        assert_eq!(location.git_ref, None);
+
+        let mut location = RepoLocation::root("https://github.com/org/unreleased.git");
+        config
+            .package_override(Ecosystem::Node, "unreleased", None)
+            .unwrap()
+            .apply_to(&mut location);
+        assert_eq!(location.git_ref.as_deref(), Some("main"));
+        assert!(location.tag_templates.is_empty());
+        assert_eq!(location.subdir, None);
+
+        assert!(
+            config
+                .package_override(Ecosystem::Python, "unreleased", None)
+                .is_none()
+        );
+
+        let escaping = r#"{"overrides": {"node": {"lib": {"subdir": "../../.."}}}}"#;
+        let err = serde_json::from_str::<Config>(escaping).unwrap_err();
+        assert!(err.to_string().contains("Invalid subdir"));
+    }
+
+    #[test]
+    fn test_project_config_layered_over_global() {
+        let global = PathBuf::from("/home/me/.config/dotdeps/config.json");
+        let project = PathBuf::from("/work/app/.dotdeps.json");
+        let layers = vec![
+            (
+                ConfigSource::Global(global.clone()),
+                serde_json::json!({
+                    "cache_limit_gb": 10,
+                    "go_proxy": "https://goproxy.example.com",
+                    "overrides": {"python": {"internal-lib": {"repo": "https://github.com/me/fork"}}}
+                }),
+            ),
+            (
+                ConfigSource::Project(project.clone()),
+                serde_json::json!({
+                    "cache_limit_gb": 2,
+                    "overrides": {
+                        "python": {"internal-lib": {"repo": "https://git.corp.example/internal-lib", "tag": "release-{version}"}},
+                        "node": {"@corp/ui": {"subdir": "packages/ui"}}
+                    }
+                }),
+            ),
+        ];
+
+        let config = Config::from_layers(&layers).unwrap();
+        assert_eq!(config.cache_limit_gb, 2.0);
+        assert_eq!(
+            config.go_proxy.as_deref(),
+            Some("https://goproxy.example.com")
+        );
+        assert_eq!(
+            config
+                .repo_override(Ecosystem::Python, "internal-lib", None)
+                .as_deref(),
+            Some("https://git.corp.example/internal-lib")
+        );
+        assert!(
+            config
+                .package_override(Ecosystem::Node, "@corp/ui", None)
+                .is_some()
+        );
+
+        let values = resolve_sources(&config, &layers);
+        let source_of = |key: &str| {
+            values
+                .iter()
+                .find(|v| v.key == key)
+                .map(|v| v.source.clone())
+                .unwrap()
+        };
+        assert_eq!(
+            source_of("cache_limit_gb"),
+            ConfigSource::Project(project.clone())
+        );
+        assert_eq!(source_of("go_proxy"), ConfigSource::Global(global));
+        assert_eq!(
+            source_of("overrides.python.internal-lib.tag"),
+            ConfigSource::Project(project)
+        );
+        assert_eq!(source_of("metadata_ttl_hours"), ConfigSource::Default);
+        assert!(!values.iter().any(|v| v.key == "gem_source"));
+
+        assert_eq!(
+            Config::from_layers(&[]).unwrap().cache_limit_gb,
+            DEFAULT_CACHE_LIMIT_GB
+        );
+    }
+
+    #[test]
+    fn test_find_project_config_stops_at_git_root() {
+        let root = std::env::temp_dir().join("dotdeps_project_config_test");
+        let _ = std::fs::remove_dir_all(&root);
+        let repo = root.join("repo");
+        let nested = repo.join("packages").join("app");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::create_dir_all(repo.join(".git")).unwrap();
+
+        // Above the git root: not the project's
+        std::fs::write(root.join(PROJECT_CONFIG_FILE), "{}").unwrap();
+        assert_eq!(find_project_config(&nested), None);
+
+        std::fs::write(repo.join(PROJECT_CONFIG_FILE), "{}").unwrap();
+        assert_eq!(
+            find_project_config(&nested),
+            Some(repo.join(PROJECT_CONFIG_FILE))
+        );
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_read_layer_names_invalid_file() {
+        let path = std::env::temp_dir().join("dotdeps_invalid_layer.json");
+        std::fs::write(&path, r#"{"cache_limit_gb": "lots"}"#).unwrap();
+
+        let err = read_layer(&path).unwrap_err();
+        assert!(err.to_string().contains("dotdeps_invalid_layer.json"));
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("@acme/*", "@acme/ui"));
+        assert!(glob_matches("acme-*", "acme-"));
+        assert!(glob_matches("*-plugin", "acme-plugin"));
+        assert!(glob_matches("acme-*-plugin", "acme-lint-plugin"));
+        assert!(glob_matches("*", "anything"));
+        assert!(glob_matches("exact", "exact"));
+        assert!(!glob_matches("@acme/*", "@other/ui"));
+        assert!(!glob_matches("acme-*-plugin", "acme-plugin"));
+        assert!(!glob_matches("exact", "exactly"));
+    }
+
+    #[test]
+    fn test_pattern_overrides() {
+        let json = r#"{
+            "overrides": {
+                "node": {
+                    "@acme/*": {
+                        "repo": "https://git.acme.internal/{basename}.git",
+                        "tag": "{basename}-v{version}"
+                    },
+                    "@acme/legacy-*": {
+                        "repo": "https://git.acme.internal/legacy/{scope}-{basename}.git"
+                    },
+                    "@acme/legacy-ui": {
+                        "subdir": "packages/{basename}"
+                    },
+                    "*": {
+                        "strategy": "registry-artifact"
+                    }
+                },
+                "python": {
+                    "acme-*": {
+                        "repo": "https://git.acme.internal/{name}.git"
+                    }
+                }
+            }
+        }"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            config
+                .repo_override(Ecosystem::Python, "Acme-Billing", None)
+                .as_deref(),
+            Some("https://git.acme.internal/Acme-Billing.git")
+        );
+        assert_eq!(
+            config.repo_override(Ecosystem::Python, "requests", None),
+            None
+        );
+
+        let ui = config
+            .package_override(Ecosystem::Node, "@acme/ui", None)
+            .unwrap();
+        assert_eq!(ui.repo.as_deref(), Some("https://git.acme.internal/ui.git"));
+        assert_eq!(ui.tag.as_deref(), Some("ui-v{version}"));
+        assert_eq!(ui.strategy, Some(Strategy::RegistryArtifact));
+
+        // The exact key, then the longer pattern, then the shorter ones
+        let legacy = config
+            .package_override(Ecosystem::Node, "@acme/legacy-ui", None)
+            .unwrap();
+        assert_eq!(
+            legacy.repo.as_deref(),
+            Some("https://git.acme.internal/legacy/acme-legacy-ui.git")
+        );
+        assert_eq!(legacy.subdir.as_deref(), Some("packages/legacy-ui"));
+        assert_eq!(legacy.tag.as_deref(), Some("legacy-ui-v{version}"));
+
+        assert_eq!(
+            config.preferred_strategy(Ecosystem::Node, "lodash", None),
+            Strategy::RegistryArtifact
+        );
+        assert_eq!(config.repo_override(Ecosystem::Node, "lodash", None), None);
+    }
+
+    #[test]
+    fn test_version_scoped_overrides() {
+        let json = r#"{
+            "overrides": {
+                "python": {
+                    "moved-lib": {
+                        "repo": "https://github.com/new-org/moved-lib",
+                        "versions": {
+                            "<2.0": {
+                                "repo": "https://github.com/old-org/moved-lib",
+                                "tag": "release-{version}"
+                            },
+                            ">=1.5, <2.0": {
+                                "subdir": "src"
+                            }
+                        }
+                    }
+                },
+                "ruby": {
+                    "moved-gem": {
+                        "versions": {">=3.1": {"repo": "https://github.com/new-org/moved-gem"}}
+                    }
+                }
+            }
+        }"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+
+        let legacy = config
+            .package_override(Ecosystem::Python, "moved-lib", Some("1.9.2"))
+            .unwrap();
+        assert_eq!(
+            legacy.repo.as_deref(),
+            Some("https://github.com/old-org/moved-lib")
+        );
+        assert_eq!(legacy.tag.as_deref(), Some("release-{version}"));
+        assert_eq!(legacy.subdir.as_deref(), Some("src"));
+
+        // 2.0rc1 is a prerelease of 2.0 and so still below it
+        assert_eq!(
+            config
+                .repo_override(Ecosystem::Python, "moved-lib", Some("2.0rc1"))
+                .as_deref(),
+            Some("https://github.com/old-org/moved-lib")
+        );
+        let current = config
+            .package_override(Ecosystem::Python, "moved-lib", Some("2.0"))
+            .unwrap();
+        assert_eq!(
+            current.repo.as_deref(),
+            Some("https://github.com/new-org/moved-lib")
+        );
+        assert_eq!(current.tag, None);
+        assert_eq!(
+            config
+                .repo_override(Ecosystem::Python, "moved-lib", None)
+                .as_deref(),
+            Some("https://github.com/new-org/moved-lib")
+        );
+
+        assert_eq!(
+            config
+                .repo_override(Ecosystem::Ruby, "moved-gem", Some("3.1.0"))
+                .as_deref(),
+            Some("https://github.com/new-org/moved-gem")
+        );
+        assert_eq!(
+            config.repo_override(Ecosystem::Ruby, "moved-gem", Some("3.1.0.rc1")),
+            None
+        );
+        // The entry exists but nothing in it applies to this version
+        let unscoped = config
+            .package_override(Ecosystem::Ruby, "moved-gem", Some("3.0.0"))
+            .unwrap();
+        assert!(!unscoped.is_set());
+        assert!(legacy.is_set());
+
+        let invalid = r#"{"overrides": {"node": {"lib": {"versions": {"~> 2.0": {}}}}}}"#;
+        let err = serde_json::from_str::<Config>(invalid).unwrap_err();
+        assert!(err.to_string().contains("Invalid version constraint"));
+    }
+
+    #[test]
+    fn test_most_specific_version_override_wins() {
+        // Key order would put "<2.0" and "<3.0" ahead of ">=1.5, <2.0" and "==1.9.0"
+        let json = r#"{
+            "overrides": {
+                "node": {
+                    "lib": {
+                        "versions": {
+                            "<2.0": {"repo": "https://example.com/a", "tag": "a-{version}"},
+                            "<3.0": {"repo": "https://example.com/c"},
+                            ">=1.5, <2.0": {"repo": "https://example.com/b"},
+                            "==1.9.0": {"repo": "https://example.com/d"}
+                        }
+                    }
+                }
+            }
+        }"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        let repo = |version| config.repo_override(Ecosystem::Node, "lib", Some(version));
+
+        assert_eq!(repo("1.9.0").as_deref(), Some("https://example.com/d"));
+        assert_eq!(repo("1.9.2").as_deref(), Some("https://example.com/b"));
+        assert_eq!(repo("1.2.0").as_deref(), Some("https://example.com/a"));
+        assert_eq!(repo("2.5.0").as_deref(), Some("https://example.com/c"));
+
+        // Wider matches still fill in what the narrowest leaves unset
+        let exact = config
+            .package_override(Ecosystem::Node, "lib", Some("1.9.0"))
+            .unwrap();
+        assert_eq!(exact.tag.as_deref(), Some("a-{version}"));
     }
 
     #[test]
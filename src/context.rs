@@ -3,6 +3,7 @@
 //! Collects direct dependencies across ecosystems and formats a prompt-friendly message.
 
 use crate::cli::Ecosystem;
+use crate::state::{State, StateEntry};
 use crate::{go, node, python, ruby, rust, swift};
 
 pub fn render_context() -> Result<Option<String>, Box<dyn std::error::Error>> {
@@ -38,10 +39,11 @@ pub fn render_context() -> Result<Option<String>, Box<dyn std::error::Error>> {
         return Ok(None);
     }
 
-    Ok(Some(format_context(&entries)))
+    let state = State::load_or_default();
+    Ok(Some(format_context(&entries, &state.dependencies)))
 }
 
-fn format_context(entries: &[(Ecosystem, Vec<String>)]) -> String {
+fn format_context(entries: &[(Ecosystem, Vec<String>)], fetched: &[StateEntry]) -> String {
     let mut output = String::new();
     output.push_str("## Dependency Source Code\n\n");
     output.push_str("Libraries in this project may have changed since your training. Before writing code that uses these dependencies, fetch their source to verify API details.\n\n");
@@ -59,6 +61,22 @@ fn format_context(entries: &[(Ecosystem, Vec<String>)]) -> String {
     }
 
     output.push_str("```\n\n");
+
+    if !fetched.is_empty() {
+        output.push_str("**Already fetched:**\n\n");
+        for entry in fetched {
+            output.push_str(&format!(
+                "- `.deps/{}/{}/` ({}@{}",
+                entry.ecosystem, entry.package, entry.package, entry.version
+            ));
+            if let Some(provenance) = entry.provenance() {
+                output.push_str(&format!(", {}", provenance));
+            }
+            output.push_str(")\n");
+        }
+        output.push('\n');
+    }
+
     output.push_str("After fetching, use a sub-agent to explore the source and answer specific questions about the implementation.\n");
     output
 }
@@ -113,10 +131,12 @@ fn collect_swift_deps() -> Result<Option<Vec<String>>, Box<dyn std::error::Error
 
 #[cfg(test)]
 mod tests {
+    use crate::state::StateEntry;
+
     #[test]
     fn test_format_context_output_exact() {
         let entries = vec![(crate::cli::Ecosystem::Python, vec!["requests".to_string()])];
-        let output = super::format_context(&entries);
+        let output = super::format_context(&entries, &[]);
         let expected = concat!(
             "## Dependency Source Code\n\n",
             "Libraries in this project may have changed since your training. Before writing code that uses these dependencies, fetch their source to verify API details.\n\n",
@@ -132,4 +152,26 @@ mod tests {
         );
         assert_eq!(output, expected);
     }
+
+    #[test]
+    fn test_format_context_lists_fetched_dependencies() {
+        let entries = vec![(crate::cli::Ecosystem::Python, vec!["requests".to_string()])];
+        let fetched = vec![StateEntry {
+            ecosystem: "python".to_string(),
+            package: "requests".to_string(),
+            requested_version: None,
+            version: "2.31.0".to_string(),
+            resolved_ref: Some("v2.31.0".to_string()),
+            commit: Some("0e322af87745eff34caffe4df68456ebc20d9068".to_string()),
+            repo_url: None,
+            lockfile: None,
+        }];
+
+        let output = super::format_context(&entries, &fetched);
+        assert!(output.contains(concat!(
+            "**Already fetched:**\n\n",
+            "- `.deps/python/requests/` (requests@2.31.0, v2.31.0, commit 0e322af87745)\n\n",
+            "After fetching"
+        )));
+    }
 }
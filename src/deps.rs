@@ -5,6 +5,8 @@
 
 use crate::cache;
 use crate::cli::Ecosystem;
+use crate::projects;
+use crate::state::{self, State};
 use std::fs;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
@@ -45,6 +47,9 @@ pub enum DepsError {
 
     #[error("Cache path does not exist: {path}. The cache entry may have been evicted.")]
     CacheMissing { path: PathBuf },
+
+    #[error("State error: {0}")]
+    State(#[from] state::StateError),
 }
 
 /// Information about a dependency in .deps/
@@ -57,9 +62,11 @@ pub struct DepEntry {
     #[allow(dead_code)]
     pub path: PathBuf,
     /// Target path the symlink points to (cache location)
-    #[allow(dead_code)]
     pub target: PathBuf,
     pub is_broken: bool,
+    /// How the entry was resolved: from `.deps/.dotdeps-state.json`, or
+    /// derived from the cache entry's `.dotdeps-meta.json`
+    pub resolution: Option<state::StateEntry>,
 }
 
 /// Returns the .deps directory path in the current working directory
@@ -86,9 +93,22 @@ fn is_windows() -> bool {
 
 /// Create a symlink (or copy on Windows) from .deps to cache
 ///
-/// Creates: `.deps/<ecosystem>/<package>` -> `~/.cache/dotdeps/<ecosystem>/<package>/<version>/`
-pub fn link(ecosystem: Ecosystem, package: &str, version: &str) -> Result<PathBuf, DepsError> {
-    let cache_path = cache::package_dir(ecosystem, package, version)?;
+/// Creates: `.deps/<ecosystem>/<package>` -> `cache_entry`, usually
+/// `~/.cache/dotdeps/<ecosystem>/<package>/<version>/` (a replaced package links
+/// to its replacement's entry)
+///
+/// If `subdir` is given (package inside a multi-package repository), the link
+/// points at that directory within the cache entry instead.
+pub fn link(
+    ecosystem: Ecosystem,
+    package: &str,
+    cache_entry: &Path,
+    subdir: Option<&str>,
+) -> Result<PathBuf, DepsError> {
+    let cache_path = match subdir {
+        Some(subdir) => cache_entry.join(subdir),
+        None => cache_entry.to_path_buf(),
+    };
     let link_path = package_path(ecosystem, package);
 
     // Track if .deps/ existed before we create directories
@@ -144,6 +164,12 @@ pub fn link(ecosystem: Ecosystem, package: &str, version: &str) -> Result<PathBu
         })?;
     }
 
+    // Record the project so `dotdeps cache ls` can show who uses the entry.
+    // Ignore errors - the registry is best-effort
+    if let Ok(project_dir) = std::env::current_dir() {
+        let _ = projects::register(&project_dir);
+    }
+
     Ok(link_path)
 }
 
@@ -215,6 +241,11 @@ pub fn remove(ecosystem: Ecosystem, package: &str) -> Result<bool, DepsError> {
         remove_link(&link_path)?;
     }
 
+    let mut state = State::load_or_default();
+    if state.remove(ecosystem, package) {
+        state.save()?;
+    }
+
     // Clean up empty parent directories, but keep .deps/
     let ecosystem_dir = deps_dir().join(ecosystem.to_string());
     if ecosystem_dir.exists()
@@ -256,6 +287,31 @@ pub fn list() -> Result<Vec<DepEntry>, DepsError> {
         collect_packages(&ecosystem_path, &ecosystem_path, ecosystem, &mut entries)?;
     }
 
+    // Prefer recorded provenance over the version guessed from the symlink target
+    let state = State::load_or_default();
+    for entry in &mut entries {
+        if let Some(recorded) = state.get(entry.ecosystem, &entry.package) {
+            entry.version = recorded.version.clone();
+            entry.resolution = Some(recorded.clone());
+        } else if let Some(meta) =
+            cache::package_dir(entry.ecosystem, &entry.package, &entry.version)
+                .ok()
+                .and_then(|dir| cache::read_meta(&dir))
+                .or_else(|| cache::read_meta(&entry.target))
+        {
+            entry.resolution = Some(state::StateEntry {
+                ecosystem: entry.ecosystem.to_string(),
+                package: entry.package.clone(),
+                requested_version: None,
+                version: entry.version.clone(),
+                resolved_ref: Some(meta.resolved_ref),
+                commit: meta.commit,
+                repo_url: Some(meta.repo_url),
+                lockfile: None,
+            });
+        }
+    }
+
     Ok(entries)
 }
 
@@ -286,7 +342,7 @@ fn collect_packages(
             let (target, is_broken, version) = match fs::read_link(&path) {
                 Ok(target) => {
                     let is_broken = !target.exists();
-                    let version = extract_version_from_path(&target);
+                    let version = extract_version_from_path(&target, ecosystem, &package);
                     (target, is_broken, version)
                 }
                 Err(_) => (PathBuf::new(), true, "unknown".to_string()),
@@ -299,11 +355,12 @@ fn collect_packages(
                 path: path.clone(),
                 target,
                 is_broken,
+                resolution: None,
             });
         } else if metadata.is_dir() {
             // Could be a nested directory (e.g., @org in @org/pkg) or a copied directory on Windows
-            // Check if it looks like a cache directory (has .git)
-            if path.join(".git").is_dir() {
+            // Check if it looks like a cache directory
+            if cache::is_entry(&path) {
                 // This is a copied directory (Windows fallback)
                 let package = path
                     .strip_prefix(base)
@@ -318,6 +375,7 @@ fn collect_packages(
                     path: path.clone(),
                     target: path.clone(),
                     is_broken: false,
+                    resolution: None,
                 });
             } else {
                 // Recurse into nested directories
@@ -331,7 +389,24 @@ fn collect_packages(
 
 /// Extract version from cache path
 /// e.g., `/home/user/.cache/dotdeps/python/requests/2.31.0` -> `2.31.0`
-fn extract_version_from_path(path: &Path) -> String {
+///
+/// Links into a package subdirectory
+/// (`.../go/github.com/aws/aws-sdk-go-v2/service/s3/1.40.0/service/s3`) use the
+/// path component after the package instead of the last one.
+fn extract_version_from_path(path: &Path, ecosystem: Ecosystem, package: &str) -> String {
+    // Symlinks store the canonicalized cache path, so try both forms of the base
+    let bases = cache::base_dir()
+        .map(|base| vec![base.canonicalize().unwrap_or_else(|_| base.clone()), base])
+        .unwrap_or_default();
+    for base in bases {
+        let package_root = base.join(ecosystem.to_string()).join(package);
+        if let Ok(rest) = path.strip_prefix(&package_root)
+            && let Some(version) = rest.components().next()
+        {
+            return version.as_os_str().to_string_lossy().to_string();
+        }
+    }
+
     path.file_name()
         .and_then(|n| n.to_str())
         .unwrap_or("unknown")
@@ -374,7 +449,10 @@ mod tests {
     #[test]
     fn test_extract_version_from_path() {
         let path = PathBuf::from("/home/user/.cache/dotdeps/python/requests/2.31.0");
-        assert_eq!(extract_version_from_path(&path), "2.31.0");
+        assert_eq!(
+            extract_version_from_path(&path, Ecosystem::Python, "requests"),
+            "2.31.0"
+        );
     }
 
     #[test]
@@ -382,4 +460,28 @@ mod tests {
         // This test just ensures the function compiles and runs
         let _ = is_windows();
     }
+
+    #[test]
+    fn test_extract_version_from_subdir_link() {
+        let base = cache::base_dir().unwrap();
+        let module = "github.com/aws/aws-sdk-go-v2/service/s3";
+
+        let subdir_target = base.join("go").join(module).join("1.40.0/service/s3");
+        assert_eq!(
+            extract_version_from_path(&subdir_target, Ecosystem::Go, module),
+            "1.40.0"
+        );
+
+        let root_target = base.join("python/requests/2.31.0");
+        assert_eq!(
+            extract_version_from_path(&root_target, Ecosystem::Python, "requests"),
+            "2.31.0"
+        );
+
+        // Outside the cache: fall back to the last path component
+        assert_eq!(
+            extract_version_from_path(Path::new("/elsewhere/1.0.0"), Ecosystem::Python, "x"),
+            "1.0.0"
+        );
+    }
 }
@@ -1,11 +1,18 @@
 //! Git operations for cloning repositories
 //!
-//! Handles shallow cloning with tag resolution:
-//! 1. Try `v{version}` tag first
-//! 2. Fall back to `{version}` tag
-//! 3. Try `{package}-{version}` for monorepo crates
-//! 4. Try `{package}-v{version}` for monorepo crates
-//! 5. Fall back to default branch (with warning)
+//! Handles shallow cloning with tag resolution. The remote's tags are listed
+//! once (`git ls-remote --tags`) and matched, ignoring case, against:
+//! 1. Package-specific tag templates (e.g., `service/s3/v1.40.0`, `@babel/core@7.24.0`)
+//! 2. `v{version}`, then `{version}`
+//! 3. `{package}-{version}` and `{package}-v{version}` for monorepo crates
+//! 4. `release-{version}` and `release-v{version}`
+//! 5. `{package}@{version}` and `{package}/v{version}` for monorepo packages
+//!
+//! Packages tagged with their subdirectory as prefix (nested Go modules) only
+//! match their own templates.
+//!
+//! Only the matching tag is cloned. Without a match the default branch is
+//! cloned (with a warning naming the nearest existing tags).
 
 use std::path::Path;
 use std::process::Command;
@@ -18,6 +25,52 @@ pub enum GitError {
 
     #[error("Failed to execute git: {source}")]
     Exec { source: std::io::Error },
+
+    #[error(transparent)]
+    Offline(#[from] crate::network::NetworkError),
+}
+
+/// Where a package's source lives
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoLocation {
+    /// Repository URL to clone
+    pub url: String,
+    /// Directory within the repository that holds the package, if not the root
+    pub subdir: Option<String>,
+    /// Release tag patterns tried before the generic ones, with `{version}` as
+    /// placeholder (e.g., `service/s3/v{version}` or `@babel/core@{version}`)
+    pub tag_templates: Vec<String>,
+    /// Branch, tag or commit checked out instead of the version's release tag
+    pub git_ref: Option<String>,
+}
+
+impl RepoLocation {
+    /// A package at the root of a repository
+    pub fn root(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            subdir: None,
+            tag_templates: Vec::new(),
+            git_ref: None,
+        }
+    }
+}
+
+/// Normalize a package directory within a repository (e.g. `./packages/core/`)
+///
+/// Returns None if it is empty or has a component other than a plain name
+/// (`..`, an absolute path), so that it can't point outside the checkout.
+pub fn normalize_subdir(dir: &str) -> Option<String> {
+    let dir = dir.trim_start_matches("./").trim_matches('/');
+    let path = Path::new(dir);
+    if dir.is_empty()
+        || path
+            .components()
+            .any(|c| !matches!(c, std::path::Component::Normal(_)))
+    {
+        return None;
+    }
+    Some(dir.to_string())
 }
 
 /// Result of a clone operation
@@ -26,42 +79,232 @@ pub struct CloneResult {
     pub used_default_branch: bool,
     /// The ref that was actually cloned (tag name or "default branch")
     pub cloned_ref: String,
+    /// Existing tags closest to the requested version, when none matched it
+    pub nearest_tags: Vec<String>,
+    /// Package subdirectory the location named but the checkout doesn't have
+    /// (the repository root is linked instead)
+    pub missing_subdir: Option<String>,
 }
 
-/// Clone a repository to the specified directory with shallow clone
+/// Clone the repository at `location` to the specified directory with shallow clone
 ///
-/// Tries tags in order:
-/// 1. `v{version}` (e.g., `v2.31.0`)
-/// 2. `{version}` (e.g., `2.31.0`)
-/// 3. `{package}-{version}` (e.g., `tokio-1.0.0`) - for monorepo crates
-/// 4. `{package}-v{version}` (e.g., `tokio-v1.0.0`) - for monorepo crates
-/// 5. Default branch (warns user)
+/// Checks out `location.git_ref` if set. Otherwise clones the remote tag
+/// matching the first of the candidates (see `build_tag_candidates`), or the
+/// default branch if none exists; the result then lists the nearest existing tags.
 ///
 /// On failure, cleans up any partial clone.
 pub fn clone(
-    repo_url: &str,
+    location: &RepoLocation,
     version: &str,
     package: &str,
     dest: &Path,
 ) -> Result<CloneResult, GitError> {
-    // Ensure parent directory exists
+    let repo_url = &location.url;
+    crate::network::ensure_online(repo_url)?;
+
+    if let Some(git_ref) = &location.git_ref {
+        return clone_ref(repo_url, git_ref, dest);
+    }
+
+    let remote_tags = list_remote_tags(repo_url)?;
+    let candidates = build_tag_candidates(
+        version,
+        package,
+        &location.tag_templates,
+        location.subdir.as_deref(),
+    );
+    match match_tag(&candidates, &remote_tags) {
+        Some(tag) => clone_at_tag(repo_url, tag, dest),
+        None => {
+            let mut result = clone_default_branch(repo_url, dest)?;
+            result.nearest_tags = nearest_tags(version, &remote_tags, NEAREST_TAGS_SHOWN);
+            Ok(result)
+        }
+    }
+}
+
+/// Clone the repository at `location` at the release tag of `version`, without falling back
+///
+/// Looks for the same tags as `clone` (or checks out `location.git_ref`).
+/// Returns None (leaving nothing at `dest`) if none of them exists or the tags
+/// can't be listed.
+pub fn clone_tag(
+    location: &RepoLocation,
+    version: &str,
+    package: &str,
+    dest: &Path,
+) -> Result<Option<CloneResult>, GitError> {
+    let repo_url = &location.url;
+    crate::network::ensure_online(repo_url)?;
+
+    if let Some(git_ref) = &location.git_ref {
+        return clone_ref(repo_url, git_ref, dest).map(Some);
+    }
+
+    let Ok(remote_tags) = list_remote_tags(repo_url) else {
+        return Ok(None);
+    };
+    let candidates = build_tag_candidates(
+        version,
+        package,
+        &location.tag_templates,
+        location.subdir.as_deref(),
+    );
+    match match_tag(&candidates, &remote_tags) {
+        Some(tag) => clone_at_tag(repo_url, tag, dest).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Clone a repository at a branch, tag or commit
+///
+/// Branches and tags are shallow-cloned. A ref that looks like a commit SHA
+/// and isn't also a branch or tag is checked out with `clone_at_commit`; for
+/// anything else the clone error is returned as is (a misspelled ref or a
+/// network failure shouldn't start a clone of the whole history).
+pub fn clone_ref(repo_url: &str, git_ref: &str, dest: &Path) -> Result<CloneResult, GitError> {
+    match clone_at_tag(repo_url, git_ref, dest) {
+        Ok(result) => Ok(result),
+        Err(_) if looks_like_commit(git_ref) => {
+            clone_at_commit(repo_url, git_ref, dest).map(|mut result| {
+                // Checking out HEAD is the default branch, but was asked for
+                result.used_default_branch = false;
+                result
+            })
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Whether a ref is `HEAD` or an abbreviated or full commit SHA (7-40 hex digits)
+fn looks_like_commit(git_ref: &str) -> bool {
+    git_ref == "HEAD"
+        || ((7..=40).contains(&git_ref.len()) && git_ref.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// How many of the nearest tags are reported when no tag matches
+const NEAREST_TAGS_SHOWN: usize = 3;
+
+/// List the tag names of a remote repository (`git ls-remote --tags`)
+pub fn list_remote_tags(repo_url: &str) -> Result<Vec<String>, GitError> {
+    crate::network::ensure_online(repo_url)?;
+
+    let output = Command::new("git")
+        .args(["ls-remote", "--tags", "--refs", repo_url])
+        .output()
+        .map_err(|source| GitError::Exec { source })?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError::CommandFailed {
+            message: stderr.trim().to_string(),
+        });
+    }
+
+    Ok(parse_ls_remote_tags(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Tag names from `git ls-remote --tags --refs` output (`<sha>\trefs/tags/<name>`)
+fn parse_ls_remote_tags(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .filter_map(|(_, reference)| reference.strip_prefix("refs/tags/"))
+        .map(str::to_string)
+        .collect()
+}
+
+/// The remote tag matching the first candidate that has one
+///
+/// An exact match is preferred over one that differs in case (`V1.0.0`).
+fn match_tag<'a>(candidates: &[String], remote_tags: &'a [String]) -> Option<&'a str> {
+    candidates.iter().find_map(|candidate| {
+        remote_tags
+            .iter()
+            .find(|tag| *tag == candidate)
+            .or_else(|| {
+                remote_tags
+                    .iter()
+                    .find(|tag| tag.eq_ignore_ascii_case(candidate))
+            })
+            .map(String::as_str)
+    })
+}
+
+/// Up to `limit` tags whose version is closest to `version`
+///
+/// Tags sharing more leading version components come first, then those whose
+/// first differing component is closest (`1.4.0` is nearer `1.5.0` than `1.9.0`).
+fn nearest_tags(version: &str, remote_tags: &[String], limit: usize) -> Vec<String> {
+    let wanted = version_numbers(version);
+    let mut ranked: Vec<(usize, u64, &String)> = remote_tags
+        .iter()
+        .filter_map(|tag| {
+            let numbers = version_numbers(tag);
+            if numbers.is_empty() {
+                return None;
+            }
+            let shared = wanted
+                .iter()
+                .zip(&numbers)
+                .take_while(|(a, b)| a == b)
+                .count();
+            let distance = match (wanted.get(shared), numbers.get(shared)) {
+                (Some(a), Some(b)) => a.abs_diff(*b),
+                _ => 0,
+            };
+            Some((shared, distance, tag))
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)).then(a.2.cmp(b.2)));
+    ranked
+        .into_iter()
+        .take(limit)
+        .map(|(_, _, tag)| tag.clone())
+        .collect()
+}
+
+/// Leading dot-separated numbers of a version or tag (`v1.2.3-rc.1` -> `[1, 2, 3]`)
+fn version_numbers(s: &str) -> Vec<u64> {
+    let Some(start) = s.find(|c: char| c.is_ascii_digit()) else {
+        return Vec::new();
+    };
+    let mut numbers = Vec::new();
+    for part in s[start..].split('.') {
+        let digits = part.bytes().take_while(u8::is_ascii_digit).count();
+        match part[..digits].parse() {
+            Ok(number) => numbers.push(number),
+            Err(_) => break,
+        }
+        // Stop after a component with a suffix (`3-rc` in `1.2.3-rc.1`)
+        if digits < part.len() {
+            break;
+        }
+    }
+    numbers
+}
+
+/// Shallow-clone a tag (or branch) that is known to exist
+///
+/// Ensures the parent directory exists; on failure, cleans up any partial clone.
+fn clone_at_tag(repo_url: &str, tag: &str, dest: &Path) -> Result<CloneResult, GitError> {
     if let Some(parent) = dest.parent() {
         std::fs::create_dir_all(parent).map_err(|e| GitError::CommandFailed {
             message: format!("Failed to create directory {}: {}", parent.display(), e),
         })?;
     }
 
-    // Build list of tags to try
-    let tags_to_try = build_tag_candidates(version, package);
+    try_clone_at_ref(repo_url, tag, dest).inspect_err(|_| cleanup_partial_clone(dest))
+}
 
-    // Try each tag in order
-    for tag in &tags_to_try {
-        if let Ok(result) = try_clone_at_ref(repo_url, tag, dest) {
-            return Ok(result);
-        }
-    }
+/// Clone the default branch, marking the result as a fallback
+///
+/// On failure, cleans up any partial clone.
+pub fn clone_default_branch(repo_url: &str, dest: &Path) -> Result<CloneResult, GitError> {
+    crate::network::ensure_online(repo_url)?;
 
-    // Fall back to default branch
     match try_clone_default_branch(repo_url, dest) {
         Ok(mut result) => {
             result.used_default_branch = true;
@@ -78,24 +321,59 @@ pub fn clone(
 
 /// Build the list of tag candidates to try for a given version and package
 ///
-/// Returns tags in priority order:
-/// 1. `v{version}` - most common format
-/// 2. `{version}` - used by some projects
-/// 3. `{package}-{version}` - monorepo format (e.g., tokio-1.0.0)
-/// 4. `{package}-v{version}` - monorepo format with v prefix
-fn build_tag_candidates(version: &str, package: &str) -> Vec<String> {
+/// Returns tags in priority order, without duplicates:
+/// 1. `tag_templates` - package-specific patterns (e.g., service/s3/v1.40.0, @babel/core@7.24.0)
+/// 2. `v{version}` - most common format
+/// 3. `{version}` - used by some projects
+/// 4. `{package}-{version}` - monorepo format (e.g., tokio-1.0.0)
+/// 5. `{package}-v{version}` - monorepo format with v prefix
+/// 6. `release-{version}` and `release-v{version}`
+/// 7. `{package}@{version}` - changesets/lerna format, full and base name
+/// 8. `{package}/v{version}` - monorepo format with a directory-style prefix
+///
+/// If a template is prefixed with `subdir` (nested Go modules tagged
+/// `service/s3/v1.40.0`), only the templates are returned: the generic tags
+/// belong to the module at the repository root.
+fn build_tag_candidates(
+    version: &str,
+    package: &str,
+    tag_templates: &[String],
+    subdir: Option<&str>,
+) -> Vec<String> {
     // Extract the base package name (last component of path-like names)
     // e.g., "github.com/org/repo" -> "repo"
     // e.g., "@scope/pkg" -> "pkg"
     // e.g., "simple-name" -> "simple-name"
     let base_name = extract_base_package_name(package);
 
-    vec![
+    let mut tags: Vec<String> = tag_templates
+        .iter()
+        .map(|template| template.replace("{version}", version))
+        .collect();
+    let subdir_tagged = subdir.is_some_and(|subdir| {
+        let prefix = format!("{}/", subdir);
+        tag_templates
+            .iter()
+            .any(|template| template.starts_with(&prefix))
+    });
+    if subdir_tagged {
+        return tags;
+    }
+    tags.extend([
         format!("v{}", version),
         version.to_string(),
         format!("{}-{}", base_name, version),
         format!("{}-v{}", base_name, version),
-    ]
+        format!("release-{}", version),
+        format!("release-v{}", version),
+        format!("{}@{}", package, version),
+        format!("{}@{}", base_name, version),
+        format!("{}/v{}", base_name, version),
+    ]);
+
+    let mut seen = std::collections::HashSet::new();
+    tags.retain(|tag| seen.insert(tag.clone()));
+    tags
 }
 
 /// Extract the base package name for monorepo tag patterns
@@ -135,6 +413,8 @@ fn try_clone_at_ref(repo_url: &str, git_ref: &str, dest: &Path) -> Result<CloneR
         Ok(CloneResult {
             used_default_branch: false,
             cloned_ref: git_ref.to_string(),
+            nearest_tags: Vec::new(),
+            missing_subdir: None,
         })
     } else {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -159,6 +439,8 @@ fn try_clone_default_branch(repo_url: &str, dest: &Path) -> Result<CloneResult,
         Ok(CloneResult {
             used_default_branch: true,
             cloned_ref: "default branch".to_string(),
+            nearest_tags: Vec::new(),
+            missing_subdir: None,
         })
     } else {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -175,6 +457,8 @@ fn try_clone_default_branch(repo_url: &str, dest: &Path) -> Result<CloneResult,
 /// blobs on-demand. This is much faster than a full clone for large repos while
 /// still allowing checkout of any commit.
 pub fn clone_at_commit(repo_url: &str, commit: &str, dest: &Path) -> Result<CloneResult, GitError> {
+    crate::network::ensure_online(repo_url)?;
+
     // Ensure parent directory exists
     if let Some(parent) = dest.parent() {
         std::fs::create_dir_all(parent).map_err(|e| GitError::CommandFailed {
@@ -204,6 +488,8 @@ pub fn clone_at_commit(repo_url: &str, commit: &str, dest: &Path) -> Result<Clon
         return Ok(CloneResult {
             used_default_branch: true,
             cloned_ref: commit.to_string(),
+            nearest_tags: Vec::new(),
+            missing_subdir: None,
         });
     }
 
@@ -243,9 +529,40 @@ pub fn clone_at_commit(repo_url: &str, commit: &str, dest: &Path) -> Result<Clon
     Ok(CloneResult {
         used_default_branch: false,
         cloned_ref: commit.to_string(),
+        nearest_tags: Vec::new(),
+        missing_subdir: None,
     })
 }
 
+/// Get the full commit SHA checked out in a repository (`git rev-parse HEAD`)
+pub fn head_commit(repo_dir: &Path) -> Result<String, GitError> {
+    run_git_query(repo_dir, &["rev-parse", "HEAD"])
+}
+
+/// Get the URL of the `origin` remote of a repository
+pub fn remote_url(repo_dir: &Path) -> Result<String, GitError> {
+    run_git_query(repo_dir, &["remote", "get-url", "origin"])
+}
+
+/// Run a read-only git command in a repository and return its trimmed stdout
+fn run_git_query(repo_dir: &Path, args: &[&str]) -> Result<String, GitError> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo_dir)
+        .args(args)
+        .output()
+        .map_err(|source| GitError::Exec { source })?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(GitError::CommandFailed {
+            message: stderr.trim().to_string(),
+        })
+    }
+}
+
 /// Remove a partial clone directory if it exists
 fn cleanup_partial_clone(dest: &Path) {
     if dest.exists() {
@@ -265,23 +582,244 @@ mod tests {
 
     #[test]
     fn test_build_tag_candidates_simple_name() {
-        let tags = build_tag_candidates("1.0.0", "serde");
-        assert_eq!(tags, vec!["v1.0.0", "1.0.0", "serde-1.0.0", "serde-v1.0.0"]);
+        let tags = build_tag_candidates("1.0.0", "serde", &[], None);
+        assert_eq!(
+            tags,
+            vec![
+                "v1.0.0",
+                "1.0.0",
+                "serde-1.0.0",
+                "serde-v1.0.0",
+                "release-1.0.0",
+                "release-v1.0.0",
+                "serde@1.0.0",
+                "serde/v1.0.0",
+            ]
+        );
     }
 
     #[test]
     fn test_build_tag_candidates_scoped_npm() {
-        let tags = build_tag_candidates("4.17.21", "@types/node");
+        let tags = build_tag_candidates("4.17.21", "@types/node", &[], None);
         assert_eq!(
             tags,
-            vec!["v4.17.21", "4.17.21", "node-4.17.21", "node-v4.17.21"]
+            vec![
+                "v4.17.21",
+                "4.17.21",
+                "node-4.17.21",
+                "node-v4.17.21",
+                "release-4.17.21",
+                "release-v4.17.21",
+                "@types/node@4.17.21",
+                "node@4.17.21",
+                "node/v4.17.21",
+            ]
         );
     }
 
     #[test]
     fn test_build_tag_candidates_go_module() {
-        let tags = build_tag_candidates("1.9.1", "github.com/gin-gonic/gin");
-        assert_eq!(tags, vec!["v1.9.1", "1.9.1", "gin-1.9.1", "gin-v1.9.1"]);
+        let tags = build_tag_candidates("1.9.1", "github.com/gin-gonic/gin", &[], None);
+        assert_eq!(tags[..4], ["v1.9.1", "1.9.1", "gin-1.9.1", "gin-v1.9.1"]);
+    }
+
+    #[test]
+    fn test_build_tag_candidates_tag_templates() {
+        let tags = build_tag_candidates(
+            "1.40.0",
+            "github.com/aws/aws-sdk-go-v2/service/s3",
+            &["service/s3/v{version}".to_string()],
+            None,
+        );
+        assert_eq!(tags[0], "service/s3/v1.40.0");
+        assert_eq!(tags[1], "v1.40.0");
+
+        // Nested module: the root module's tags would check out the wrong code
+        let tags = build_tag_candidates(
+            "1.40.0",
+            "github.com/aws/aws-sdk-go-v2/service/s3",
+            &["service/s3/v{version}".to_string()],
+            Some("service/s3"),
+        );
+        assert_eq!(tags, ["service/s3/v1.40.0"]);
+
+        let tags = build_tag_candidates(
+            "7.24.0",
+            "@babel/core",
+            &["@babel/core@{version}".to_string()],
+            Some("packages/babel-core"),
+        );
+        assert_eq!(tags[0], "@babel/core@7.24.0");
+        assert_eq!(
+            tags.iter().filter(|t| *t == "@babel/core@7.24.0").count(),
+            1
+        );
+        // Repository-wide tags still apply to monorepo packages
+        assert!(tags.contains(&"v7.24.0".to_string()));
+    }
+
+    #[test]
+    fn test_normalize_subdir() {
+        assert_eq!(
+            normalize_subdir("./packages/core/").as_deref(),
+            Some("packages/core")
+        );
+        assert_eq!(normalize_subdir("/src").as_deref(), Some("src"));
+        assert_eq!(normalize_subdir(""), None);
+        assert_eq!(normalize_subdir("./"), None);
+        assert_eq!(normalize_subdir("../../.."), None);
+        assert_eq!(normalize_subdir("packages/../../x"), None);
+    }
+
+    #[test]
+    fn test_parse_ls_remote_tags() {
+        let output = "3f2a1b\trefs/tags/v1.0.0\n\
+                      9c8d7e\trefs/tags/@babel/core@7.24.0\n\
+                      0a1b2c\trefs/heads/main\n";
+        assert_eq!(
+            parse_ls_remote_tags(output),
+            vec!["v1.0.0", "@babel/core@7.24.0"]
+        );
+    }
+
+    #[test]
+    fn test_match_tag() {
+        let remote: Vec<String> = ["1.0.0", "V2.0.0", "v2.0.0", "Release-3.0.0"]
+            .iter()
+            .map(|t| t.to_string())
+            .collect();
+
+        // Candidate priority wins over the order of the remote's tags
+        let candidates = build_tag_candidates("1.0.0", "serde", &[], None);
+        assert_eq!(match_tag(&candidates, &remote), Some("1.0.0"));
+
+        // Exact case preferred, other cases accepted
+        let candidates = build_tag_candidates("2.0.0", "serde", &[], None);
+        assert_eq!(match_tag(&candidates, &remote), Some("v2.0.0"));
+        let candidates = build_tag_candidates("3.0.0", "serde", &[], None);
+        assert_eq!(match_tag(&candidates, &remote), Some("Release-3.0.0"));
+
+        let candidates = build_tag_candidates("4.0.0", "serde", &[], None);
+        assert_eq!(match_tag(&candidates, &remote), None);
+    }
+
+    #[test]
+    fn test_version_numbers() {
+        assert_eq!(version_numbers("v1.2.3"), vec![1, 2, 3]);
+        assert_eq!(version_numbers("serde-1.0.197"), vec![1, 0, 197]);
+        assert_eq!(version_numbers("1.2.3-rc.1"), vec![1, 2, 3]);
+        assert_eq!(version_numbers("release-2024.01"), vec![2024, 1]);
+        assert!(version_numbers("nightly").is_empty());
+    }
+
+    #[test]
+    fn test_nearest_tags() {
+        let remote: Vec<String> = ["v1.4.0", "v1.5.1", "v1.9.0", "v2.0.0", "v1.5.3", "nightly"]
+            .iter()
+            .map(|t| t.to_string())
+            .collect();
+        assert_eq!(
+            nearest_tags("1.5.2", &remote, 3),
+            vec!["v1.5.1", "v1.5.3", "v1.4.0"]
+        );
+        assert_eq!(nearest_tags("3.0.0", &remote, 1), vec!["v2.0.0"]);
+        assert!(nearest_tags("1.0.0", &[], 3).is_empty());
+    }
+
+    #[test]
+    fn test_clone_resolves_tag_from_listing() {
+        let root = std::env::temp_dir().join("dotdeps_git_clone_tag_test");
+        cleanup_partial_clone(&root);
+        let repo = root.join("repo");
+        std::fs::create_dir_all(&repo).unwrap();
+
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .arg("-C")
+                .arg(&repo)
+                .args([
+                    "-c",
+                    "user.name=dotdeps",
+                    "-c",
+                    "user.email=dotdeps@example.com",
+                ])
+                .args(args)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "git {:?} failed", args);
+        };
+        git(&["init", "-q"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "initial"]);
+        git(&["tag", "Release-1.2.0"]);
+        git(&["tag", "v1.3.0"]);
+        let location = RepoLocation::root(&format!("file://{}", repo.display()));
+
+        let result = clone(&location, "1.2.0", "leftpad", &root.join("tagged")).unwrap();
+        assert_eq!(result.cloned_ref, "Release-1.2.0");
+        assert!(!result.used_default_branch);
+
+        let result = clone(&location, "1.2.5", "leftpad", &root.join("untagged")).unwrap();
+        assert!(result.used_default_branch);
+        assert_eq!(result.nearest_tags, vec!["Release-1.2.0", "v1.3.0"]);
+
+        assert!(
+            clone_tag(&location, "1.2.5", "leftpad", &root.join("none"))
+                .unwrap()
+                .is_none()
+        );
+        assert!(!root.join("none").exists());
+
+        // A configured ref or tag template wins over the generic tags
+        let pinned = RepoLocation {
+            git_ref: Some("v1.3.0".to_string()),
+            ..location.clone()
+        };
+        let result = clone(&pinned, "1.2.0", "leftpad", &root.join("pinned")).unwrap();
+        assert_eq!(result.cloned_ref, "v1.3.0");
+        assert!(!result.used_default_branch);
+
+        // A misspelled ref fails with the clone error instead of trying it as a commit
+        let misspelled = RepoLocation {
+            git_ref: Some("v1.3.O".to_string()),
+            ..location.clone()
+        };
+        assert!(matches!(
+            clone(&misspelled, "1.3.0", "leftpad", &root.join("misspelled")),
+            Err(GitError::CommandFailed { .. })
+        ));
+        assert!(!root.join("misspelled").exists());
+
+        let commit = head_commit(&repo).unwrap();
+        let by_commit = RepoLocation {
+            git_ref: Some(commit[..12].to_string()),
+            ..location.clone()
+        };
+        let result = clone(&by_commit, "1.3.0", "leftpad", &root.join("by-commit")).unwrap();
+        assert_eq!(result.cloned_ref, commit[..12]);
+        assert_eq!(head_commit(&root.join("by-commit")).unwrap(), commit);
+        assert!(!result.used_default_branch);
+
+        let templated = RepoLocation {
+            tag_templates: vec!["v{version}".to_string()],
+            ..location.clone()
+        };
+        let result = clone(&templated, "1.3.0", "leftpad", &root.join("templated")).unwrap();
+        assert_eq!(result.cloned_ref, "v1.3.0");
+
+        cleanup_partial_clone(&root);
+    }
+
+    #[test]
+    fn test_looks_like_commit() {
+        assert!(looks_like_commit("0e322af"));
+        assert!(looks_like_commit(
+            "0e322af87745eff34caffe4df68456ebc20d9068"
+        ));
+        assert!(looks_like_commit("HEAD"));
+        assert!(!looks_like_commit("main"));
+        assert!(!looks_like_commit("v1.2.0"));
+        assert!(!looks_like_commit("abc12"));
+        assert!(!looks_like_commit(&"a".repeat(41)));
     }
 
     #[test]
@@ -301,4 +839,50 @@ mod tests {
         assert_eq!(extract_base_package_name("github.com/gin-gonic/gin"), "gin");
         assert_eq!(extract_base_package_name("golang.org/x/sync"), "sync");
     }
+
+    #[test]
+    fn test_head_commit_and_remote_url() {
+        let dir = std::env::temp_dir().join("dotdeps_git_query_test");
+        cleanup_partial_clone(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .arg("-C")
+                .arg(&dir)
+                .args(args)
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success(), "git {:?} failed", args);
+        };
+        git(&["init", "-q"]);
+        git(&[
+            "-c",
+            "user.name=dotdeps",
+            "-c",
+            "user.email=dotdeps@example.com",
+            "commit",
+            "-q",
+            "--allow-empty",
+            "-m",
+            "initial",
+        ]);
+        git(&[
+            "remote",
+            "add",
+            "origin",
+            "https://github.com/psf/requests.git",
+        ]);
+
+        let commit = head_commit(&dir).unwrap();
+        assert_eq!(commit.len(), 40);
+        assert!(commit.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(
+            remote_url(&dir).unwrap(),
+            "https://github.com/psf/requests.git"
+        );
+
+        cleanup_partial_clone(&dir);
+    }
 }
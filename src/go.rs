@@ -1,9 +1,24 @@
 //! Go ecosystem support
 //!
 //! Handles:
-//! - Lockfile parsing: go.sum (and go.mod for require statements)
-//! - Repository URL detection is handled in main.rs since Go module paths are URLs
+//! - Lockfile parsing: go.sum (and go.mod for require statements), go.work workspaces
+//! - Pseudo-versions, which pin an exact commit
+//! - Repository detection from module paths, including nested modules of multi-module repositories
+//! - Vanity import paths (`go-import` meta tags, gopkg.in)
+//! - Module zips from a GOPROXY server, for modules without a git repository
+//! - Modules already extracted into the module cache (GOMODCACHE)
 
+mod discovery;
+mod installed;
 mod lockfile;
+mod module;
+mod proxy;
+mod version;
+mod workspace;
 
+pub use discovery::{discover, locate_gopkg_in};
+pub use installed::find_installed;
 pub use lockfile::{LockfileError, find_lockfile_path, find_version, list_direct_dependencies};
+pub use module::locate_known_host;
+pub use proxy::{fetch_module, proxy_url};
+pub use version::version_info;
@@ -0,0 +1,251 @@
+//! Go vanity import path resolution
+//!
+//! Modules outside well-known hosts declare their repository with a
+//! `go-import` meta tag served at `https://<module>?go-get=1`:
+//!
+//! ```html
+//! <meta name="go-import" content="go.uber.org/zap git https://github.com/uber-go/zap">
+//! ```
+//!
+//! gopkg.in paths follow fixed rules instead:
+//! - `gopkg.in/yaml.v3` -> `https://github.com/go-yaml/yaml.git`
+//! - `gopkg.in/user/pkg.v1` -> `https://github.com/user/pkg.git`
+
+use crate::git::RepoLocation;
+use thiserror::Error;
+
+use super::module::locate_in_repo;
+
+#[derive(Error, Debug)]
+pub enum DiscoveryError {
+    #[error("Failed to fetch {url}: {message}")]
+    Fetch { url: String, message: String },
+
+    #[error("No go-import meta tag for {module} at {url}")]
+    NotFound { module: String, url: String },
+}
+
+/// A `go-import` declaration: `<import-prefix> <vcs> <repo-root>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoImport {
+    /// Import path of the repository root
+    pub prefix: String,
+    /// Version control system ("git", "hg", "mod", ...)
+    pub vcs: String,
+    /// Repository URL (or module proxy URL for "mod")
+    pub repo_root: String,
+}
+
+impl GoImport {
+    /// Location of `module` inside this git repository
+    pub fn location(&self, module: &str) -> RepoLocation {
+        locate_in_repo(&self.repo_root, &self.prefix, module)
+    }
+}
+
+/// Resolve a gopkg.in module path to its GitHub repository
+pub fn locate_gopkg_in(module: &str) -> Option<RepoLocation> {
+    let rest = module.strip_prefix("gopkg.in/")?;
+    let parts: Vec<&str> = rest.split('/').collect();
+
+    // gopkg.in/pkg.vN -> github.com/go-pkg/pkg
+    if let Some(pkg) = strip_gopkg_version(parts[0]) {
+        let url = format!("https://github.com/go-{}/{}.git", pkg, pkg);
+        return Some(locate_in_repo(
+            &url,
+            &format!("gopkg.in/{}", parts[0]),
+            module,
+        ));
+    }
+
+    // gopkg.in/user/pkg.vN -> github.com/user/pkg
+    if parts.len() >= 2
+        && let Some(pkg) = strip_gopkg_version(parts[1])
+    {
+        let url = format!("https://github.com/{}/{}.git", parts[0], pkg);
+        let root = format!("gopkg.in/{}/{}", parts[0], parts[1]);
+        return Some(locate_in_repo(&url, &root, module));
+    }
+
+    None
+}
+
+/// Strip the `.vN` suffix of a gopkg.in path element
+fn strip_gopkg_version(element: &str) -> Option<&str> {
+    let (pkg, major) = element.rsplit_once(".v")?;
+    if pkg.is_empty() || major.is_empty() || !major.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some(pkg)
+}
+
+/// Discover a module's repository from its `go-import` meta tag
+pub fn discover(module: &str) -> Result<GoImport, DiscoveryError> {
+    discover_at("https://", module)
+}
+
+/// Fetch `<base><module>?go-get=1` and find the matching `go-import` tag
+fn discover_at(base: &str, module: &str) -> Result<GoImport, DiscoveryError> {
+    let url = format!("{}{}?go-get=1", base, module);
+
+    let response = crate::network::get(&url).map_err(|e| DiscoveryError::Fetch {
+        url: url.clone(),
+        message: e.to_string(),
+    })?;
+
+    let body = response
+        .into_body()
+        .read_to_string()
+        .map_err(|e| DiscoveryError::Fetch {
+            url: url.clone(),
+            message: e.to_string(),
+        })?;
+
+    parse_go_import(&body, module).ok_or_else(|| DiscoveryError::NotFound {
+        module: module.to_string(),
+        url,
+    })
+}
+
+/// Find the `go-import` meta tag whose prefix matches `module`
+///
+/// If several match, the longest prefix wins.
+fn parse_go_import(html: &str, module: &str) -> Option<GoImport> {
+    let mut best: Option<GoImport> = None;
+
+    for tag in html.split('<').skip(1) {
+        let Some(attrs) = tag
+            .strip_prefix("meta ")
+            .or_else(|| tag.strip_prefix("META "))
+        else {
+            continue;
+        };
+        let attrs = attrs.split('>').next().unwrap_or("");
+
+        if attribute(attrs, "name").as_deref() != Some("go-import") {
+            continue;
+        }
+        let Some(content) = attribute(attrs, "content") else {
+            continue;
+        };
+
+        let fields: Vec<&str> = content.split_whitespace().collect();
+        let [prefix, vcs, repo_root] = fields[..] else {
+            continue;
+        };
+
+        let matches = module == prefix || module.starts_with(&format!("{}/", prefix));
+        let longer = best.as_ref().is_none_or(|b| prefix.len() > b.prefix.len());
+        if matches && longer {
+            best = Some(GoImport {
+                prefix: prefix.to_string(),
+                vcs: vcs.to_string(),
+                repo_root: repo_root.to_string(),
+            });
+        }
+    }
+
+    best
+}
+
+/// Read an HTML attribute value (single- or double-quoted)
+fn attribute(attrs: &str, name: &str) -> Option<String> {
+    let mut rest = attrs;
+    while let Some(idx) = rest.find(name) {
+        let after = rest[idx + name.len()..].trim_start();
+        let preceded_ok = idx == 0 || rest[..idx].ends_with(char::is_whitespace);
+        rest = &rest[idx + name.len()..];
+
+        let Some(value) = after.strip_prefix('=') else {
+            continue;
+        };
+        if !preceded_ok {
+            continue;
+        }
+        let value = value.trim_start();
+        let quote = value.chars().next()?;
+        if quote != '"' && quote != '\'' {
+            continue;
+        }
+        let end = value[1..].find(quote)?;
+        return Some(value[1..1 + end].to_string());
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_http;
+
+    #[test]
+    fn test_parse_go_import() {
+        let html = r#"<!DOCTYPE html>
+<html><head>
+<meta name="go-import" content="go.uber.org/zap git https://github.com/uber-go/zap">
+<meta name="go-source" content="go.uber.org/zap https://github.com/uber-go/zap https://github.com/uber-go/zap/tree/master{/dir} https://github.com/uber-go/zap/tree/master{/dir}/{file}#L{line}">
+</head></html>"#;
+
+        let import = parse_go_import(html, "go.uber.org/zap").unwrap();
+        assert_eq!(import.prefix, "go.uber.org/zap");
+        assert_eq!(import.vcs, "git");
+        assert_eq!(import.repo_root, "https://github.com/uber-go/zap");
+
+        assert!(parse_go_import(html, "go.uber.org/zapper").is_none());
+    }
+
+    #[test]
+    fn test_parse_go_import_longest_prefix_and_quotes() {
+        let html = r#"
+<meta name='go-import' content='go.opentelemetry.io/otel git https://github.com/open-telemetry/opentelemetry-go'>
+<meta content="go.opentelemetry.io/otel/sdk mod https://proxy.example.com" name="go-import" />
+"#;
+
+        let import = parse_go_import(html, "go.opentelemetry.io/otel/sdk").unwrap();
+        assert_eq!(import.prefix, "go.opentelemetry.io/otel/sdk");
+        assert_eq!(import.vcs, "mod");
+
+        let import = parse_go_import(html, "go.opentelemetry.io/otel/trace").unwrap();
+        let location = import.location("go.opentelemetry.io/otel/trace");
+        assert_eq!(
+            location.url,
+            "https://github.com/open-telemetry/opentelemetry-go"
+        );
+        assert_eq!(location.subdir.as_deref(), Some("trace"));
+        assert_eq!(location.tag_templates, ["trace/v{version}"]);
+    }
+
+    #[test]
+    fn test_locate_gopkg_in() {
+        assert_eq!(
+            locate_gopkg_in("gopkg.in/yaml.v3"),
+            Some(RepoLocation::root("https://github.com/go-yaml/yaml.git"))
+        );
+        assert_eq!(
+            locate_gopkg_in("gopkg.in/DataDog/dd-trace-go.v1"),
+            Some(RepoLocation::root(
+                "https://github.com/DataDog/dd-trace-go.git"
+            ))
+        );
+        assert_eq!(locate_gopkg_in("gopkg.in/noversion"), None);
+        assert_eq!(locate_gopkg_in("github.com/org/repo"), None);
+    }
+
+    #[test]
+    fn test_discover_at_local_server() {
+        let server = test_http::serve(vec![(
+            "/k8s.io/client-go?go-get=1",
+            br#"<html><head><meta name="go-import" content="k8s.io/client-go git https://github.com/kubernetes/client-go"></head></html>"#.to_vec(),
+        )]);
+
+        let import = discover_at(&format!("{}/", server.url), "k8s.io/client-go").unwrap();
+        assert_eq!(import.repo_root, "https://github.com/kubernetes/client-go");
+        assert_eq!(
+            import.location("k8s.io/client-go"),
+            RepoLocation::root("https://github.com/kubernetes/client-go")
+        );
+
+        let missing = discover_at(&format!("{}/", server.url), "k8s.io/missing");
+        assert!(matches!(missing, Err(DiscoveryError::Fetch { .. })));
+    }
+}
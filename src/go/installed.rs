@@ -0,0 +1,75 @@
+//! Modules already extracted into the Go module cache
+//!
+//! The go command extracts every downloaded module into
+//! `$GOMODCACHE/<module>@v<version>/`, with upper-case letters case-encoded like
+//! proxy URLs (`github.com/!azure/...`). GOMODCACHE defaults to
+//! `$GOPATH/pkg/mod`, and GOPATH to `~/go`. Directories only appear once
+//! extraction is complete.
+
+use std::path::{Path, PathBuf};
+
+use super::proxy::escape_module_path;
+use crate::installed::InstalledSource;
+
+/// Find `module@version` in the module cache (`version` without the leading `v`)
+pub fn find_installed(module: &str, version: &str) -> Option<InstalledSource> {
+    find_in_mod_cache(&mod_cache_dir()?, module, version)
+}
+
+/// Module cache directory, as the go command picks it
+fn mod_cache_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("GOMODCACHE").filter(|v| !v.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+
+    // The first GOPATH entry holds the module cache
+    let gopath = std::env::var_os("GOPATH")
+        .and_then(|v| std::env::split_paths(&v).next())
+        .filter(|p| !p.as_os_str().is_empty())
+        .or_else(|| dirs::home_dir().map(|h| h.join("go")))?;
+    Some(gopath.join("pkg").join("mod"))
+}
+
+fn find_in_mod_cache(mod_cache: &Path, module: &str, version: &str) -> Option<InstalledSource> {
+    let dir = mod_cache.join(format!(
+        "{}@v{}",
+        escape_module_path(module),
+        escape_module_path(version)
+    ));
+    dir.is_dir().then(|| InstalledSource::dir(dir))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_in_mod_cache() {
+        let root = std::env::temp_dir().join("dotdeps_go_installed_test");
+        let _ = std::fs::remove_dir_all(&root);
+
+        let gin = root.join("github.com/gin-gonic/gin@v1.9.1");
+        let azure = root.join("github.com/!azure/azure-sdk-for-go@v68.0.0+incompatible");
+        std::fs::create_dir_all(&gin).unwrap();
+        std::fs::create_dir_all(&azure).unwrap();
+
+        assert_eq!(
+            find_in_mod_cache(&root, "github.com/gin-gonic/gin", "1.9.1"),
+            Some(InstalledSource::dir(gin))
+        );
+        assert_eq!(
+            find_in_mod_cache(
+                &root,
+                "github.com/Azure/azure-sdk-for-go",
+                "68.0.0+incompatible"
+            ),
+            Some(InstalledSource::dir(azure))
+        );
+        assert_eq!(
+            find_in_mod_cache(&root, "github.com/gin-gonic/gin", "1.9.0"),
+            None
+        );
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -5,18 +5,28 @@
 //! go.sum format: `<module path> <version>[/go.mod] <hash>`
 //! go.mod format: `require <module path> <version>` or `require (...)` blocks
 //!
+//! In a workspace (go.work), dependencies are the union of every used module's
+//! requirements, versions come from go.work.sum and each module's go.sum, and the
+//! workspace's own modules are local.
+//!
+//! go.mod `replace` directives take precedence over go.sum:
+//! - `replace <module> [<version>] => <path>` - local directory, skipped like other local deps
+//! - `replace <module> [<version>] => <module> <version>` - fetched from the replacement (e.g., a fork)
+//!
 //! Note: Go modules use the module path as the repo URL, so there's no distinction
 //! between git and non-git dependencies. All Go modules are effectively "git deps".
 
+use super::workspace::Workspace;
 use crate::cli::VersionInfo;
 use crate::lockfile::find_nearest_file;
+use crate::version_req::compare_semver;
 use std::fs;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum LockfileError {
-    #[error("No go.sum found. Specify version explicitly.")]
+    #[error("No go.sum or go.work found. Specify version explicitly.")]
     NotFound,
 
     #[error(
@@ -33,20 +43,56 @@ pub enum LockfileError {
 
 /// Find the version of a Go module by searching go.sum
 ///
-/// Searches upward from the current directory for go.sum
-/// Returns `VersionInfo::Version` - Go doesn't use git dependencies in the same way
+/// Searches upward from the current directory for go.work, then go.sum
+/// Returns `VersionInfo::Version`, or `VersionInfo::Revision` for pseudo-versions
+///
+/// A matching `replace` directive in the go.mod next to go.sum takes precedence.
 pub fn find_version(package: &str) -> Result<VersionInfo, LockfileError> {
     let lockfile = find_lockfile_path()?;
+    if is_workspace_file(&lockfile) {
+        return find_workspace_version(&Workspace::load(&lockfile)?, package);
+    }
+
+    let go_mod = lockfile.with_file_name("go.mod");
+    if go_mod.exists()
+        && let Some(info) = resolve_replacement(&go_mod, &lockfile, package)?
+    {
+        return Ok(info);
+    }
+
     parse_version_from_lockfile(&lockfile, package)
 }
 
-/// Find the nearest go.sum by walking up from current directory
+/// Find the nearest go.work, or else the nearest go.sum, walking up from current directory
+///
+/// A go.work covers every module below it, so it wins over a closer go.sum.
+/// Like the go command, honours `GOWORK` (`off` disables workspace mode).
 pub fn find_lockfile_path() -> Result<PathBuf, LockfileError> {
-    find_nearest_file(&["go.sum"]).ok_or(LockfileError::NotFound)
+    let go_work = match std::env::var("GOWORK") {
+        Ok(value) if value == "off" => None,
+        Ok(value) if !value.is_empty() => Some(PathBuf::from(value)).filter(|p| p.exists()),
+        _ => find_nearest_file(&["go.work"]),
+    };
+
+    go_work
+        .or_else(|| find_nearest_file(&["go.sum"]))
+        .ok_or(LockfileError::NotFound)
+}
+
+/// Whether a path found by `find_lockfile_path` is a workspace file
+fn is_workspace_file(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "work")
 }
 
 /// List direct dependencies from go.mod if present, otherwise fall back to go.sum.
+///
+/// For a go.work, lists the requirements of every used module, excluding the
+/// workspace's own modules.
 pub fn list_direct_dependencies(path: &Path) -> Result<Vec<String>, LockfileError> {
+    if is_workspace_file(path) {
+        return list_workspace_dependencies(&Workspace::load(path)?);
+    }
+
     if let Some(parent) = path.parent() {
         let go_mod = parent.join("go.mod");
         if go_mod.exists() {
@@ -107,7 +153,7 @@ fn parse_version_from_lockfile(path: &Path, package: &str) -> Result<VersionInfo
         if normalize_module_path(module_path) == normalized_package {
             // Strip 'v' prefix for our cache format
             let clean_version = version.strip_prefix('v').unwrap_or(version);
-            return Ok(VersionInfo::Version(clean_version.to_string()));
+            return Ok(super::version::version_info(clean_version));
         }
     }
 
@@ -122,8 +168,94 @@ fn parse_go_mod_direct_dependencies(path: &Path) -> Result<Vec<String>, Lockfile
         source,
     })?;
 
-    let mut deps = Vec::new();
-    let mut in_require_block = false;
+    Ok(direct_requirements(&content))
+}
+
+/// Module paths of the direct (non-`// indirect`) requirements in go.mod content
+fn direct_requirements(content: &str) -> Vec<String> {
+    go_mod_directive_lines(content, "require")
+        .into_iter()
+        .filter_map(parse_go_mod_require_line)
+        .collect()
+}
+
+/// Union of the direct requirements of a workspace's modules
+fn list_workspace_dependencies(workspace: &Workspace) -> Result<Vec<String>, LockfileError> {
+    let local: Vec<String> = workspace
+        .modules
+        .iter()
+        .map(|m| normalize_module_path(&m.path))
+        .collect();
+
+    let mut unique = workspace
+        .modules
+        .iter()
+        .flat_map(|m| direct_requirements(&m.go_mod))
+        .map(|d| normalize_module_path(&d))
+        .filter(|d| !local.contains(d))
+        .collect::<Vec<_>>();
+    unique.sort();
+    unique.dedup();
+    Ok(unique)
+}
+
+/// Find the version of a module in a workspace
+///
+/// Modules of the workspace itself are local. Otherwise the highest version
+/// required by any module wins (as with minimal version selection), falling
+/// back to go.work.sum and the modules' go.sum files. `replace` directives in
+/// go.work take precedence over those in the modules' go.mod files.
+fn find_workspace_version(
+    workspace: &Workspace,
+    package: &str,
+) -> Result<VersionInfo, LockfileError> {
+    let normalized_package = normalize_module_path(package);
+    if let Some(module) = workspace
+        .modules
+        .iter()
+        .find(|m| normalize_module_path(&m.path) == normalized_package)
+    {
+        return Ok(VersionInfo::LocalPath {
+            path: module.dir.clone(),
+        });
+    }
+
+    let required = workspace
+        .modules
+        .iter()
+        .filter_map(|m| required_version(&m.go_mod, package))
+        .map(|v| v.trim_start_matches('v').to_string())
+        .max_by(|a, b| compare_semver(a, b))
+        .or_else(|| {
+            workspace.sum_files().iter().find_map(|sum| {
+                parse_version_from_lockfile(sum, package)
+                    .ok()?
+                    .cache_version()
+                    .map(str::to_string)
+            })
+        });
+
+    let replacement = std::iter::once(workspace.content.as_str())
+        .chain(workspace.modules.iter().map(|m| m.go_mod.as_str()))
+        .find_map(|content| apply_replacements(content, required.as_deref(), package));
+    if let Some(info) = replacement {
+        return Ok(info);
+    }
+
+    required
+        .map(|version| super::version::version_info(&version))
+        .ok_or_else(|| LockfileError::VersionNotFound {
+            package: package.to_string(),
+        })
+}
+
+/// Collect the arguments of a go.mod directive, from single lines and `(...)` blocks
+///
+/// `require a v1` and the lines inside `require ( ... )` both yield `a v1`.
+/// Comments are kept so callers can check for `// indirect`.
+pub(super) fn go_mod_directive_lines<'a>(content: &'a str, directive: &str) -> Vec<&'a str> {
+    let mut lines = Vec::new();
+    let mut in_block = false;
 
     for line in content.lines() {
         let line = line.trim();
@@ -131,31 +263,28 @@ fn parse_go_mod_direct_dependencies(path: &Path) -> Result<Vec<String>, Lockfile
             continue;
         }
 
-        if line.starts_with("require ") {
-            let rest = line.trim_start_matches("require").trim();
-            if rest == "(" {
-                in_require_block = true;
-                continue;
-            }
-
-            if let Some(dep) = parse_go_mod_require_line(rest) {
-                deps.push(dep);
+        if in_block {
+            if line.starts_with(')') {
+                in_block = false;
+            } else {
+                lines.push(line);
             }
             continue;
         }
 
-        if in_require_block {
-            if line.starts_with(')') {
-                in_require_block = false;
-                continue;
-            }
-            if let Some(dep) = parse_go_mod_require_line(line) {
-                deps.push(dep);
+        if let Some(rest) = line.strip_prefix(directive)
+            && rest.starts_with(char::is_whitespace)
+        {
+            let rest = rest.trim();
+            if rest == "(" {
+                in_block = true;
+            } else {
+                lines.push(rest);
             }
         }
     }
 
-    Ok(deps)
+    lines
 }
 
 fn parse_go_mod_require_line(line: &str) -> Option<String> {
@@ -175,6 +304,121 @@ fn parse_go_mod_require_line(line: &str) -> Option<String> {
     }
 }
 
+/// A `replace` directive from go.mod
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Replace {
+    /// Module being replaced
+    old_path: String,
+    /// Only this version is replaced (all versions if None)
+    old_version: Option<String>,
+    /// Replacement module path, or a local directory
+    new_path: String,
+    /// Replacement version (None for local directories)
+    new_version: Option<String>,
+}
+
+/// Parse a replace line: `<module> [<version>] => <module> <version>` or `=> <path>`
+fn parse_go_mod_replace_line(line: &str) -> Option<Replace> {
+    let code = line.split("//").next()?;
+    let (old, new) = code.split_once("=>")?;
+
+    let mut old = old.split_whitespace();
+    let old_path = old.next()?.to_string();
+    let old_version = old.next().map(str::to_string);
+
+    let mut new = new.split_whitespace();
+    let new_path = new.next()?.to_string();
+    let new_version = new.next().map(str::to_string);
+
+    Some(Replace {
+        old_path,
+        old_version,
+        new_path,
+        new_version,
+    })
+}
+
+/// Version of `package` required by go.mod (with the leading `v`)
+fn required_version(content: &str, package: &str) -> Option<String> {
+    let normalized_package = normalize_module_path(package);
+    go_mod_directive_lines(content, "require")
+        .into_iter()
+        .find_map(|line| {
+            let mut parts = line.split_whitespace();
+            let module = parts.next()?;
+            let version = parts.next()?;
+            (normalize_module_path(module) == normalized_package).then(|| version.to_string())
+        })
+}
+
+/// Apply go.mod `replace` directives to `package`
+///
+/// A version-specific replacement applies only to the version go.mod requires
+/// (or go.sum lists) and wins over a replacement of all versions.
+/// Returns None if no directive replaces the package.
+fn resolve_replacement(
+    go_mod: &Path,
+    go_sum: &Path,
+    package: &str,
+) -> Result<Option<VersionInfo>, LockfileError> {
+    let content = fs::read_to_string(go_mod).map_err(|source| LockfileError::ReadFile {
+        path: go_mod.to_path_buf(),
+        source,
+    })?;
+
+    // Compare versions without the leading 'v'
+    let required = match required_version(&content, package) {
+        Some(version) => Some(version.trim_start_matches('v').to_string()),
+        None => parse_version_from_lockfile(go_sum, package)
+            .ok()
+            .and_then(|info| info.cache_version().map(str::to_string)),
+    };
+
+    Ok(apply_replacements(&content, required.as_deref(), package))
+}
+
+/// Apply the `replace` directives in `content` (go.mod or go.work) to `package`
+///
+/// `required` is the selected version of `package`, without the leading `v`.
+fn apply_replacements(content: &str, required: Option<&str>, package: &str) -> Option<VersionInfo> {
+    let normalized_package = normalize_module_path(package);
+    let replaces: Vec<Replace> = go_mod_directive_lines(content, "replace")
+        .into_iter()
+        .filter_map(parse_go_mod_replace_line)
+        .filter(|r| normalize_module_path(&r.old_path) == normalized_package)
+        .collect();
+
+    let replace = replaces
+        .iter()
+        .find(|r| {
+            r.old_version
+                .as_deref()
+                .is_some_and(|v| Some(v.trim_start_matches('v')) == required)
+        })
+        .or_else(|| replaces.iter().find(|r| r.old_version.is_none()))?;
+
+    let info = match &replace.new_version {
+        None => VersionInfo::LocalPath {
+            path: replace.new_path.clone(),
+        },
+        Some(version) => {
+            let version = version.strip_prefix('v').unwrap_or(version);
+            if normalize_module_path(&replace.new_path) == normalized_package {
+                // Same module, different version
+                super::version::version_info(version)
+            } else {
+                VersionInfo::Replaced {
+                    package: replace.new_path.clone(),
+                    version: version.to_string(),
+                    commit: super::version::pseudo_version_revision(version).map(str::to_string),
+                }
+            }
+        }
+    };
+
+    Some(info)
+}
+
 fn list_modules_from_go_sum(path: &Path) -> Result<Vec<String>, LockfileError> {
     let content = fs::read_to_string(path).map_err(|source| LockfileError::ReadFile {
         path: path.to_path_buf(),
@@ -343,4 +587,219 @@ require (
         assert!(deps.contains(&"golang.org/x/sync".to_string()));
         assert!(!deps.contains(&"github.com/indirect/dep".to_string()));
     }
+
+    #[test]
+    fn test_parse_version_from_lockfile_pseudo_version() {
+        let content = r#"
+github.com/example/pkg v0.0.0-20231215123456-abc123def456 h1:hash=
+github.com/example/pkg v0.0.0-20231215123456-abc123def456/go.mod h1:hash=
+github.com/gin-gonic/gin v1.9.1 h1:hash=
+"#;
+        let path = write_temp_file("go.sum", content);
+
+        assert_eq!(
+            parse_version_from_lockfile(&path, "github.com/example/pkg").unwrap(),
+            VersionInfo::Revision {
+                version: "0.0.0-20231215123456-abc123def456".to_string(),
+                commit: "abc123def456".to_string(),
+            }
+        );
+        assert_eq!(
+            parse_version_from_lockfile(&path, "github.com/gin-gonic/gin").unwrap(),
+            VersionInfo::Version("1.9.1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_go_mod_replace_line() {
+        assert_eq!(
+            parse_go_mod_replace_line("github.com/a/b => github.com/us/b v1.2.3"),
+            Some(Replace {
+                old_path: "github.com/a/b".to_string(),
+                old_version: None,
+                new_path: "github.com/us/b".to_string(),
+                new_version: Some("v1.2.3".to_string()),
+            })
+        );
+        assert_eq!(
+            parse_go_mod_replace_line("github.com/a/b v1.0.0 => ../b // local checkout"),
+            Some(Replace {
+                old_path: "github.com/a/b".to_string(),
+                old_version: Some("v1.0.0".to_string()),
+                new_path: "../b".to_string(),
+                new_version: None,
+            })
+        );
+        assert_eq!(parse_go_mod_replace_line("github.com/a/b v1.0.0"), None);
+    }
+
+    #[test]
+    fn test_resolve_replacement() {
+        let go_mod = r#"
+module example.com/test
+
+require (
+  github.com/a/fork v1.0.0
+  github.com/a/local v0.3.0
+  github.com/a/pinned v2.0.0+incompatible
+  github.com/a/other v1.1.0
+)
+
+replace github.com/a/fork => github.com/us/fork v1.0.1-0.20240101000000-0123456789ab
+
+replace (
+  github.com/a/local => ../local
+  github.com/a/pinned v2.0.0+incompatible => github.com/a/pinned v2.0.1+incompatible
+  github.com/a/other v1.0.0 => github.com/us/other v1.0.5
+)
+"#;
+        let go_mod_path = write_temp_file("go.mod", go_mod);
+        let go_sum_path = go_mod_path.with_file_name("go.sum");
+        fs::write(&go_sum_path, "").unwrap();
+
+        let resolve = |package| resolve_replacement(&go_mod_path, &go_sum_path, package).unwrap();
+
+        assert_eq!(
+            resolve("github.com/a/fork"),
+            Some(VersionInfo::Replaced {
+                package: "github.com/us/fork".to_string(),
+                version: "1.0.1-0.20240101000000-0123456789ab".to_string(),
+                commit: Some("0123456789ab".to_string()),
+            })
+        );
+        assert_eq!(
+            resolve("github.com/a/local"),
+            Some(VersionInfo::LocalPath {
+                path: "../local".to_string()
+            })
+        );
+        assert_eq!(
+            resolve("github.com/a/pinned"),
+            Some(VersionInfo::Version("2.0.1+incompatible".to_string()))
+        );
+        // Version-specific replacement of a version that isn't required
+        assert_eq!(resolve("github.com/a/other"), None);
+        assert_eq!(resolve("github.com/a/unreplaced"), None);
+    }
+
+    #[test]
+    fn test_version_specific_replacement_uses_go_sum() {
+        let go_mod = r#"
+module example.com/test
+
+replace github.com/a/b v1.0.0 => github.com/us/b v1.0.0
+replace github.com/a/b => ./vendor/b
+"#;
+        let go_mod_path = write_temp_file("go.mod", go_mod);
+        let go_sum_path = go_mod_path.with_file_name("go.sum");
+        fs::write(&go_sum_path, "github.com/a/b v1.0.0 h1:hash=\n").unwrap();
+
+        assert_eq!(
+            resolve_replacement(&go_mod_path, &go_sum_path, "github.com/a/b").unwrap(),
+            Some(VersionInfo::Replaced {
+                package: "github.com/us/b".to_string(),
+                version: "1.0.0".to_string(),
+                commit: None,
+            })
+        );
+    }
+
+    fn write_workspace(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("api")).unwrap();
+        fs::create_dir_all(root.join("worker")).unwrap();
+
+        fs::write(
+            root.join("go.work"),
+            "go 1.22\n\nuse (\n\t./api\n\t./worker\n)\n\nreplace github.com/a/forked => github.com/us/forked v1.1.0\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("go.work.sum"),
+            "github.com/only/worksum v0.4.0 h1:hash=\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("api/go.mod"),
+            r#"module example.com/api
+
+require (
+	example.com/worker v0.0.0
+	github.com/gin-gonic/gin v1.9.1
+	github.com/a/forked v1.0.0
+	golang.org/x/sync v0.5.0 // indirect
+)
+
+replace github.com/a/forked => ../forked
+"#,
+        )
+        .unwrap();
+        fs::write(
+            root.join("worker/go.mod"),
+            "module example.com/worker\n\nrequire github.com/gin-gonic/gin v1.10.0\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("worker/go.sum"),
+            "golang.org/x/sync v0.6.0 h1:hash=\n",
+        )
+        .unwrap();
+        root
+    }
+
+    #[test]
+    fn test_list_workspace_dependencies() {
+        let root = write_workspace("dotdeps_go_workspace_deps_test");
+
+        let deps = list_direct_dependencies(&root.join("go.work")).unwrap();
+        assert_eq!(deps, ["github.com/a/forked", "github.com/gin-gonic/gin"]);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_find_workspace_version() {
+        let root = write_workspace("dotdeps_go_workspace_version_test");
+        let workspace = Workspace::load(&root.join("go.work")).unwrap();
+        let find = |package| find_workspace_version(&workspace, package).unwrap();
+
+        // Highest requirement across modules
+        assert_eq!(
+            find("github.com/gin-gonic/gin"),
+            VersionInfo::Version("1.10.0".to_string())
+        );
+        // Workspace modules are local
+        assert_eq!(
+            find("example.com/worker"),
+            VersionInfo::LocalPath {
+                path: "./worker".to_string()
+            }
+        );
+        // go.work replacements win over module replacements
+        assert_eq!(
+            find("github.com/a/forked"),
+            VersionInfo::Replaced {
+                package: "github.com/us/forked".to_string(),
+                version: "1.1.0".to_string(),
+                commit: None,
+            }
+        );
+        // Not required anywhere: go.work.sum, then each module's go.sum
+        assert_eq!(
+            find("github.com/only/worksum"),
+            VersionInfo::Version("0.4.0".to_string())
+        );
+        // Indirect requirements still have a version
+        assert_eq!(
+            find("golang.org/x/sync"),
+            VersionInfo::Version("0.5.0".to_string())
+        );
+        assert!(matches!(
+            find_workspace_version(&workspace, "github.com/missing/dep"),
+            Err(LockfileError::VersionNotFound { .. })
+        ));
+
+        let _ = fs::remove_dir_all(&root);
+    }
 }
@@ -0,0 +1,127 @@
+//! Go module path to repository mapping
+//!
+//! Module paths on well-known hosts embed the repository:
+//! - `github.com/org/repo` -> `https://github.com/org/repo.git`
+//! - `github.com/org/repo/v2` -> same repository (major version suffix)
+//! - `golang.org/x/sync` -> `https://go.googlesource.com/sync.git`
+//!
+//! Multi-module repositories keep nested modules in subdirectories, tagged
+//! with the subdirectory as prefix:
+//! - `github.com/aws/aws-sdk-go-v2/service/s3` -> repository
+//!   `github.com/aws/aws-sdk-go-v2`, subdirectory `service/s3`, tags `service/s3/v1.40.0`
+
+use crate::git::RepoLocation;
+
+/// Hosts where the repository root is `<host>/<owner>/<repo>`
+const OWNER_REPO_HOSTS: &[&str] = &["github.com", "bitbucket.org"];
+
+/// Locate the repository for a module path on a well-known host
+///
+/// Returns None if the host isn't known; those modules need a config override.
+pub fn locate_known_host(module: &str) -> Option<RepoLocation> {
+    let parts: Vec<&str> = module.split('/').collect();
+
+    if OWNER_REPO_HOSTS.contains(&parts[0]) && parts.len() >= 3 {
+        let root = parts[..3].join("/");
+        return Some(locate_in_repo(
+            &format!("https://{}.git", root),
+            &root,
+            module,
+        ));
+    }
+
+    if module.starts_with("golang.org/x/") && parts.len() >= 3 {
+        let root = parts[..3].join("/");
+        return Some(locate_in_repo(
+            &format!("https://go.googlesource.com/{}.git", parts[2]),
+            &root,
+            module,
+        ));
+    }
+
+    None
+}
+
+/// Build the location of `module` inside the repository whose import path is `root`
+pub fn locate_in_repo(url: &str, root: &str, module: &str) -> RepoLocation {
+    let subdir = module
+        .strip_prefix(root)
+        .map(|rest| strip_major_version_suffix(rest.trim_matches('/')))
+        .filter(|rest| !rest.is_empty());
+
+    match subdir {
+        Some(subdir) => RepoLocation {
+            subdir: Some(subdir.to_string()),
+            tag_templates: vec![format!("{}/v{{version}}", subdir)],
+            ..RepoLocation::root(url)
+        },
+        None => RepoLocation::root(url),
+    }
+}
+
+/// Strip a Go major version suffix (`/v2`, `/v3`, ...) or a bare `vN` path
+fn strip_major_version_suffix(path: &str) -> &str {
+    let (head, last) = match path.rsplit_once('/') {
+        Some((head, last)) => (head, last),
+        None => ("", path),
+    };
+
+    let is_major = last
+        .strip_prefix('v')
+        .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+
+    if is_major { head } else { path }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locate_github_root_module() {
+        assert_eq!(
+            locate_known_host("github.com/gin-gonic/gin"),
+            Some(RepoLocation::root("https://github.com/gin-gonic/gin.git"))
+        );
+        assert_eq!(
+            locate_known_host("github.com/go-redis/redis/v9"),
+            Some(RepoLocation::root("https://github.com/go-redis/redis.git"))
+        );
+    }
+
+    #[test]
+    fn test_locate_nested_module() {
+        let location = locate_known_host("github.com/aws/aws-sdk-go-v2/service/s3").unwrap();
+        assert_eq!(location.url, "https://github.com/aws/aws-sdk-go-v2.git");
+        assert_eq!(location.subdir.as_deref(), Some("service/s3"));
+        assert_eq!(location.tag_templates, ["service/s3/v{version}"]);
+
+        let location = locate_known_host("github.com/org/repo/tools/v2").unwrap();
+        assert_eq!(location.subdir.as_deref(), Some("tools"));
+    }
+
+    #[test]
+    fn test_locate_golang_org_x() {
+        assert_eq!(
+            locate_known_host("golang.org/x/sync"),
+            Some(RepoLocation::root("https://go.googlesource.com/sync.git"))
+        );
+        let location = locate_known_host("golang.org/x/tools/gopls").unwrap();
+        assert_eq!(location.url, "https://go.googlesource.com/tools.git");
+        assert_eq!(location.subdir.as_deref(), Some("gopls"));
+    }
+
+    #[test]
+    fn test_locate_unknown_host() {
+        assert_eq!(locate_known_host("go.uber.org/zap"), None);
+        assert_eq!(locate_known_host("github.com/incomplete"), None);
+    }
+
+    #[test]
+    fn test_strip_major_version_suffix() {
+        assert_eq!(strip_major_version_suffix("v2"), "");
+        assert_eq!(strip_major_version_suffix("service/s3/v12"), "service/s3");
+        assert_eq!(strip_major_version_suffix("service/s3"), "service/s3");
+        assert_eq!(strip_major_version_suffix("v2beta"), "v2beta");
+    }
+}
@@ -0,0 +1,251 @@
+//! Go module proxy support
+//!
+//! Fetches module sources from a GOPROXY server (https://go.dev/ref/mod#goproxy-protocol)
+//! when a module has no git repository to clone:
+//! - `<proxy>/<module>/@v/<version>.info` - JSON with optional VCS origin
+//! - `<proxy>/<module>/@v/<version>.zip` - source archive rooted at `<module>@<version>/`
+//!
+//! Module paths are case-encoded: uppercase letters become `!` + lowercase.
+
+use serde::Deserialize;
+use std::path::Path;
+use thiserror::Error;
+
+use crate::archive::{self, ArchiveError};
+
+/// Proxy used when neither config nor GOPROXY names one
+pub const DEFAULT_PROXY: &str = "https://proxy.golang.org";
+
+#[derive(Error, Debug)]
+pub enum ProxyError {
+    #[error(transparent)]
+    Archive(#[from] ArchiveError),
+}
+
+/// Result of fetching a module from the proxy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxyFetch {
+    /// Repository URL reported by the proxy, if any
+    pub origin_url: Option<String>,
+    /// Commit reported by the proxy, if any
+    pub commit: Option<String>,
+}
+
+/// Response of the `.info` endpoint
+#[derive(Debug, Deserialize)]
+struct ModuleInfo {
+    #[serde(rename = "Origin")]
+    origin: Option<ModuleOrigin>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModuleOrigin {
+    #[serde(rename = "URL")]
+    url: Option<String>,
+    #[serde(rename = "Hash")]
+    hash: Option<String>,
+}
+
+/// Pick the proxy to use: config first, then the GOPROXY environment variable
+///
+/// Returns None if module proxies are disabled (`GOPROXY=off`).
+pub fn proxy_url(configured: Option<&str>) -> Option<String> {
+    if let Some(url) = configured {
+        return Some(url.trim_end_matches('/').to_string());
+    }
+    match std::env::var("GOPROXY") {
+        Ok(value) if !value.trim().is_empty() => select_proxy(&value),
+        _ => Some(DEFAULT_PROXY.to_string()),
+    }
+}
+
+/// Select the first usable proxy from a GOPROXY list
+///
+/// Entries are separated by `,` or `|`. `direct` is skipped (dotdeps clones
+/// git repositories itself); `off` ends the list.
+fn select_proxy(value: &str) -> Option<String> {
+    for entry in value.split([',', '|']).map(str::trim) {
+        match entry {
+            "" | "direct" => continue,
+            "off" => return None,
+            url => return Some(url.trim_end_matches('/').to_string()),
+        }
+    }
+    None
+}
+
+/// Case-encode a module path for proxy URLs
+pub fn escape_module_path(module: &str) -> String {
+    let mut escaped = String::with_capacity(module.len());
+    for c in module.chars() {
+        if c.is_ascii_uppercase() {
+            escaped.push('!');
+            escaped.push(c.to_ascii_lowercase());
+        } else {
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+/// Download `module@version` from `proxy` and extract it into `dest`
+///
+/// `version` is given without the leading `v`.
+pub fn fetch_module(
+    proxy: &str,
+    module: &str,
+    version: &str,
+    dest: &Path,
+) -> Result<ProxyFetch, ProxyError> {
+    let base = format!(
+        "{}/{}/@v/v{}",
+        proxy.trim_end_matches('/'),
+        escape_module_path(module),
+        version
+    );
+
+    // The origin is informational; older proxies don't report it
+    let origin = archive::fetch(&format!("{}.info", base))
+        .ok()
+        .and_then(|body| serde_json::from_slice::<ModuleInfo>(&body).ok())
+        .and_then(|info| info.origin);
+
+    let zip_url = format!("{}.zip", base);
+    let body = archive::fetch(&zip_url)?;
+    // Files are rooted at `<module>@v<version>/`, one component per path segment
+    let root_components = module.split('/').count();
+    archive::unpack_zip(&body, root_components, dest).map_err(|source| ArchiveError::Unpack {
+        url: zip_url.clone(),
+        source,
+    })?;
+
+    Ok(ProxyFetch {
+        origin_url: origin.as_ref().and_then(|o| o.url.clone()),
+        commit: origin.and_then(|o| o.hash),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_http;
+    use std::io::{Cursor, Write};
+
+    fn module_zip(prefix: &str, files: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in files {
+            writer
+                .start_file(
+                    format!("{}/{}", prefix, name),
+                    zip::write::FileOptions::default(),
+                )
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_select_proxy() {
+        assert_eq!(
+            select_proxy("https://proxy.golang.org,direct"),
+            Some("https://proxy.golang.org".to_string())
+        );
+        assert_eq!(
+            select_proxy("direct|https://goproxy.io/"),
+            Some("https://goproxy.io".to_string())
+        );
+        assert_eq!(select_proxy("off"), None);
+        assert_eq!(select_proxy("direct"), None);
+    }
+
+    #[test]
+    fn test_proxy_url_prefers_config() {
+        assert_eq!(
+            proxy_url(Some("https://goproxy.example.com/")),
+            Some("https://goproxy.example.com".to_string())
+        );
+    }
+
+    #[test]
+    fn test_escape_module_path() {
+        assert_eq!(
+            escape_module_path("github.com/Azure/azure-sdk-for-go"),
+            "github.com/!azure/azure-sdk-for-go"
+        );
+        assert_eq!(escape_module_path("go.uber.org/zap"), "go.uber.org/zap");
+    }
+
+    #[test]
+    fn test_fetch_module_from_local_proxy() {
+        let zip = module_zip(
+            "example.com/My/mod@v1.2.0",
+            &[
+                ("go.mod", "module example.com/My/mod\n"),
+                ("sub/a.go", "package sub\n"),
+            ],
+        );
+        let info = br#"{"Version":"v1.2.0","Origin":{"VCS":"git","URL":"https://git.example.com/mod","Hash":"0123456789abcdef0123456789abcdef01234567"}}"#;
+        let server = test_http::serve(vec![
+            ("/example.com/!my/mod/@v/v1.2.0.info", info.to_vec()),
+            ("/example.com/!my/mod/@v/v1.2.0.zip", zip),
+        ]);
+
+        let dest = std::env::temp_dir().join("dotdeps_goproxy_fetch_test");
+        let _ = std::fs::remove_dir_all(&dest);
+
+        let fetched = fetch_module(&server.url, "example.com/My/mod", "1.2.0", &dest).unwrap();
+        assert_eq!(
+            fetched.origin_url.as_deref(),
+            Some("https://git.example.com/mod")
+        );
+        assert_eq!(
+            fetched.commit.as_deref(),
+            Some("0123456789abcdef0123456789abcdef01234567")
+        );
+        assert_eq!(
+            std::fs::read_to_string(dest.join("go.mod")).unwrap(),
+            "module example.com/My/mod\n"
+        );
+        assert!(dest.join("sub").join("a.go").exists());
+
+        let _ = std::fs::remove_dir_all(&dest);
+    }
+
+    #[test]
+    fn test_fetch_module_without_info() {
+        let zip = module_zip(
+            "example.com/plain@v0.1.0",
+            &[("plain.go", "package plain\n")],
+        );
+        let server = test_http::serve(vec![("/example.com/plain/@v/v0.1.0.zip", zip)]);
+
+        let dest = std::env::temp_dir().join("dotdeps_goproxy_noinfo_test");
+        let _ = std::fs::remove_dir_all(&dest);
+
+        let fetched = fetch_module(&server.url, "example.com/plain", "0.1.0", &dest).unwrap();
+        assert_eq!(
+            fetched,
+            ProxyFetch {
+                origin_url: None,
+                commit: None
+            }
+        );
+        assert!(dest.join("plain.go").exists());
+        assert_eq!(
+            server.requests.lock().unwrap().as_slice(),
+            [
+                "/example.com/plain/@v/v0.1.0.info",
+                "/example.com/plain/@v/v0.1.0.zip"
+            ]
+        );
+
+        let missing = fetch_module(&server.url, "example.com/missing", "0.1.0", &dest);
+        assert!(matches!(
+            missing,
+            Err(ProxyError::Archive(ArchiveError::Fetch { .. }))
+        ));
+
+        let _ = std::fs::remove_dir_all(&dest);
+    }
+}
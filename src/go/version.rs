@@ -0,0 +1,118 @@
+//! Go module version handling
+//!
+//! Recognises pseudo-versions, which encode an exact commit instead of a tag:
+//! - `v0.0.0-20231215123456-abc123def456` (no earlier tag)
+//! - `v1.2.4-0.20231215123456-abc123def456` (after release v1.2.3)
+//! - `v1.2.3-pre.0.20231215123456-abc123def456` (after pre-release v1.2.3-pre)
+//!
+//! Any of these may carry a `+incompatible` suffix.
+
+use crate::cli::VersionInfo;
+
+/// Length of the commit prefix encoded in a pseudo-version
+const REVISION_LEN: usize = 12;
+
+/// Length of the `yyyymmddhhmmss` timestamp in a pseudo-version
+const TIMESTAMP_LEN: usize = 14;
+
+/// Extract the 12-character commit prefix from a Go pseudo-version
+///
+/// Accepts versions with or without the leading `v`. Returns None for
+/// regular (tagged) versions.
+pub fn pseudo_version_revision(version: &str) -> Option<&str> {
+    // Drop build metadata such as +incompatible
+    let version = version.split_once('+').map_or(version, |(v, _)| v);
+
+    let (rest, revision) = version.rsplit_once('-')?;
+    if revision.len() != REVISION_LEN
+        || !revision
+            .chars()
+            .all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c))
+    {
+        return None;
+    }
+
+    // The timestamp follows either '-' (vX.0.0-) or '.' (-0. / -pre.0.)
+    if rest.len() <= TIMESTAMP_LEN {
+        return None;
+    }
+    let (prefix, timestamp) = rest.split_at(rest.len() - TIMESTAMP_LEN);
+    if !timestamp.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    if !(prefix.ends_with('-') || prefix.ends_with(".0.") || prefix.ends_with("-0.")) {
+        return None;
+    }
+
+    Some(revision)
+}
+
+/// Build the `VersionInfo` for a Go module version (without the leading `v`)
+///
+/// Pseudo-versions pin the commit they encode; everything else is a tag lookup.
+pub fn version_info(version: &str) -> VersionInfo {
+    match pseudo_version_revision(version) {
+        Some(revision) => VersionInfo::Revision {
+            version: version.to_string(),
+            commit: revision.to_string(),
+        },
+        None => VersionInfo::Version(version.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pseudo_version_forms() {
+        assert_eq!(
+            pseudo_version_revision("v0.0.0-20231215123456-abc123def456"),
+            Some("abc123def456")
+        );
+        assert_eq!(
+            pseudo_version_revision("1.2.4-0.20231215123456-abc123def456"),
+            Some("abc123def456")
+        );
+        assert_eq!(
+            pseudo_version_revision("v1.2.3-pre.0.20231215123456-abc123def456"),
+            Some("abc123def456")
+        );
+        assert_eq!(
+            pseudo_version_revision("v2.0.1-0.20190109142624-2b1f4bb6a9b7+incompatible"),
+            Some("2b1f4bb6a9b7")
+        );
+    }
+
+    #[test]
+    fn test_regular_versions_are_not_pseudo() {
+        assert_eq!(pseudo_version_revision("1.9.1"), None);
+        assert_eq!(pseudo_version_revision("v3.0.0-beta.1"), None);
+        assert_eq!(pseudo_version_revision("v2.0.0+incompatible"), None);
+        // Right shape, but the revision isn't lowercase hex
+        assert_eq!(
+            pseudo_version_revision("v0.0.0-20231215123456-ABC123DEF456"),
+            None
+        );
+        // Timestamp too short
+        assert_eq!(
+            pseudo_version_revision("v0.0.0-2023121512-abc123def456"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_version_info() {
+        assert_eq!(
+            version_info("0.0.0-20231215123456-abc123def456"),
+            VersionInfo::Revision {
+                version: "0.0.0-20231215123456-abc123def456".to_string(),
+                commit: "abc123def456".to_string(),
+            }
+        );
+        assert_eq!(
+            version_info("1.9.1"),
+            VersionInfo::Version("1.9.1".to_string())
+        );
+    }
+}
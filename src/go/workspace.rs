@@ -0,0 +1,170 @@
+//! Go workspace (go.work) support
+//!
+//! A go.work file lists the modules of a multi-module checkout:
+//!
+//! ```text
+//! go 1.22
+//!
+//! use (
+//!     ./api
+//!     ./worker
+//! )
+//! ```
+//!
+//! Each used directory holds its own go.mod and go.sum; checksums for
+//! dependencies not covered by any module's go.sum live in go.work.sum.
+
+use super::lockfile::{LockfileError, go_mod_directive_lines};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A parsed go.work and the modules it uses
+#[derive(Debug)]
+pub struct Workspace {
+    /// Path of the go.work file
+    pub path: PathBuf,
+    /// Contents of go.work (for its `replace` directives)
+    pub content: String,
+    /// Modules listed in `use` directives that have a go.mod
+    pub modules: Vec<WorkspaceModule>,
+}
+
+/// A module that is part of a workspace
+#[derive(Debug)]
+pub struct WorkspaceModule {
+    /// Directory as written in the `use` directive
+    pub dir: String,
+    /// Module path declared in its go.mod
+    pub path: String,
+    /// Contents of its go.mod
+    pub go_mod: String,
+    /// Its go.sum
+    pub go_sum: PathBuf,
+}
+
+impl Workspace {
+    /// Read go.work and the go.mod of every module it uses
+    pub fn load(path: &Path) -> Result<Self, LockfileError> {
+        let content = fs::read_to_string(path).map_err(|source| LockfileError::ReadFile {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let root = path.parent().unwrap_or(Path::new("."));
+
+        let mut modules = Vec::new();
+        for line in go_mod_directive_lines(&content, "use") {
+            let Some(dir) = parse_use_line(line) else {
+                continue;
+            };
+            if modules.iter().any(|m: &WorkspaceModule| m.dir == dir) {
+                continue;
+            }
+            let module_dir = root.join(&dir);
+            let go_mod_path = module_dir.join("go.mod");
+            if !go_mod_path.exists() {
+                continue;
+            }
+
+            let go_mod =
+                fs::read_to_string(&go_mod_path).map_err(|source| LockfileError::ReadFile {
+                    path: go_mod_path.clone(),
+                    source,
+                })?;
+            let Some(module_path) = parse_module_path(&go_mod) else {
+                continue;
+            };
+
+            modules.push(WorkspaceModule {
+                dir,
+                path: module_path,
+                go_mod,
+                go_sum: module_dir.join("go.sum"),
+            });
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            content,
+            modules,
+        })
+    }
+
+    /// Checksum files to search for versions: go.work.sum, then each module's go.sum
+    pub fn sum_files(&self) -> Vec<PathBuf> {
+        std::iter::once(self.path.with_file_name("go.work.sum"))
+            .chain(self.modules.iter().map(|m| m.go_sum.clone()))
+            .filter(|path| path.exists())
+            .collect()
+    }
+}
+
+/// Parse the directory of a `use` line, dropping comments and quotes
+fn parse_use_line(line: &str) -> Option<String> {
+    let dir = line.split("//").next()?.trim();
+    let dir = dir.trim_matches(|c| c == '"' || c == '`');
+    (!dir.is_empty()).then(|| dir.to_string())
+}
+
+/// Read the `module` directive of a go.mod
+fn parse_module_path(go_mod: &str) -> Option<String> {
+    go_mod.lines().find_map(|line| {
+        let rest = line.trim().strip_prefix("module")?;
+        if !rest.starts_with(char::is_whitespace) {
+            return None;
+        }
+        let path = rest.split("//").next()?.trim();
+        Some(path.trim_matches(|c| c == '"' || c == '`').to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_module_path() {
+        assert_eq!(
+            parse_module_path("// comment\nmodule example.com/api // main\n\ngo 1.22\n"),
+            Some("example.com/api".to_string())
+        );
+        assert_eq!(
+            parse_module_path("module \"example.com/quoted\"\n"),
+            Some("example.com/quoted".to_string())
+        );
+        assert_eq!(parse_module_path("go 1.22\n"), None);
+    }
+
+    #[test]
+    fn test_load_workspace() {
+        let root = std::env::temp_dir().join("dotdeps_go_workspace_load_test");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("api")).unwrap();
+        fs::create_dir_all(root.join("tools/gen")).unwrap();
+        fs::create_dir_all(root.join("missing")).unwrap();
+
+        fs::write(
+            root.join("go.work"),
+            "go 1.22\n\nuse (\n\t./api\n\t\"./tools/gen\" // generator\n\t./missing\n)\n\nuse ./api\n",
+        )
+        .unwrap();
+        fs::write(root.join("go.work.sum"), "").unwrap();
+        fs::write(root.join("api/go.mod"), "module example.com/api\n").unwrap();
+        fs::write(root.join("api/go.sum"), "").unwrap();
+        fs::write(
+            root.join("tools/gen/go.mod"),
+            "module example.com/tools/gen\n",
+        )
+        .unwrap();
+
+        let workspace = Workspace::load(&root.join("go.work")).unwrap();
+        let paths: Vec<&str> = workspace.modules.iter().map(|m| m.path.as_str()).collect();
+        assert_eq!(paths, ["example.com/api", "example.com/tools/gen"]);
+        assert_eq!(workspace.modules[1].dir, "./tools/gen");
+        assert_eq!(
+            workspace.sum_files(),
+            [root.join("go.work.sum"), root.join("./api").join("go.sum")]
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
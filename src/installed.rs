@@ -0,0 +1,147 @@
+//! Sources already installed by a package manager
+//!
+//! Package managers keep unpacked sources on disk: `~/.cargo/registry/src`,
+//! `$GOMODCACHE`, `node_modules`, a virtualenv's `site-packages`, bundler's gem
+//! directories. When the resolved version is there, it is copied into the cache
+//! instead of fetching anything over the network.
+//!
+//! Each ecosystem module finds its own installed sources (`find_installed`);
+//! this module holds what they return and how it is copied.
+
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+/// Directories never copied: installed dependencies of the package and VCS data
+const SKIPPED_DIRS: &[&str] = &["node_modules", ".git", "__pycache__"];
+
+/// An installed package's sources
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstalledSource {
+    /// Directory the package was installed into
+    pub path: PathBuf,
+    /// Files belonging to the package, relative to `path`, when `path` is
+    /// shared with other packages (e.g. `site-packages`). None copies all of `path`.
+    pub files: Option<Vec<PathBuf>>,
+}
+
+impl InstalledSource {
+    /// A package that has `path` to itself
+    pub fn dir(path: PathBuf) -> Self {
+        Self { path, files: None }
+    }
+
+    /// A package made of `files` inside the shared directory `path`
+    pub fn files(path: PathBuf, files: Vec<PathBuf>) -> Self {
+        Self {
+            path,
+            files: Some(files),
+        }
+    }
+
+    /// Copy the package's files into `dest`
+    ///
+    /// Symlinks and paths leaving `path` are skipped.
+    pub fn copy_into(&self, dest: &Path) -> io::Result<()> {
+        fs::create_dir_all(dest)?;
+        match &self.files {
+            None => copy_dir(&self.path, dest),
+            Some(files) => {
+                for file in files {
+                    if file
+                        .components()
+                        .any(|c| !matches!(c, Component::Normal(_)))
+                    {
+                        continue;
+                    }
+                    let source = self.path.join(file);
+                    if !fs::symlink_metadata(&source).is_ok_and(|m| m.is_file()) {
+                        continue;
+                    }
+                    let target = dest.join(file);
+                    if let Some(parent) = target.parent() {
+                        fs::create_dir_all(parent)?;
+                    }
+                    fs::copy(&source, &target)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Recursively copy regular files from `source` into `dest`
+fn copy_dir(source: &Path, dest: &Path) -> io::Result<()> {
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let target = dest.join(entry.file_name());
+
+        if file_type.is_dir() {
+            if SKIPPED_DIRS.contains(&entry.file_name().to_string_lossy().as_ref()) {
+                continue;
+            }
+            fs::create_dir_all(&target)?;
+            copy_dir(&entry.path(), &target)?;
+        } else if file_type.is_file() {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, file: &str, content: &str) {
+        let path = root.join(file);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_copy_dir_skips_nested_dependencies() {
+        let root = std::env::temp_dir().join("dotdeps_installed_copy_dir_test");
+        let _ = fs::remove_dir_all(&root);
+        let source = root.join("node_modules").join("lodash");
+        write(&source, "package.json", "{}");
+        write(&source, "fp/map.js", "module.exports = 1;");
+        write(&source, "node_modules/dep/index.js", "");
+
+        let dest = root.join("dest");
+        InstalledSource::dir(source).copy_into(&dest).unwrap();
+
+        assert!(dest.join("package.json").exists());
+        assert!(dest.join("fp").join("map.js").exists());
+        assert!(!dest.join("node_modules").exists());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_copy_files() {
+        let root = std::env::temp_dir().join("dotdeps_installed_copy_files_test");
+        let _ = fs::remove_dir_all(&root);
+        let site_packages = root.join("site-packages");
+        write(&site_packages, "requests/__init__.py", "");
+        write(&site_packages, "urllib3/__init__.py", "");
+
+        let dest = root.join("dest");
+        InstalledSource::files(
+            site_packages,
+            vec![
+                PathBuf::from("requests/__init__.py"),
+                PathBuf::from("../../../bin/requests"),
+                PathBuf::from("requests/missing.py"),
+            ],
+        )
+        .copy_into(&dest)
+        .unwrap();
+
+        assert!(dest.join("requests").join("__init__.py").exists());
+        assert!(!dest.join("urllib3").exists());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
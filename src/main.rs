@@ -1,3 +1,4 @@
+mod archive;
 mod cache;
 mod cli;
 mod config;
@@ -6,21 +7,33 @@ mod deps;
 mod git;
 mod go;
 mod init;
+mod installed;
 mod lock;
 mod lockfile;
+mod manifest;
+mod metadata;
+mod network;
 mod node;
 mod output;
+mod projects;
 mod python;
 mod ruby;
 mod rust;
+mod state;
 mod swift;
+mod sync;
+#[cfg(test)]
+mod test_http;
 mod update;
+mod version_req;
 
 use clap::Parser;
 use cli::{Cli, Command};
 use output::{
-    AddResult, CleanResult, InitAction, InitOutput, ListEntry, ListResult, RemoveResult,
-    SkipResult, UpdateCheckOutput, UpdateOutput,
+    AddResult, CacheEntryOutput, CacheListResult, CacheRemoveResult, CacheSizeResult,
+    CacheVerifyEntry, CacheVerifyResult, CleanResult, ConfigResolvedResult, ConfigValueOutput,
+    InitAction, InitOutput, ListEntry, ListResult, RemoveResult, SkipResult, SyncActionEntry,
+    SyncResult, UpdateCheckOutput, UpdateOutput,
 };
 
 fn main() {
@@ -28,10 +41,16 @@ fn main() {
     let json_output = cli.json;
     let dry_run = cli.dry_run;
 
-    // Check for updates periodically (skip for update command itself and JSON output)
+    // Offline mode comes from the flag or the config (a broken config is
+    // reported by the command that loads it)
+    let offline = cli.offline || config::Config::load().is_ok_and(|config| config.offline);
+    network::set_offline(offline);
+    metadata::set_refresh(cli.refresh);
+
+    // Check for updates periodically (skip for update command itself, JSON output and offline mode)
     // Capture the message now to avoid blocking on network after command completes
     let is_update_cmd = matches!(cli.command, Some(Command::Update { .. }));
-    let update_msg = if !is_update_cmd && !json_output {
+    let update_msg = if !is_update_cmd && !json_output && !offline {
         update::maybe_notify_update()
     } else {
         None
@@ -47,6 +66,9 @@ fn main() {
         Some(Command::List) => run_list(json_output),
         Some(Command::Context) => run_context(json_output),
         Some(Command::Clean) => run_clean(json_output, dry_run),
+        Some(Command::Sync) => run_sync(json_output, dry_run),
+        Some(Command::Cache { command }) => run_cache(command, json_output, dry_run),
+        Some(Command::Config { command }) => run_config(command, json_output),
         Some(Command::Update { check }) => run_update(check, json_output),
         None => {
             eprintln!("No command specified. Use --help for usage information.");
@@ -206,58 +228,190 @@ fn run_add(
     // Load configuration
     let config = config::Config::load()?;
 
+    // Resolve version: use explicit version, or look up from lockfile
+    let version_info = resolve_version(&spec)?;
+
+    let outcome = add_dependency(&spec, &version_info, &config, json_output, dry_run)?;
+
+    if json_output {
+        match outcome {
+            AddOutcome::Added(result) => output::print_json(&result),
+            AddOutcome::Skipped(result) => output::print_json(&result),
+        }
+    }
+
+    Ok(())
+}
+
+/// Outcome of adding a single dependency
+enum AddOutcome {
+    Added(AddResult),
+    Skipped(SkipResult),
+}
+
+/// Resolve the version of a dependency spec: explicit version, or lockfile lookup
+fn resolve_version(spec: &cli::DepSpec) -> Result<cli::VersionInfo, Box<dyn std::error::Error>> {
+    match spec.version.as_deref() {
+        // Explicit Go pseudo-versions pin a commit just like lockfile ones
+        Some(v) if spec.ecosystem == cli::Ecosystem::Go => Ok(go::version_info(v)),
+        Some(v) => Ok(cli::VersionInfo::Version(v.to_string())),
+        None => lookup_version(spec.ecosystem, &spec.package),
+    }
+}
+
+/// Fetch a dependency with a resolved version into the cache and link it into .deps/
+///
+/// Prints progress in text mode; JSON output is left to the caller.
+/// Records how the dependency was resolved in the .deps/ state file.
+fn add_dependency(
+    spec: &cli::DepSpec,
+    version_info: &cli::VersionInfo,
+    config: &config::Config,
+    json_output: bool,
+    dry_run: bool,
+) -> Result<AddOutcome, Box<dyn std::error::Error>> {
+    let ecosystem = spec.ecosystem;
+    let package = spec.package.as_str();
+
     // Verify cache is writable (fail fast) - skip in dry-run mode
     if !dry_run {
         cache::ensure_writable()?;
     }
 
-    // Resolve version: use explicit version, or look up from lockfile
-    let version_info = match spec.version.as_deref() {
-        Some(v) => cli::VersionInfo::Version(v.to_string()),
-        None => lookup_version(spec.ecosystem, &spec.package)?,
-    };
-
     // Handle different version types
-    match &version_info {
+    let result = match version_info {
         cli::VersionInfo::LocalPath { path } => {
             // Skip local path dependencies
-            if json_output {
-                output::print_json(&SkipResult::local_path(spec.ecosystem, &spec.package, path));
-            } else {
+            if !json_output {
                 let prefix = if dry_run { "[dry-run] " } else { "" };
                 println!(
                     "{}Skipping local dependency {} (path: {})",
-                    prefix, spec.package, path
+                    prefix, package, path
                 );
             }
-            return Ok(());
+            return Ok(AddOutcome::Skipped(SkipResult::local_path(
+                ecosystem, package, path,
+            )));
         }
         cli::VersionInfo::Git { url, commit } => {
             // Git dependency - clone from URL, use commit as version
             run_add_git_dep(
-                spec.ecosystem,
-                &spec.package,
+                ecosystem,
+                package,
                 url,
                 commit,
-                &config,
+                config,
                 json_output,
                 dry_run,
-            )?;
+            )?
         }
         cli::VersionInfo::Version(version) => {
             // Regular version - use registry detection
             run_add_registry_dep(
-                spec.ecosystem,
-                &spec.package,
-                version,
-                &config,
+                ecosystem,
+                package,
+                RegistryFetch::new(package, version),
+                config,
+                json_output,
+                dry_run,
+            )?
+        }
+        cli::VersionInfo::Revision { version, commit } => {
+            // Registry version pinned to a commit - detect the repo, check out the commit
+            run_add_registry_dep(
+                ecosystem,
+                package,
+                RegistryFetch::new(package, version).at_commit(Some(commit)),
+                config,
+                json_output,
+                dry_run,
+            )?
+        }
+        cli::VersionInfo::Replaced {
+            package: replacement,
+            version,
+            commit,
+        } => {
+            // Another package stands in for this one - fetch it, link it under this name
+            run_add_registry_dep(
+                ecosystem,
+                package,
+                RegistryFetch::new(replacement, version).at_commit(commit.as_deref()),
+                config,
                 json_output,
                 dry_run,
-            )?;
+            )?
         }
+    };
+
+    if !dry_run {
+        record_state(spec, &result, json_output);
     }
 
-    Ok(())
+    Ok(AddOutcome::Added(result))
+}
+
+/// Record the resolution of an added dependency in `.deps/.dotdeps-state.json`
+///
+/// Failing to write the state file only produces a warning: the dependency
+/// itself is already linked.
+fn record_state(spec: &cli::DepSpec, result: &AddResult, json_output: bool) {
+    let update = || -> Result<(), state::StateError> {
+        let mut state = state::State::load()?;
+
+        // Cached entries aren't re-cloned, so take the ref from the entry's metadata
+        // or the previous resolution
+        let resolved_ref = result
+            .cloned_ref
+            .clone()
+            .or_else(|| {
+                cache::package_dir(spec.ecosystem, &spec.package, &result.version)
+                    .ok()
+                    .and_then(|dir| cache::read_meta(&dir))
+                    .map(|meta| meta.resolved_ref)
+            })
+            .or_else(|| {
+                state
+                    .get(spec.ecosystem, &spec.package)
+                    .filter(|e| e.version == result.version)
+                    .and_then(|e| e.resolved_ref.clone())
+            });
+
+        let lockfile = match spec.version {
+            Some(_) => None,
+            None => lockfile_path(spec.ecosystem).map(|p| p.display().to_string()),
+        };
+
+        state.upsert(state::StateEntry {
+            ecosystem: spec.ecosystem.to_string(),
+            package: spec.package.clone(),
+            requested_version: spec.version.clone(),
+            version: result.version.clone(),
+            resolved_ref,
+            commit: result.commit.clone(),
+            repo_url: result.repo_url.clone(),
+            lockfile,
+        });
+        state.save()
+    };
+
+    if let Err(e) = update()
+        && !json_output
+    {
+        eprintln!("Warning: {}", e);
+    }
+}
+
+/// Path of the lockfile a dependency's version would be read from, if any
+fn lockfile_path(ecosystem: cli::Ecosystem) -> Option<std::path::PathBuf> {
+    match ecosystem {
+        cli::Ecosystem::Python => python::find_lockfile_path().ok(),
+        cli::Ecosystem::Node => node::find_lockfile_path().ok(),
+        cli::Ecosystem::Go => go::find_lockfile_path().ok(),
+        cli::Ecosystem::Rust => rust::find_lockfile_path().ok(),
+        cli::Ecosystem::Ruby => ruby::find_lockfile_path().ok(),
+        cli::Ecosystem::Swift => swift::find_lockfile_path().ok(),
+    }
 }
 
 /// Add a git dependency (URL + commit hash)
@@ -269,14 +423,13 @@ fn run_add_git_dep(
     config: &config::Config,
     json_output: bool,
     dry_run: bool,
-) -> Result<(), Box<dyn std::error::Error>> {
-    // For git deps, use the commit hash as the version
-    // Truncate long commit hashes for display/cache path
-    let version = if commit.len() > 12 {
-        &commit[..12]
-    } else {
-        commit
+) -> Result<AddResult, Box<dyn std::error::Error>> {
+    // For git deps, use the (truncated) commit hash as the version, as sync does
+    let git_version = cli::VersionInfo::Git {
+        url: url.to_string(),
+        commit: commit.to_string(),
     };
+    let version = git_version.cache_version().unwrap_or(commit);
 
     let prefix = if dry_run { "[dry-run] " } else { "" };
     let cache_path = cache::package_dir(ecosystem, package, version)?;
@@ -295,20 +448,35 @@ fn run_add_git_dep(
         }
         (false, Some(commit.to_string()))
     } else {
+        if network::is_offline() {
+            return Err(offline_error(ecosystem, package, version).into());
+        }
         if !json_output {
             println!("{}Fetching {} {} (git)...", prefix, package, version);
         }
 
         // Clone atomically with locking to prevent race conditions
-        let url_owned = url.to_string();
+        let url_owned = config.rewrite_git_url(url);
         let commit_owned = commit.to_string();
+        // The commit is pinned, so only a configured subdirectory has any effect
+        let mut location = git::RepoLocation::root(&url_owned);
+        // The version is a commit, so version-scoped overrides don't apply
+        if let Some(recipe) = config.package_override(ecosystem, package, None) {
+            recipe.apply_to(&mut location);
+        }
+
+        let missing_subdir: std::cell::Cell<Option<String>> = std::cell::Cell::new(None);
 
         let populate_result = cache::populate_atomically(
             ecosystem,
             package,
             version,
-            |temp_dir| -> Result<(), git::GitError> {
-                git::clone_at_commit(&url_owned, &commit_owned, temp_dir)?;
+            |temp_dir| -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+                let result = git::clone_at_commit(&url_owned, &commit_owned, temp_dir)?;
+                let (location, missing) =
+                    locate_in_checkout(ecosystem, package, location, temp_dir);
+                write_cache_meta(temp_dir, &location, &result)?;
+                missing_subdir.set(missing);
                 Ok(())
             },
         )?;
@@ -323,6 +491,9 @@ fn run_add_git_dep(
             cache::PopulateResult::Populated => {
                 if !json_output {
                     println!("  cloned at {}", commit);
+                    if let Some(subdir) = missing_subdir.take() {
+                        eprintln!("Warning: {}", missing_subdir_warning(&subdir, commit));
+                    }
                 }
 
                 // Run cache eviction if over limit
@@ -336,64 +507,491 @@ fn run_add_git_dep(
     // Calculate link path (but don't create in dry-run mode)
     let link_path = deps::link_path(ecosystem, package);
 
+    let mut subdir = None;
     if !dry_run {
-        // Create symlink in .deps/
-        deps::link(ecosystem, package, version)?;
+        // Create symlink in .deps/, pointing into the package's subdirectory if it has one
+        subdir = cache::read_meta(&cache_path).and_then(|meta| meta.subdir);
+        deps::link(ecosystem, package, &cache_path, subdir.as_deref())?;
     }
 
-    if json_output {
-        let mut result = AddResult::new(
-            ecosystem,
+    if !json_output {
+        println!("{}Created {}", prefix, link_path.display());
+        if let Some(ref subdir) = subdir {
+            println!(
+                "  linked to {}/, repository root at {}",
+                subdir,
+                cache_path.display()
+            );
+        }
+    }
+
+    let mut result = AddResult::new(
+        ecosystem,
+        package,
+        version,
+        &link_path.display().to_string(),
+        cached,
+    );
+    if let Some(ref cloned) = cloned_ref {
+        result = result.with_cloned_ref(cloned);
+    }
+    if dry_run {
+        result = result.with_dry_run();
+    } else {
+        result = with_checkout_provenance(result, &cache_path);
+    }
+
+    Ok(result)
+}
+
+/// Add the commit and remote URL of a cached checkout to an add result
+///
+/// Uses the entry's `.dotdeps-meta.json` when present, otherwise asks git.
+/// If the package lives in a subdirectory, the checkout root is added too, and
+/// the recorded fetch strategy labels entries that aren't git checkouts.
+fn with_checkout_provenance(mut result: AddResult, cache_path: &std::path::Path) -> AddResult {
+    let meta = cache::read_meta(cache_path);
+    let has_subdir = meta.as_ref().is_some_and(|m| m.subdir.is_some());
+    let strategy = meta.as_ref().map(|m| m.strategy);
+    let commit = meta
+        .as_ref()
+        .and_then(|m| m.commit.clone())
+        .or_else(|| git::head_commit(cache_path).ok());
+    let repo_url = meta
+        .map(|m| m.repo_url)
+        .or_else(|| git::remote_url(cache_path).ok());
+
+    if let Some(ref commit) = commit {
+        result = result.with_commit(commit);
+    }
+    if let Some(ref url) = repo_url {
+        result = result.with_repo_url(url);
+    }
+    if has_subdir {
+        result = result.with_repo_root(&cache_path.display().to_string());
+    }
+    if let Some(strategy) = strategy {
+        result = result.with_strategy(strategy);
+    }
+    result
+}
+
+/// Record where a fresh checkout came from in its `.dotdeps-meta.json`
+fn write_cache_meta(
+    dir: &std::path::Path,
+    location: &git::RepoLocation,
+    clone: &git::CloneResult,
+) -> Result<(), cache::CacheError> {
+    let meta = cache::CacheMeta::new(&location.url, &clone.cloned_ref, clone.used_default_branch)
+        .with_commit(git::head_commit(dir).ok())
+        .with_subdir(location.subdir.clone());
+    cache::write_meta(dir, &meta)
+}
+
+/// Clone a package's repository into `dir` and record it in `.dotdeps-meta.json`
+///
+/// Checks out `commit` if given, otherwise the release tag of `version`. If no
+/// tag matches, the published `artifact` is downloaded instead of falling back
+/// to the default branch.
+fn fetch_from_git(
+    ecosystem: cli::Ecosystem,
+    package: &str,
+    version: &str,
+    commit: Option<&str>,
+    location: &git::RepoLocation,
+    artifact: Option<&Artifact>,
+    dir: &std::path::Path,
+) -> Result<git::CloneResult, Box<dyn std::error::Error + Send + Sync>> {
+    let result = match (commit, artifact) {
+        (Some(commit), _) => git::clone_at_commit(&location.url, commit, dir)?,
+        (None, None) => git::clone(location, version, package, dir)?,
+        (None, Some(artifact)) => match git::clone_tag(location, version, package, dir)? {
+            Some(result) => result,
+            None => return fetch_artifact(artifact, package, version, dir),
+        },
+    };
+
+    let mut result = result;
+    let (location, missing_subdir) = locate_in_checkout(ecosystem, package, location.clone(), dir);
+    result.missing_subdir = missing_subdir;
+    write_cache_meta(dir, &location, &result)?;
+    Ok(result)
+}
+
+/// Fill in where a package lives inside a fresh checkout, if detection couldn't tell
+///
+/// Rust crates are often workspace members in a subdirectory of their
+/// repository; the member is found by the `name` in its Cargo.toml. A
+/// subdirectory the checkout doesn't have is dropped (so the repository root
+/// is linked) and returned alongside.
+fn locate_in_checkout(
+    ecosystem: cli::Ecosystem,
+    package: &str,
+    mut location: git::RepoLocation,
+    dir: &std::path::Path,
+) -> (git::RepoLocation, Option<String>) {
+    if location.subdir.is_none() && ecosystem == cli::Ecosystem::Rust {
+        location.subdir = rust::find_crate_dir(dir, package);
+    }
+    let missing = match &location.subdir {
+        Some(subdir) if !dir.join(subdir).is_dir() => location.subdir.take(),
+        _ => None,
+    };
+    (location, missing)
+}
+
+/// Warning for a package subdirectory that a fresh checkout doesn't have
+fn missing_subdir_warning(subdir: &str, cloned_ref: &str) -> String {
+    format!(
+        "Directory {} not found at {}, linked the repository root",
+        subdir, cloned_ref
+    )
+}
+
+/// Download a Go module zip from a module proxy into `dir`
+///
+/// Records the proxy's reported origin (if any) in `.dotdeps-meta.json`.
+fn fetch_from_go_proxy(
+    proxy: &str,
+    package: &str,
+    version: &str,
+    dir: &std::path::Path,
+) -> Result<git::CloneResult, Box<dyn std::error::Error + Send + Sync>> {
+    let fetched = go::fetch_module(proxy, package, version, dir)?;
+    let resolved_ref = format!("v{}", version);
+    let meta = cache::CacheMeta::new(
+        fetched.origin_url.as_deref().unwrap_or(proxy),
+        &resolved_ref,
+        false,
+    )
+    .with_commit(fetched.commit)
+    .with_strategy(cache::FetchStrategy::GoProxy);
+    cache::write_meta(dir, &meta)?;
+
+    Ok(git::CloneResult {
+        used_default_branch: false,
+        cloned_ref: resolved_ref,
+        nearest_tags: Vec::new(),
+        missing_subdir: None,
+    })
+}
+
+/// A file published to a package registry that can stand in for a git checkout
+enum Artifact {
+    /// `.crate` file from crates.io, with the name and checksum Cargo.lock records (if locked)
+    Crate {
+        base_url: String,
+        registry_url: String,
+        locked: Option<rust::LockedCrate>,
+    },
+    /// sdist or pure-Python wheel from PyPI, with the hashes the lockfile records
+    PyPi {
+        base_url: String,
+        hashes: Vec<python::FileHash>,
+    },
+    /// Tarball from the npm registry, with the URL and integrity the lockfile records
+    Npm {
+        registry_url: String,
+        locked: node::LockedTarball,
+    },
+    /// `.gem` file from a gem source, with the name and checksum Gemfile.lock records (if locked)
+    Gem {
+        source_url: String,
+        registry_url: String,
+        locked: Option<ruby::LockedGem>,
+    },
+}
+
+/// The published artifact of a registry dependency, if its ecosystem has one
+///
+/// Used when the repository has no tag for the version or can't be detected,
+/// so that the source matches the published package instead of the default
+/// branch, or first if the package's preferred strategy is `registry-artifact`.
+fn registry_artifact(
+    ecosystem: cli::Ecosystem,
+    package: &str,
+    version: &str,
+    config: &config::Config,
+) -> Option<Artifact> {
+    match ecosystem {
+        cli::Ecosystem::Rust => Some(Artifact::Crate {
+            base_url: rust::download_url(config.crate_download_url.as_deref()),
+            registry_url: config.registry_url_or(ecosystem, rust::DEFAULT_REGISTRY_URL),
+            locked: rust::find_locked_crate(package, version),
+        }),
+        cli::Ecosystem::Python => Some(Artifact::PyPi {
+            base_url: config.registry_url_or(ecosystem, python::DEFAULT_REGISTRY_URL),
+            hashes: python::find_file_hashes(package, version),
+        }),
+        cli::Ecosystem::Node => Some(Artifact::Npm {
+            registry_url: config.registry_url_or(ecosystem, node::DEFAULT_REGISTRY_URL),
+            locked: node::find_locked_tarball(package, version),
+        }),
+        cli::Ecosystem::Ruby => Some(Artifact::Gem {
+            // The registry (or its mirror) serves gems unless a gem source is configured
+            source_url: match config.gem_source.as_deref() {
+                Some(source) => ruby::gem_source(Some(source)),
+                None => config.registry_url_or(ecosystem, ruby::DEFAULT_REGISTRY_URL),
+            },
+            registry_url: config.registry_url_or(ecosystem, ruby::DEFAULT_REGISTRY_URL),
+            locked: ruby::find_locked_gem(package, version),
+        }),
+        _ => None,
+    }
+}
+
+/// Download and unpack a registry artifact into `dir`
+///
+/// Records the download URL and checksum in `.dotdeps-meta.json`.
+fn fetch_artifact(
+    artifact: &Artifact,
+    package: &str,
+    version: &str,
+    dir: &std::path::Path,
+) -> Result<git::CloneResult, Box<dyn std::error::Error + Send + Sync>> {
+    let (url, file_name, checksum, commit) = match artifact {
+        Artifact::Crate {
+            base_url,
+            registry_url,
+            locked,
+        } => {
+            // Download paths use the published capitalization, which the
+            // (lowercased) package name may have lost
+            let name = match locked {
+                Some(locked) => locked.name.clone(),
+                None => rust::canonical_name(registry_url, package)
+                    .unwrap_or_else(|| package.to_string()),
+            };
+            let checksum = locked
+                .as_ref()
+                .and_then(|locked| locked.checksum.as_deref());
+            let downloaded = rust::download_crate(base_url, &name, version, checksum, dir)?;
+            (
+                downloaded.url,
+                downloaded.file_name,
+                downloaded.checksum,
+                downloaded.commit,
+            )
+        }
+        Artifact::PyPi { base_url, hashes } => {
+            let downloaded = python::download_package(base_url, package, version, hashes, dir)?;
+            (
+                downloaded.url,
+                downloaded.file_name,
+                downloaded.checksum,
+                None,
+            )
+        }
+        Artifact::Npm {
+            registry_url,
+            locked,
+        } => {
+            let downloaded = node::download_tarball(registry_url, package, version, locked, dir)?;
+            (
+                downloaded.url,
+                downloaded.file_name,
+                downloaded.integrity,
+                None,
+            )
+        }
+        Artifact::Gem {
+            source_url,
+            registry_url,
+            locked,
+        } => {
+            // As for crates, the download path needs the published capitalization
+            let name = match locked {
+                Some(locked) => locked.name.clone(),
+                None => ruby::canonical_name(registry_url, package)
+                    .unwrap_or_else(|| package.to_string()),
+            };
+            let checksum = locked
+                .as_ref()
+                .and_then(|locked| locked.checksum.as_deref());
+            let downloaded = ruby::download_gem(source_url, &name, version, checksum, dir)?;
+            (
+                downloaded.url,
+                downloaded.file_name,
+                downloaded.checksum,
+                None,
+            )
+        }
+    };
+
+    let meta = cache::CacheMeta::new(&url, &file_name, false)
+        .with_commit(commit)
+        .with_checksum(Some(checksum))
+        .with_strategy(cache::FetchStrategy::RegistryArtifact);
+    cache::write_meta(dir, &meta)?;
+
+    Ok(git::CloneResult {
+        used_default_branch: false,
+        cloned_ref: file_name,
+        nearest_tags: Vec::new(),
+        missing_subdir: None,
+    })
+}
+
+/// Error for a package that is neither cached nor installed, in offline mode
+fn offline_error(ecosystem: cli::Ecosystem, package: &str, version: &str) -> network::NetworkError {
+    network::NetworkError::Offline {
+        resource: format!("{}:{}@{}", ecosystem, package, version),
+    }
+}
+
+/// Sources of `package@version` already installed by the ecosystem's package manager
+fn find_installed(
+    ecosystem: cli::Ecosystem,
+    package: &str,
+    version: &str,
+) -> Option<installed::InstalledSource> {
+    match ecosystem {
+        cli::Ecosystem::Python => python::find_installed(package, version),
+        cli::Ecosystem::Node => node::find_installed(package, version),
+        cli::Ecosystem::Go => go::find_installed(package, version),
+        cli::Ecosystem::Rust => rust::find_installed(package, version),
+        cli::Ecosystem::Ruby => ruby::find_installed(package, version),
+        cli::Ecosystem::Swift => None,
+    }
+}
+
+/// Copy installed sources into `dir`
+///
+/// Records the directory they were copied from in `.dotdeps-meta.json`.
+fn fetch_installed(
+    installed: &installed::InstalledSource,
+    version: &str,
+    dir: &std::path::Path,
+) -> Result<git::CloneResult, Box<dyn std::error::Error + Send + Sync>> {
+    installed.copy_into(dir)?;
+
+    let meta = cache::CacheMeta::new(&installed.path.display().to_string(), version, false)
+        .with_strategy(cache::FetchStrategy::Installed);
+    cache::write_meta(dir, &meta)?;
+
+    Ok(git::CloneResult {
+        used_default_branch: false,
+        cloned_ref: version.to_string(),
+        nearest_tags: Vec::new(),
+        missing_subdir: None,
+    })
+}
+
+/// What to fetch for a registry dependency
+struct RegistryFetch<'a> {
+    /// Package whose source is fetched and cached (differs from the linked
+    /// package when it is replaced, e.g. by a Go `replace` directive)
+    package: &'a str,
+    version: &'a str,
+    /// Commit to check out instead of resolving a tag
+    commit: Option<&'a str>,
+}
+
+impl<'a> RegistryFetch<'a> {
+    fn new(package: &'a str, version: &'a str) -> Self {
+        Self {
             package,
             version,
-            &link_path.display().to_string(),
-            cached,
-        );
-        if let Some(ref cloned) = cloned_ref {
-            result = result.with_cloned_ref(cloned);
-        }
-        if dry_run {
-            result = result.with_dry_run();
+            commit: None,
         }
-        output::print_json(&result);
-    } else {
-        println!("{}Created {}", prefix, link_path.display());
     }
 
-    Ok(())
+    fn at_commit(mut self, commit: Option<&'a str>) -> Self {
+        self.commit = commit;
+        self
+    }
 }
 
 /// Add a regular registry dependency (version string)
+///
+/// Fetches `fetch.package` into the cache and links it into .deps/ as `package`.
+/// If `fetch.commit` is given, that commit is checked out instead of resolving a tag.
 fn run_add_registry_dep(
     ecosystem: cli::Ecosystem,
     package: &str,
-    version: &str,
+    fetch: RegistryFetch<'_>,
     config: &config::Config,
     json_output: bool,
     dry_run: bool,
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<AddResult, Box<dyn std::error::Error>> {
+    let RegistryFetch {
+        package: source_package,
+        version,
+        commit,
+    } = fetch;
     let prefix = if dry_run { "[dry-run] " } else { "" };
-    let cache_path = cache::package_dir(ecosystem, package, version)?;
+    let cache_path = cache::package_dir(ecosystem, source_package, version)?;
+    let label = if source_package == package {
+        package.to_string()
+    } else {
+        format!("{} (replacing {})", source_package, package)
+    };
+
+    // Existing tags near the version, when the default branch was cloned instead
+    let mut nearest_tags = Vec::new();
 
     // Check if already cached (fast path without locking)
-    let (cached, cloned_ref, warning) = if cache::exists(ecosystem, package, version)? {
+    let (cached, cloned_ref, warning) = if cache::exists(ecosystem, source_package, version)? {
         if !json_output {
-            println!("{}Using cached {} {}", prefix, package, version);
+            println!("{}Using cached {} {}", prefix, label, version);
         }
         (true, None, None)
     } else if dry_run {
         // In dry-run mode, skip actual cloning
         if !json_output {
-            println!("{}Fetching {} {}...", prefix, package, version);
+            println!("{}Fetching {} {}...", prefix, label, version);
             println!("{}  cloned at {}", prefix, version);
         }
         (false, None, None)
     } else {
-        // Detect repository URL (check config override first)
-        let repo_url = detect_repo_url(ecosystem, package, config)?;
+        // Published artifact to fall back on (pinned commits are always cloned)
+        let mut artifact = commit
+            .is_none()
+            .then(|| registry_artifact(ecosystem, source_package, version, config))
+            .flatten();
+
+        // A config override decides where the source comes from; without one,
+        // use sources the package manager already installed. Otherwise (unless
+        // offline) detect the source location, unless the published package
+        // is preferred
+        let package_override = config
+            .package_override(ecosystem, source_package, Some(version))
+            .filter(|recipe| recipe.is_set());
+        let prefer_artifact = config.preferred_strategy(ecosystem, source_package, Some(version))
+            == config::Strategy::RegistryArtifact;
+        let installed = package_override
+            .is_none()
+            .then(|| find_installed(ecosystem, source_package, version))
+            .flatten();
+        let mut source = if let Some(installed) = installed {
+            Source::Installed(installed)
+        } else if network::is_offline() {
+            return Err(offline_error(ecosystem, source_package, version).into());
+        } else if prefer_artifact && let Some(artifact) = artifact.take() {
+            Source::Artifact(artifact)
+        } else {
+            match detect_source(ecosystem, source_package, version, config) {
+                Ok(source) => source,
+                Err(e) => match artifact.take() {
+                    Some(artifact) => {
+                        if !json_output {
+                            eprintln!("Warning: {}. Using the published package instead.", e);
+                        }
+                        Source::Artifact(artifact)
+                    }
+                    None => return Err(e),
+                },
+            }
+        };
+        if let Source::Git(location) = &mut source {
+            location.url = config.rewrite_git_url(&location.url);
+            if let Some(recipe) = &package_override {
+                recipe.apply_to(location);
+            }
+        }
 
         if !json_output {
-            println!("{}Fetching {} {}...", prefix, package, version);
+            println!("{}Fetching {} {}...", prefix, label, version);
         }
 
         // Clone atomically with locking to prevent race conditions
@@ -402,10 +1000,27 @@ fn run_add_registry_dep(
 
         let populate_result = cache::populate_atomically(
             ecosystem,
-            package,
+            source_package,
             version,
-            |temp_dir| -> Result<(), git::GitError> {
-                let result = git::clone(&repo_url, version, package, temp_dir)?;
+            |temp_dir| -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+                let result = match &source {
+                    Source::Git(location) => fetch_from_git(
+                        ecosystem,
+                        source_package,
+                        version,
+                        commit,
+                        location,
+                        artifact.as_ref(),
+                        temp_dir,
+                    )?,
+                    Source::GoProxy { proxy } => {
+                        fetch_from_go_proxy(proxy, source_package, version, temp_dir)?
+                    }
+                    Source::Artifact(artifact) => {
+                        fetch_artifact(artifact, source_package, version, temp_dir)?
+                    }
+                    Source::Installed(installed) => fetch_installed(installed, version, temp_dir)?,
+                };
                 clone_result.set(Some(result));
                 Ok(())
             },
@@ -421,21 +1036,37 @@ fn run_add_registry_dep(
             cache::PopulateResult::Populated => {
                 let result = clone_result.take().expect("clone_result should be set");
 
-                let warning = if result.used_default_branch {
-                    let msg = format!(
+                let mut warnings = Vec::new();
+                if result.used_default_branch {
+                    let mut msg = format!(
                         "No tag found for version {}, cloned {}",
                         version, result.cloned_ref
                     );
-                    if !json_output {
-                        eprintln!("Warning: {}", msg);
+                    if !result.nearest_tags.is_empty() {
+                        msg.push_str(&format!(
+                            " (nearest tags: {})",
+                            result.nearest_tags.join(", ")
+                        ));
                     }
-                    Some(msg)
-                } else {
-                    if !json_output {
-                        println!("  cloned at {}", result.cloned_ref);
+                    nearest_tags = result.nearest_tags.clone();
+                    warnings.push(msg);
+                } else if !json_output {
+                    match &source {
+                        Source::Installed(installed) => {
+                            println!("  copied from {}", installed.path.display())
+                        }
+                        _ => println!("  cloned at {}", result.cloned_ref),
                     }
-                    None
-                };
+                }
+                if let Some(ref subdir) = result.missing_subdir {
+                    warnings.push(missing_subdir_warning(subdir, &result.cloned_ref));
+                }
+                if !json_output {
+                    for msg in &warnings {
+                        eprintln!("Warning: {}", msg);
+                    }
+                }
+                let warning = (!warnings.is_empty()).then(|| warnings.join("; "));
 
                 // Run cache eviction if over limit
                 run_cache_eviction(config, &cache_path, json_output)?;
@@ -448,34 +1079,45 @@ fn run_add_registry_dep(
     // Calculate link path (but don't create in dry-run mode)
     let link_path = deps::link_path(ecosystem, package);
 
+    let mut subdir = None;
     if !dry_run {
-        // Create symlink in .deps/
-        deps::link(ecosystem, package, version)?;
+        // Create symlink in .deps/, pointing into the package's subdirectory if it has one
+        subdir = cache::read_meta(&cache_path).and_then(|meta| meta.subdir);
+        deps::link(ecosystem, package, &cache_path, subdir.as_deref())?;
     }
 
-    if json_output {
-        let mut result = AddResult::new(
-            ecosystem,
-            package,
-            version,
-            &link_path.display().to_string(),
-            cached,
-        );
-        if let Some(ref cloned) = cloned_ref {
-            result = result.with_cloned_ref(cloned);
-        }
-        if let Some(ref warn) = warning {
-            result = result.with_warning(warn);
-        }
-        if dry_run {
-            result = result.with_dry_run();
+    if !json_output {
+        println!("{}Created {}", prefix, link_path.display());
+        if let Some(ref subdir) = subdir {
+            println!(
+                "  linked to {}/, repository root at {}",
+                subdir,
+                cache_path.display()
+            );
         }
-        output::print_json(&result);
+    }
+
+    let mut result = AddResult::new(
+        ecosystem,
+        package,
+        version,
+        &link_path.display().to_string(),
+        cached,
+    );
+    if let Some(ref cloned) = cloned_ref {
+        result = result.with_cloned_ref(cloned);
+    }
+    if let Some(ref warn) = warning {
+        result = result.with_warning(warn);
+    }
+    result = result.with_nearest_tags(nearest_tags);
+    if dry_run {
+        result = result.with_dry_run();
     } else {
-        println!("{}Created {}", prefix, link_path.display());
+        result = with_checkout_provenance(result, &cache_path);
     }
 
-    Ok(())
+    Ok(result)
 }
 
 /// Run cache eviction if cache exceeds configured limit
@@ -532,64 +1174,97 @@ fn lookup_version(
     }
 }
 
-/// Detect the repository URL for a package
+/// Where a package's source is fetched from
+enum Source {
+    /// Clone a git repository
+    Git(git::RepoLocation),
+    /// Download the module zip from a Go module proxy
+    GoProxy { proxy: String },
+    /// Download the package file published to the registry
+    Artifact(Artifact),
+    /// Copy sources the package manager already installed
+    Installed(installed::InstalledSource),
+}
+
+/// Detect where a package's source lives
 ///
-/// Checks config override first, then falls back to ecosystem-specific detection.
-fn detect_repo_url(
+/// Checks config override first (including overrides scoped to `version`),
+/// then falls back to ecosystem-specific detection.
+fn detect_source(
     ecosystem: cli::Ecosystem,
     package: &str,
+    version: &str,
     config: &config::Config,
-) -> Result<String, Box<dyn std::error::Error>> {
+) -> Result<Source, Box<dyn std::error::Error>> {
     // Check for config override first
-    if let Some(repo_url) = config.repo_override(ecosystem, package) {
-        return Ok(repo_url.to_string());
+    if let Some(repo_url) = config.repo_override(ecosystem, package, Some(version)) {
+        return Ok(Source::Git(git::RepoLocation::root(&repo_url)));
     }
 
-    // Fall back to ecosystem-specific detection
-    match ecosystem {
-        cli::Ecosystem::Python => python::detect_repo_url(package).map_err(|e| e.into()),
-        cli::Ecosystem::Node => node::detect_repo_url(package).map_err(|e| e.into()),
-        cli::Ecosystem::Go => detect_go_repo_url(package),
-        cli::Ecosystem::Rust => rust::detect_repo_url(package).map_err(|e| e.into()),
-        cli::Ecosystem::Ruby => ruby::detect_repo_url(package).map_err(|e| e.into()),
-        cli::Ecosystem::Swift => swift::detect_repo_url(package).map_err(|e| e.into()),
+    // Fall back to ecosystem-specific detection, asking the configured registry mirror if any
+    let default_registry = match ecosystem {
+        cli::Ecosystem::Python => python::DEFAULT_REGISTRY_URL,
+        cli::Ecosystem::Node => node::DEFAULT_REGISTRY_URL,
+        cli::Ecosystem::Rust => rust::DEFAULT_REGISTRY_URL,
+        cli::Ecosystem::Ruby => ruby::DEFAULT_REGISTRY_URL,
+        cli::Ecosystem::Go => return detect_go_source(package, config),
+        cli::Ecosystem::Swift => {
+            let repo_url = swift::detect_repo_url(package)?;
+            return Ok(Source::Git(git::RepoLocation::root(&repo_url)));
+        }
+    };
+    let registry = config.registry_url_or(ecosystem, default_registry);
+
+    // A recent answer from the same registry saves the request
+    if let Some(location) = metadata::load(ecosystem, package, &registry, config.metadata_ttl()) {
+        return Ok(Source::Git(location));
     }
+
+    let detected = match ecosystem {
+        cli::Ecosystem::Python => python::detect_repo(&registry, package)?,
+        cli::Ecosystem::Node => node::detect_repo(&registry, package)?,
+        cli::Ecosystem::Rust => rust::detect_repo(&registry, package)?,
+        cli::Ecosystem::Ruby => ruby::detect_repo(&registry, package)?,
+        cli::Ecosystem::Go | cli::Ecosystem::Swift => unreachable!("no package registry"),
+    };
+    // Failing to record it only costs another request next time
+    let _ = metadata::store(ecosystem, package, &registry, &detected);
+    Ok(Source::Git(detected.location))
 }
 
-/// Detect repository URL for Go modules
+/// Detect where a Go module's source lives
 ///
-/// Go modules have the repo URL embedded in their module path:
-/// - github.com/org/repo -> https://github.com/org/repo.git
-/// - golang.org/x/name -> https://go.googlesource.com/name.git
-fn detect_go_repo_url(package: &str) -> Result<String, Box<dyn std::error::Error>> {
-    if package.starts_with("github.com/") {
-        // Strip any version suffix like /v2, /v3, etc.
-        let repo_path = strip_go_version_suffix(package);
-        Ok(format!("https://{}.git", repo_path))
-    } else if package.starts_with("golang.org/x/") {
-        let name = package.strip_prefix("golang.org/x/").unwrap();
-        // Also strip version suffix from golang.org packages
-        let name = strip_go_version_suffix(name);
-        Ok(format!("https://go.googlesource.com/{}.git", name))
-    } else {
-        Err(format!(
-            "Repository URL not found for go:{}. Add override to ~/.config/dotdeps/config.json",
-            package
-        )
-        .into())
+/// Module paths on well-known hosts and gopkg.in embed the repository; nested
+/// modules of multi-module repositories live in a subdirectory (see
+/// `go::locate_known_host`). Other paths are resolved through their `go-import`
+/// meta tag. Modules without a git repository are downloaded from the module proxy.
+fn detect_go_source(
+    package: &str,
+    config: &config::Config,
+) -> Result<Source, Box<dyn std::error::Error>> {
+    if let Some(location) = go::locate_known_host(package).or_else(|| go::locate_gopkg_in(package))
+    {
+        return Ok(Source::Git(location));
     }
-}
 
-/// Strip Go module version suffix (/v2, /v3, etc.)
-fn strip_go_version_suffix(path: &str) -> &str {
-    // Check for /vN suffix where N is a digit
-    if let Some(idx) = path.rfind("/v") {
-        let suffix = &path[idx + 2..];
-        if !suffix.is_empty() && suffix.chars().all(|c| c.is_ascii_digit()) {
-            return &path[..idx];
+    match go::discover(package) {
+        Ok(import) if import.vcs == "git" => return Ok(Source::Git(import.location(package))),
+        Ok(import) if import.vcs == "mod" => {
+            return Ok(Source::GoProxy {
+                proxy: import.repo_root,
+            });
         }
+        _ => {}
+    }
+
+    match go::proxy_url(config.go_proxy.as_deref()) {
+        Some(proxy) => Ok(Source::GoProxy { proxy }),
+        None => Err(format!(
+            "Repository URL not found for go:{} and GOPROXY is off. Add override to ~/.config/dotdeps/config.json",
+            package
+        )
+        .into()),
     }
-    path
 }
 
 fn run_remove(
@@ -626,7 +1301,17 @@ fn run_list(json_output: bool) -> Result<(), Box<dyn std::error::Error>> {
     if json_output {
         let list_entries: Vec<ListEntry> = entries
             .iter()
-            .map(|e| ListEntry::new(e.ecosystem, &e.package, &e.version, e.is_broken))
+            .map(|e| {
+                let entry = ListEntry::new(e.ecosystem, &e.package, &e.version, e.is_broken);
+                match &e.resolution {
+                    Some(s) => entry.with_provenance(
+                        s.resolved_ref.as_deref(),
+                        s.commit.as_deref(),
+                        s.repo_url.as_deref(),
+                    ),
+                    None => entry,
+                }
+            })
             .collect();
         output::print_json(&ListResult {
             dependencies: list_entries,
@@ -641,9 +1326,12 @@ fn run_list(json_output: bool) -> Result<(), Box<dyn std::error::Error>> {
 
     for entry in entries {
         let status = if entry.is_broken {
-            " (broken - cache evicted)"
+            " (broken - cache evicted)".to_string()
         } else {
-            ""
+            match entry.resolution.as_ref().and_then(|s| s.provenance()) {
+                Some(provenance) => format!(" ({})", provenance),
+                None => String::new(),
+            }
         };
         println!(
             "{}:{}@{}{}",
@@ -681,6 +1369,497 @@ fn run_clean(json_output: bool, dry_run: bool) -> Result<(), Box<dyn std::error:
     Ok(())
 }
 
+fn run_sync(json_output: bool, dry_run: bool) -> Result<(), Box<dyn std::error::Error>> {
+    use sync::SyncAction;
+
+    let manifest = manifest::Manifest::load()?;
+    let config = config::Config::load()?;
+    let prefix = if dry_run { "[dry-run] " } else { "" };
+
+    // Resolve every manifest entry up front so the plan reflects lockfile changes
+    let desired = manifest
+        .dependencies
+        .iter()
+        .map(|spec| sync::DesiredDep {
+            ecosystem: spec.ecosystem,
+            package: spec.package.clone(),
+            resolved: resolve_version(spec).map_err(|e| e.to_string()),
+        })
+        .collect();
+
+    let current = deps::list()?;
+    let plan = sync::plan(desired, &current);
+
+    let mut entries = Vec::new();
+    let mut failures = 0;
+
+    for planned in &plan {
+        let mut entry =
+            SyncActionEntry::new(planned.ecosystem, &planned.package, planned.action.name());
+        if let Some(version) = planned.version() {
+            entry = entry.with_version(version);
+        }
+
+        match &planned.action {
+            SyncAction::Add | SyncAction::Relink { .. } => {
+                if let SyncAction::Relink { from } = &planned.action {
+                    entry = entry.with_previous_version(from);
+                    if !json_output {
+                        println!(
+                            "{}Relinking {}:{} ({} -> {})",
+                            prefix,
+                            planned.ecosystem,
+                            planned.package,
+                            from,
+                            planned.version().unwrap_or("unknown")
+                        );
+                    }
+                }
+
+                let version_info = planned
+                    .version_info
+                    .as_ref()
+                    .expect("add/relink actions have a resolved version");
+                let spec = manifest
+                    .dependencies
+                    .iter()
+                    .find(|d| d.ecosystem == planned.ecosystem && d.package == planned.package)
+                    .expect("add/relink actions come from the manifest");
+                if let Err(e) = add_dependency(spec, version_info, &config, json_output, dry_run) {
+                    failures += 1;
+                    if !json_output {
+                        eprintln!(
+                            "Error: failed to sync {}:{}: {}",
+                            planned.ecosystem, planned.package, e
+                        );
+                    }
+                    entry = entry.with_error(&e.to_string());
+                }
+            }
+            SyncAction::Remove => {
+                if !dry_run {
+                    deps::remove(planned.ecosystem, &planned.package)?;
+                }
+                if !json_output {
+                    println!(
+                        "{}Removed {}:{} (not in {})",
+                        prefix,
+                        planned.ecosystem,
+                        planned.package,
+                        manifest::MANIFEST_FILE
+                    );
+                }
+            }
+            SyncAction::Unchanged => {
+                if !json_output {
+                    println!(
+                        "{}:{}@{} is up to date",
+                        planned.ecosystem,
+                        planned.package,
+                        planned.version().unwrap_or("unknown")
+                    );
+                }
+            }
+            SyncAction::Skip { path } => {
+                if !json_output {
+                    println!(
+                        "{}Skipping local dependency {} (path: {})",
+                        prefix, planned.package, path
+                    );
+                }
+            }
+            SyncAction::Failed { message } => {
+                failures += 1;
+                if !json_output {
+                    eprintln!(
+                        "Error: failed to sync {}:{}: {}",
+                        planned.ecosystem, planned.package, message
+                    );
+                }
+                entry = entry.with_error(message);
+            }
+        }
+
+        entries.push(entry);
+    }
+
+    if json_output {
+        output::print_json(&SyncResult {
+            actions: entries,
+            dry_run,
+        });
+    } else if plan.is_empty() {
+        println!(
+            "Nothing to sync. {} lists no dependencies.",
+            manifest::MANIFEST_FILE
+        );
+    }
+
+    if failures > 0 {
+        return Err(format!("{} dependencies failed to sync", failures).into());
+    }
+
+    Ok(())
+}
+
+fn run_cache(
+    command: cli::CacheCommand,
+    json_output: bool,
+    dry_run: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use cli::CacheCommand;
+
+    match command {
+        CacheCommand::Ls => run_cache_ls(json_output),
+        CacheCommand::Size => run_cache_size(json_output),
+        CacheCommand::Prune { older_than } => {
+            let cutoff = std::time::SystemTime::now()
+                .checked_sub(older_than)
+                .unwrap_or(std::time::UNIX_EPOCH);
+            let entries = cache::entries_accessed_before(cache::list_entries()?, cutoff);
+            remove_cache_entries(entries, json_output, dry_run)
+        }
+        CacheCommand::Rm { spec } => {
+            let entries = cache::entries_for_package(
+                cache::list_entries()?,
+                spec.ecosystem,
+                &spec.package,
+                spec.version.as_deref(),
+            );
+            if entries.is_empty() && !json_output {
+                println!("No cached entries for {}", spec);
+                return Ok(());
+            }
+            remove_cache_entries(entries, json_output, dry_run)
+        }
+        CacheCommand::Gc => {
+            let config = config::Config::load()?;
+            let limit = config.cache_limit_bytes();
+            if limit == 0 {
+                if !json_output {
+                    println!("No cache limit configured (cache_limit_gb is 0). Nothing to do.");
+                    return Ok(());
+                }
+                return remove_cache_entries(Vec::new(), json_output, dry_run);
+            }
+            let entries = cache::plan_eviction(cache::list_entries()?, limit, None);
+            remove_cache_entries(entries, json_output, dry_run)
+        }
+        CacheCommand::Verify => run_cache_verify(json_output),
+    }
+}
+
+/// Build the output entry for a cache entry
+fn cache_entry_output(
+    entry: &cache::CacheEntry,
+    links: &std::collections::HashMap<std::path::PathBuf, Vec<std::path::PathBuf>>,
+) -> CacheEntryOutput {
+    let last_accessed = entry
+        .accessed
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    let mut output = CacheEntryOutput::new(
+        &entry.ecosystem,
+        &entry.package,
+        &entry.version,
+        &entry.path.display().to_string(),
+        entry.size,
+        last_accessed,
+    );
+    if let Some(meta) = &entry.meta {
+        output = output.with_provenance(&meta.resolved_ref, meta.commit.as_deref());
+    }
+
+    let canonical = entry
+        .path
+        .canonicalize()
+        .unwrap_or_else(|_| entry.path.clone());
+    // Links may point into a subdirectory of the entry
+    let mut projects: Vec<String> = links
+        .iter()
+        .filter(|(target, _)| target.starts_with(&canonical))
+        .flat_map(|(_, projects)| projects.iter().map(|p| p.display().to_string()))
+        .collect();
+    projects.sort();
+    projects.dedup();
+    if !projects.is_empty() {
+        output = output.with_projects(projects);
+    }
+
+    output
+}
+
+/// Describe how long ago a Unix timestamp was (e.g., "3 days ago")
+fn format_age(timestamp: u64) -> String {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let secs = now.saturating_sub(timestamp);
+
+    let (amount, unit) = match secs {
+        0..60 => return "just now".to_string(),
+        60..3600 => (secs / 60, "minute"),
+        3600..86400 => (secs / 3600, "hour"),
+        _ => (secs / 86400, "day"),
+    };
+    let plural = if amount == 1 { "" } else { "s" };
+    format!("{} {}{} ago", amount, unit, plural)
+}
+
+fn run_cache_ls(json_output: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let entries = cache::list_entries()?;
+    let links = projects::links_by_entry()?;
+
+    let outputs: Vec<CacheEntryOutput> = entries
+        .iter()
+        .map(|e| cache_entry_output(e, &links))
+        .collect();
+    let total_size = outputs.iter().map(|e| e.size).sum();
+
+    if json_output {
+        output::print_json(&CacheListResult {
+            entries: outputs,
+            total_size,
+        });
+        return Ok(());
+    }
+
+    if outputs.is_empty() {
+        println!("Cache is empty");
+        return Ok(());
+    }
+
+    for entry in &outputs {
+        let provenance = match (&entry.resolved_ref, &entry.commit) {
+            (Some(r), Some(c)) => format!(", {} @ {}", r, &c[..c.len().min(12)]),
+            (Some(r), None) => format!(", {}", r),
+            _ => String::new(),
+        };
+        println!(
+            "{}:{}@{}  {}  accessed {}{}",
+            entry.ecosystem,
+            entry.package,
+            entry.version,
+            output::format_size(entry.size),
+            format_age(entry.last_accessed),
+            provenance
+        );
+        for project in &entry.projects {
+            println!("    used by {}", project);
+        }
+    }
+    println!(
+        "\n{} entries, {} total",
+        outputs.len(),
+        output::format_size(total_size)
+    );
+
+    Ok(())
+}
+
+fn run_cache_size(json_output: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let config = config::Config::load()?;
+    let entries = cache::list_entries()?;
+    let total_size: u64 = entries.iter().map(|e| e.size).sum();
+    let limit = Some(config.cache_limit_bytes()).filter(|l| *l > 0);
+    let path = cache::base_dir()?;
+
+    if json_output {
+        output::print_json(&CacheSizeResult {
+            path: path.display().to_string(),
+            entries: entries.len(),
+            total_size,
+            limit,
+        });
+        return Ok(());
+    }
+
+    let limit_text = match limit {
+        Some(l) => format!(" (limit {})", output::format_size(l)),
+        None => " (no limit)".to_string(),
+    };
+    println!(
+        "{}: {} entries, {}{}",
+        path.display(),
+        entries.len(),
+        output::format_size(total_size),
+        limit_text
+    );
+
+    Ok(())
+}
+
+/// Remove the given cache entries (or report what would be removed in dry-run mode)
+fn remove_cache_entries(
+    entries: Vec<cache::CacheEntry>,
+    json_output: bool,
+    dry_run: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let prefix = if dry_run { "[dry-run] " } else { "" };
+    let links = projects::links_by_entry()?;
+    let mut removed = Vec::new();
+
+    for entry in &entries {
+        let output = cache_entry_output(entry, &links);
+        if !dry_run {
+            cache::remove_entry(&entry.path)?;
+        }
+        if !json_output {
+            println!(
+                "{}Removed {}:{}@{} ({})",
+                prefix,
+                entry.ecosystem,
+                entry.package,
+                entry.version,
+                output::format_size(entry.size)
+            );
+            for project in &output.projects {
+                println!("    was linked from {}", project);
+            }
+        }
+        removed.push(output);
+    }
+
+    let freed = removed.iter().map(|e| e.size).sum();
+
+    if json_output {
+        output::print_json(&CacheRemoveResult {
+            removed,
+            freed,
+            dry_run,
+        });
+    } else if removed.is_empty() {
+        println!("Nothing to remove.");
+    } else {
+        println!("{}Freed {}", prefix, output::format_size(freed));
+    }
+
+    Ok(())
+}
+
+fn run_cache_verify(json_output: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let entries = cache::list_entries()?;
+    let mut results = Vec::new();
+    let mut failures = 0;
+
+    for entry in &entries {
+        let result = CacheVerifyEntry::new(
+            &entry.ecosystem,
+            &entry.package,
+            &entry.version,
+            &entry.path.display().to_string(),
+            "ok",
+        );
+
+        // Downloaded archives have no git checkout to compare against
+        let is_archive = entry
+            .meta
+            .as_ref()
+            .is_some_and(|meta| meta.strategy != cache::FetchStrategy::Git);
+
+        let result = match (git::head_commit(&entry.path), &entry.meta) {
+            _ if is_archive => result,
+            (Err(e), _) => {
+                failures += 1;
+                CacheVerifyEntry {
+                    status: "corrupt".to_string(),
+                    ..result
+                }
+                .with_message(&e.to_string())
+            }
+            (Ok(_), None) => CacheVerifyEntry {
+                status: "no_metadata".to_string(),
+                ..result
+            }
+            .with_message("entry predates .dotdeps-meta.json"),
+            (Ok(head), Some(meta)) => match &meta.commit {
+                Some(expected) if expected != &head => {
+                    failures += 1;
+                    CacheVerifyEntry {
+                        status: "modified".to_string(),
+                        ..result
+                    }
+                    .with_message(&format!("HEAD is {}, expected {}", head, expected))
+                }
+                _ => result,
+            },
+        };
+
+        if !json_output {
+            let message = result
+                .message
+                .as_deref()
+                .map(|m| format!(" ({})", m))
+                .unwrap_or_default();
+            println!(
+                "{}:{}@{}: {}{}",
+                result.ecosystem, result.package, result.version, result.status, message
+            );
+        }
+        results.push(result);
+    }
+
+    if json_output {
+        output::print_json(&CacheVerifyResult { entries: results });
+    } else if entries.is_empty() {
+        println!("Cache is empty");
+    }
+
+    if failures > 0 {
+        return Err(format!("{} cache entries failed verification", failures).into());
+    }
+
+    Ok(())
+}
+
+fn run_config(
+    command: cli::ConfigCommand,
+    json_output: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let cli::ConfigCommand::Show { resolved } = command;
+
+    // The merged configuration is JSON either way
+    if !resolved {
+        output::print_json(&config::Config::load()?);
+        return Ok(());
+    }
+
+    let (_, values) = config::Config::load_resolved()?;
+    if json_output {
+        output::print_json(&ConfigResolvedResult {
+            values: values
+                .into_iter()
+                .map(|value| ConfigValueOutput {
+                    key: value.key,
+                    value: value.value,
+                    source: value.source.layer().to_string(),
+                    origin: value.source.origin(),
+                })
+                .collect(),
+        });
+        return Ok(());
+    }
+
+    let width = values.iter().map(|v| v.key.len()).max().unwrap_or(0);
+    for value in &values {
+        let source = match value.source.origin() {
+            Some(origin) => format!("{}: {}", value.source.layer(), origin),
+            None => value.source.layer().to_string(),
+        };
+        println!(
+            "{:<width$}  {}  ({})",
+            value.key,
+            value.value,
+            source,
+            width = width
+        );
+    }
+    Ok(())
+}
+
 fn run_context(json_output: bool) -> Result<(), Box<dyn std::error::Error>> {
     let context = context::render_context()?;
     if json_output {
@@ -0,0 +1,181 @@
+//! Project manifest support for dotdeps
+//!
+//! Reads the committed project manifest `.deps.toml` from the current directory:
+//!
+//! ```toml
+//! dependencies = [
+//!   "python:requests",
+//!   "node:lodash@4.17.21",
+//! ]
+//! ```
+//!
+//! `dotdeps sync` uses it to reproduce the same `.deps/` for every teammate.
+//! Entries without a version are resolved from the project's lockfiles.
+
+use crate::cli::{DepSpec, Ecosystem};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Manifest filename, looked up in the current directory
+pub const MANIFEST_FILE: &str = ".deps.toml";
+
+#[derive(Error, Debug)]
+pub enum ManifestError {
+    #[error("No {MANIFEST_FILE} found in the current directory.")]
+    NotFound,
+
+    #[error("Failed to read {path}: {source}")]
+    ReadError {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to parse {path}: {details}")]
+    ParseError { path: PathBuf, details: String },
+
+    #[error("Invalid dependency '{spec}' in {path}: {details}")]
+    InvalidSpec {
+        path: PathBuf,
+        spec: String,
+        details: String,
+    },
+
+    #[error("Duplicate dependency {ecosystem}:{package} in {path}")]
+    Duplicate {
+        path: PathBuf,
+        ecosystem: Ecosystem,
+        package: String,
+    },
+}
+
+/// Raw TOML structure of `.deps.toml`
+#[derive(Deserialize)]
+struct RawManifest {
+    #[serde(default)]
+    dependencies: Vec<String>,
+}
+
+/// A parsed project manifest
+#[derive(Debug, Clone)]
+pub struct Manifest {
+    /// Dependencies that should be present in `.deps/`
+    pub dependencies: Vec<DepSpec>,
+}
+
+impl Manifest {
+    /// Load the manifest from the current directory
+    pub fn load() -> Result<Self, ManifestError> {
+        let path = manifest_path();
+        if !path.exists() {
+            return Err(ManifestError::NotFound);
+        }
+
+        let content = fs::read_to_string(&path).map_err(|source| ManifestError::ReadError {
+            path: path.clone(),
+            source,
+        })?;
+
+        Self::parse(&content, &path)
+    }
+
+    /// Parse manifest content; `path` is only used for error messages
+    pub fn parse(content: &str, path: &Path) -> Result<Self, ManifestError> {
+        let raw: RawManifest = toml::from_str(content).map_err(|e| ManifestError::ParseError {
+            path: path.to_path_buf(),
+            details: e.to_string(),
+        })?;
+
+        let mut dependencies: Vec<DepSpec> = Vec::new();
+        for entry in raw.dependencies {
+            let spec =
+                entry
+                    .trim()
+                    .parse::<DepSpec>()
+                    .map_err(|details| ManifestError::InvalidSpec {
+                        path: path.to_path_buf(),
+                        spec: entry.clone(),
+                        details,
+                    })?;
+
+            if dependencies
+                .iter()
+                .any(|d| d.ecosystem == spec.ecosystem && d.package == spec.package)
+            {
+                return Err(ManifestError::Duplicate {
+                    path: path.to_path_buf(),
+                    ecosystem: spec.ecosystem,
+                    package: spec.package,
+                });
+            }
+
+            dependencies.push(spec);
+        }
+
+        Ok(Self { dependencies })
+    }
+}
+
+/// Returns the manifest path: `./.deps.toml`
+pub fn manifest_path() -> PathBuf {
+    PathBuf::from(MANIFEST_FILE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str) -> Result<Manifest, ManifestError> {
+        Manifest::parse(content, Path::new(MANIFEST_FILE))
+    }
+
+    #[test]
+    fn test_parse_empty_manifest() {
+        let manifest = parse("").unwrap();
+        assert!(manifest.dependencies.is_empty());
+    }
+
+    #[test]
+    fn test_parse_dependencies() {
+        let manifest = parse(
+            r#"
+dependencies = [
+  "python:requests",
+  "node:@org/pkg@4.17.21",
+  "go:github.com/gin-gonic/gin",
+]
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(manifest.dependencies.len(), 3);
+        assert_eq!(manifest.dependencies[0].ecosystem, Ecosystem::Python);
+        assert_eq!(manifest.dependencies[0].package, "requests");
+        assert_eq!(manifest.dependencies[0].version, None);
+        assert_eq!(manifest.dependencies[1].package, "@org/pkg");
+        assert_eq!(
+            manifest.dependencies[1].version,
+            Some("4.17.21".to_string())
+        );
+        assert_eq!(manifest.dependencies[2].ecosystem, Ecosystem::Go);
+    }
+
+    #[test]
+    fn test_parse_invalid_spec() {
+        let result = parse(r#"dependencies = ["java:foo"]"#);
+        assert!(matches!(result, Err(ManifestError::InvalidSpec { .. })));
+    }
+
+    #[test]
+    fn test_parse_duplicate_dependency() {
+        let result = parse(r#"dependencies = ["python:requests", "python:Requests@2.31.0"]"#);
+        assert!(matches!(result, Err(ManifestError::Duplicate { .. })));
+    }
+
+    #[test]
+    fn test_parse_invalid_toml() {
+        let result = parse("dependencies = [");
+        assert!(matches!(result, Err(ManifestError::ParseError { .. })));
+    }
+}
@@ -0,0 +1,178 @@
+//! Registry metadata cache
+//!
+//! A package's repository almost never moves between versions, so the location
+//! detected from its registry metadata is kept in
+//! `~/.cache/dotdeps/metadata/<ecosystem>/<package>.json`, together with the raw
+//! registry fields it was detected from. `dotdeps add` uses a record younger than
+//! `metadata_ttl_hours` instead of asking the registry again; `--refresh`
+//! ignores existing records (and replaces them).
+
+use crate::cache::{self, CacheError};
+use crate::cli::Ecosystem;
+use crate::git::RepoLocation;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Directory inside the cache base directory that holds the records
+pub const METADATA_DIR: &str = "metadata";
+
+static REFRESH: AtomicBool = AtomicBool::new(false);
+
+/// A repository location detected from registry metadata
+#[derive(Debug)]
+pub struct Detected {
+    pub location: RepoLocation,
+    /// The registry fields the location was detected from
+    pub fields: serde_json::Value,
+}
+
+/// Contents of a metadata record
+#[derive(Debug, Serialize, Deserialize)]
+struct Record {
+    /// Registry base URL the metadata came from
+    registry: String,
+    repo_url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    subdir: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tag_templates: Vec<String>,
+    #[serde(default)]
+    fields: serde_json::Value,
+    /// Unix timestamp (seconds) of the registry request
+    fetched_at: u64,
+}
+
+/// Ignore existing records for the rest of the process
+pub fn set_refresh(refresh: bool) {
+    REFRESH.store(refresh, Ordering::Relaxed);
+}
+
+/// Returns the record path: `~/.cache/dotdeps/metadata/<ecosystem>/<package>.json`
+pub fn record_path(ecosystem: Ecosystem, package: &str) -> Result<PathBuf, CacheError> {
+    Ok(cache::base_dir()?
+        .join(METADATA_DIR)
+        .join(ecosystem.to_string())
+        .join(format!("{}.json", package)))
+}
+
+/// The cached location of a package, if `registry` was asked within `ttl`
+pub fn load(
+    ecosystem: Ecosystem,
+    package: &str,
+    registry: &str,
+    ttl: Duration,
+) -> Option<RepoLocation> {
+    if REFRESH.load(Ordering::Relaxed) {
+        return None;
+    }
+    read_record(&record_path(ecosystem, package).ok()?, registry, ttl, now())
+}
+
+/// Record the location detected for a package
+pub fn store(
+    ecosystem: Ecosystem,
+    package: &str,
+    registry: &str,
+    detected: &Detected,
+) -> Result<(), CacheError> {
+    let record = Record {
+        registry: registry.to_string(),
+        repo_url: detected.location.url.clone(),
+        subdir: detected.location.subdir.clone(),
+        tag_templates: detected.location.tag_templates.clone(),
+        fields: detected.fields.clone(),
+        fetched_at: now(),
+    };
+    write_record(&record_path(ecosystem, package)?, &record)
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_secs()
+}
+
+/// Read a record, unless it is older than `ttl` or from another registry
+fn read_record(path: &Path, registry: &str, ttl: Duration, now: u64) -> Option<RepoLocation> {
+    let content = fs::read_to_string(path).ok()?;
+    let record: Record = serde_json::from_str(&content).ok()?;
+    if record.registry != registry || now.saturating_sub(record.fetched_at) >= ttl.as_secs() {
+        return None;
+    }
+    Some(RepoLocation {
+        subdir: record.subdir,
+        tag_templates: record.tag_templates,
+        ..RepoLocation::root(&record.repo_url)
+    })
+}
+
+/// Write a record atomically, so concurrent adds never read half a file
+fn write_record(path: &Path, record: &Record) -> Result<(), CacheError> {
+    let io_error = |source| CacheError::WriteMeta {
+        path: path.to_path_buf(),
+        source,
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(io_error)?;
+    }
+
+    let json =
+        serde_json::to_string_pretty(record).expect("metadata record is always serializable");
+    let temp_path = path.with_extension(format!("json.tmp-{}", std::process::id()));
+    fs::write(&temp_path, json + "\n")
+        .and_then(|_| fs::rename(&temp_path, path))
+        .map_err(|source| {
+            let _ = fs::remove_file(&temp_path);
+            io_error(source)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    #[test]
+    fn test_record_round_trip() {
+        let dir = std::env::temp_dir().join("dotdeps_metadata_test");
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("node").join("@babel").join("core.json");
+
+        let record = Record {
+            registry: "https://registry.npmjs.org".to_string(),
+            repo_url: "https://github.com/babel/babel.git".to_string(),
+            subdir: Some("packages/babel-core".to_string()),
+            tag_templates: vec!["@babel/core@{version}".to_string()],
+            fields: serde_json::json!({"repository": {"directory": "packages/babel-core"}}),
+            fetched_at: 1_000_000,
+        };
+        write_record(&path, &record).unwrap();
+
+        let location = read_record(&path, "https://registry.npmjs.org", DAY, 1_000_060).unwrap();
+        assert_eq!(location.url, "https://github.com/babel/babel.git");
+        assert_eq!(location.subdir.as_deref(), Some("packages/babel-core"));
+        assert_eq!(location.tag_templates, vec!["@babel/core@{version}"]);
+
+        // Expired, asked of another registry, or caching disabled
+        assert!(
+            read_record(&path, "https://registry.npmjs.org", DAY, 1_000_000 + 86_400).is_none()
+        );
+        assert!(read_record(&path, "https://npm.example.com", DAY, 1_000_060).is_none());
+        assert!(
+            read_record(
+                &path,
+                "https://registry.npmjs.org",
+                Duration::ZERO,
+                1_000_000
+            )
+            .is_none()
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,80 @@
+//! Network access: offline mode and HTTP requests
+//!
+//! Registry lookups, downloads and the update check make their requests with
+//! `get`/`get_bytes`. In offline mode (`--offline` or `"offline": true` in the
+//! config) these, and git clones, fail immediately with an `Offline` error
+//! instead of waiting on a network that isn't there.
+
+use std::io::Read;
+use std::sync::atomic::{AtomicBool, Ordering};
+use thiserror::Error;
+
+const USER_AGENT: &str = "dotdeps (https://github.com/dotdeps/dotdeps)";
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+#[derive(Error, Debug)]
+pub enum NetworkError {
+    #[error("Can't fetch {resource} in offline mode")]
+    Offline { resource: String },
+
+    #[error("{0}")]
+    Request(#[from] ureq::Error),
+}
+
+/// Turn offline mode on or off for the rest of the process
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
+
+/// Fail with `NetworkError::Offline` if offline mode is on
+///
+/// `resource` names what would have been fetched (a URL or a package).
+pub fn ensure_online(resource: &str) -> Result<(), NetworkError> {
+    if is_offline() {
+        return Err(NetworkError::Offline {
+            resource: resource.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// GET a URL
+pub fn get(url: &str) -> Result<ureq::http::Response<ureq::Body>, NetworkError> {
+    ensure_online(url)?;
+    Ok(ureq::get(url).header("User-Agent", USER_AGENT).call()?)
+}
+
+/// GET a URL and read the whole body
+///
+/// Reads through the raw reader: archives can exceed ureq's default body limit.
+pub fn get_bytes(url: &str) -> Result<Vec<u8>, NetworkError> {
+    let response = get(url)?;
+    let mut body = Vec::new();
+    response
+        .into_body()
+        .into_reader()
+        .read_to_end(&mut body)
+        .map_err(|e| NetworkError::Request(e.into()))?;
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offline_error_names_resource() {
+        let err = NetworkError::Offline {
+            resource: "https://pypi.org/pypi/requests/json".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "Can't fetch https://pypi.org/pypi/requests/json in offline mode"
+        );
+    }
+}
@@ -2,10 +2,19 @@
 //!
 //! Handles:
 //! - Lockfile parsing: pnpm-lock.yaml, yarn.lock, package-lock.json
-//! - npm repository URL detection via registry API
+//! - npm repository detection via registry API, including packages in monorepo subdirectories
+//! - Downloading the published tarball, verified against the lockfile's `integrity`
+//! - Packages already installed in `node_modules`
 
+mod download;
+mod installed;
 mod lockfile;
 mod npm;
 
-pub use lockfile::{LockfileError, find_lockfile_path, find_version, list_direct_dependencies};
-pub use npm::detect_repo_url;
+pub use download::download_tarball;
+pub use installed::find_installed;
+pub use lockfile::{
+    LockedTarball, LockfileError, find_locked_tarball, find_lockfile_path, find_version,
+    list_direct_dependencies,
+};
+pub use npm::{DEFAULT_REGISTRY_URL, detect_repo};
@@ -0,0 +1,280 @@
+//! Published tarball downloads from the npm registry
+//!
+//! Fetches the exact tarball that `npm install` unpacks, as an alternative to
+//! cloning the repository. It contains what was published (compiled `dist/`,
+//! generated `.d.ts` files) rather than the repository's sources:
+//! - the `resolved` URL from the lockfile, or else
+//! - `dist.tarball` from `<registry>/<name>/<version>`
+//!
+//! Tarballs are gzipped and rooted at a single directory (usually `package/`).
+//! Downloads are verified against the lockfile's `integrity` (Subresource
+//! Integrity, e.g. `sha512-<base64>`), or else the registry's `dist.integrity`.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde::Deserialize;
+use sha2::{Digest, Sha256, Sha512};
+use std::path::Path;
+use thiserror::Error;
+
+use super::lockfile::LockedTarball;
+use crate::archive::{self, ArchiveError};
+
+#[derive(Error, Debug)]
+pub enum DownloadError {
+    #[error(transparent)]
+    Archive(#[from] ArchiveError),
+
+    #[error("Failed to parse npm registry response from {url}: {message}")]
+    Parse { url: String, message: String },
+
+    #[error("Integrity mismatch for {url}: expected {expected}, downloaded file has {actual}")]
+    Integrity {
+        url: String,
+        expected: String,
+        actual: String,
+    },
+}
+
+/// Result of downloading a tarball
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TarballDownload {
+    /// URL the tarball was downloaded from
+    pub url: String,
+    /// File name of the tarball (e.g., `node-22.0.0.tgz`)
+    pub file_name: String,
+    /// `sha512-<base64>` integrity of the downloaded file
+    pub integrity: String,
+}
+
+/// Version document from `<registry>/<name>/<version>`
+#[derive(Debug, Deserialize)]
+struct VersionInfo {
+    dist: Dist,
+}
+
+#[derive(Debug, Deserialize)]
+struct Dist {
+    tarball: String,
+    integrity: Option<String>,
+}
+
+/// Download the tarball of `package` at `version` and unpack it into `dest`
+///
+/// Uses the lockfile's `resolved` URL and `integrity` when `locked` has them,
+/// and asks the registry at `registry` for whatever is missing.
+pub fn download_tarball(
+    registry: &str,
+    package: &str,
+    version: &str,
+    locked: &LockedTarball,
+    dest: &Path,
+) -> Result<TarballDownload, DownloadError> {
+    let (url, integrity) = match (&locked.resolved, &locked.integrity) {
+        (Some(url), Some(integrity)) => (url.clone(), Some(integrity.clone())),
+        (Some(url), None) => {
+            let dist = fetch_dist(registry, package, version)?;
+            (url.clone(), dist.integrity)
+        }
+        (None, _) => {
+            let dist = fetch_dist(registry, package, version)?;
+            (dist.tarball, locked.integrity.clone().or(dist.integrity))
+        }
+    };
+
+    let body = archive::fetch(&url)?;
+    if let Some(expected) = integrity
+        && let Err(actual) = verify_integrity(&expected, &body)
+    {
+        return Err(DownloadError::Integrity {
+            url,
+            expected,
+            actual,
+        });
+    }
+
+    // Tarballs are rooted at a single directory, usually `package/`
+    archive::unpack_tar_gz(&body, 1, dest).map_err(|source| ArchiveError::Unpack {
+        url: url.clone(),
+        source,
+    })?;
+
+    let file_name = url.rsplit('/').next().unwrap_or(&url).to_string();
+    Ok(TarballDownload {
+        url,
+        file_name,
+        integrity: format!("sha512-{}", BASE64.encode(Sha512::digest(&body))),
+    })
+}
+
+/// Fetch the `dist` section of a version document
+fn fetch_dist(registry: &str, package: &str, version: &str) -> Result<Dist, DownloadError> {
+    // Scoped packages need the slash encoded: @scope/name -> @scope%2fname
+    let url = format!(
+        "{}/{}/{}",
+        registry.trim_end_matches('/'),
+        package.replace('/', "%2f"),
+        version
+    );
+    let body = archive::fetch(&url)?;
+    let info: VersionInfo = serde_json::from_slice(&body).map_err(|e| DownloadError::Parse {
+        url,
+        message: e.to_string(),
+    })?;
+    Ok(info.dist)
+}
+
+/// Check a body against an SRI string (space-separated `<alg>-<base64>` hashes)
+///
+/// sha512 and sha256 hashes are checked; the body must match one of them. If
+/// the string only has other algorithms (e.g. sha1 in old lockfiles), it
+/// can't be checked and is accepted. On a mismatch, returns the body's digest
+/// in the algorithm of the first hash checked.
+fn verify_integrity(sri: &str, body: &[u8]) -> Result<(), String> {
+    let mut mismatch = None;
+    for hash in sri.split_whitespace() {
+        let Some((algorithm, expected)) = hash.split_once('-') else {
+            continue;
+        };
+        // Options after `?` are reserved by the SRI spec
+        let expected = expected.split('?').next().unwrap_or(expected);
+        let actual = match algorithm {
+            "sha512" => BASE64.encode(Sha512::digest(body)),
+            "sha256" => BASE64.encode(Sha256::digest(body)),
+            _ => continue,
+        };
+        if actual == expected {
+            return Ok(());
+        }
+        mismatch.get_or_insert_with(|| format!("{}-{}", algorithm, actual));
+    }
+    match mismatch {
+        Some(actual) => Err(actual),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archive::fixtures::tar_gz;
+    use crate::test_http;
+
+    #[test]
+    fn test_verify_integrity() {
+        let body = b"hello";
+        let sha512 = format!("sha512-{}", BASE64.encode(Sha512::digest(body)));
+        let sha256 = format!("sha256-{}", BASE64.encode(Sha256::digest(body)));
+
+        assert_eq!(verify_integrity(&sha512, body), Ok(()));
+        assert_eq!(verify_integrity(&sha256, body), Ok(()));
+        assert_eq!(
+            verify_integrity(&format!("sha1-abc= {}", sha512), body),
+            Ok(())
+        );
+        // Only unsupported algorithms: nothing to check
+        assert_eq!(
+            verify_integrity("sha1-W/Rejkm6QYnhfUgnid/RW9FAt7Y=", body),
+            Ok(())
+        );
+
+        // The actual digest is reported in the expected algorithm
+        let other_sha256 = format!("sha256-{}", BASE64.encode(Sha256::digest(b"other")));
+        assert_eq!(verify_integrity(&other_sha256, body), Err(sha256));
+        assert!(
+            verify_integrity(&sha512, b"other")
+                .unwrap_err()
+                .starts_with("sha512-")
+        );
+    }
+
+    #[test]
+    fn test_download_tarball_from_lockfile_url() {
+        let body = tar_gz(
+            "package",
+            &[
+                ("package.json", "{\"name\": \"@types/demo\"}"),
+                ("index.d.ts", "export declare const x: number;\n"),
+            ],
+        );
+        let integrity = format!("sha512-{}", BASE64.encode(Sha512::digest(&body)));
+        let server = test_http::serve(vec![("/@types/demo/-/demo-1.0.0.tgz", body)]);
+
+        let dest = std::env::temp_dir().join("dotdeps_npm_tarball_test");
+        let _ = std::fs::remove_dir_all(&dest);
+
+        let locked = LockedTarball {
+            resolved: Some(format!("{}/@types/demo/-/demo-1.0.0.tgz", server.url)),
+            integrity: Some(integrity.clone()),
+        };
+        // The registry is not consulted when the lockfile has the URL and integrity
+        let downloaded =
+            download_tarball("http://127.0.0.1:1", "@types/demo", "1.0.0", &locked, &dest).unwrap();
+        assert_eq!(downloaded.file_name, "demo-1.0.0.tgz");
+        assert_eq!(downloaded.integrity, integrity);
+        assert!(dest.join("index.d.ts").exists());
+
+        let tampered = LockedTarball {
+            integrity: Some(format!("sha512-{}", BASE64.encode(Sha512::digest(b"x")))),
+            ..locked
+        };
+        let mismatch = download_tarball(&server.url, "@types/demo", "1.0.0", &tampered, &dest);
+        assert!(matches!(mismatch, Err(DownloadError::Integrity { .. })));
+
+        let _ = std::fs::remove_dir_all(&dest);
+    }
+
+    #[test]
+    fn test_download_tarball_without_locked_integrity() {
+        let body = tar_gz("package", &[("index.js", "module.exports = 1;\n")]);
+        let files = test_http::serve(vec![("/left-pad/-/left-pad-1.3.0.tgz", body)]);
+        let tampered = format!("sha512-{}", BASE64.encode(Sha512::digest(b"x")));
+        let version = format!(
+            r#"{{"dist": {{"tarball": "{}/left-pad/-/left-pad-1.3.0.tgz", "integrity": "{}"}}}}"#,
+            files.url, tampered
+        );
+        let registry = test_http::serve(vec![("/left-pad/1.3.0", version.into_bytes())]);
+
+        let dest = std::env::temp_dir().join("dotdeps_npm_unlocked_integrity_test");
+        let _ = std::fs::remove_dir_all(&dest);
+
+        // The lockfile's URL is used, but verified against the registry's integrity
+        let locked = LockedTarball {
+            resolved: Some(format!("{}/left-pad/-/left-pad-1.3.0.tgz", files.url)),
+            integrity: None,
+        };
+        let result = download_tarball(&registry.url, "left-pad", "1.3.0", &locked, &dest);
+        assert!(matches!(result, Err(DownloadError::Integrity { .. })));
+        assert!(!dest.join("index.js").exists());
+
+        let _ = std::fs::remove_dir_all(&dest);
+    }
+
+    #[test]
+    fn test_download_tarball_from_registry_dist() {
+        let body = tar_gz("lodash", &[("lodash.js", "module.exports = {};\n")]);
+        let integrity = format!("sha512-{}", BASE64.encode(Sha512::digest(&body)));
+        let files = test_http::serve(vec![("/lodash/-/lodash-4.17.21.tgz", body)]);
+        let version = format!(
+            r#"{{"name": "lodash", "version": "4.17.21", "dist": {{"tarball": "{}/lodash/-/lodash-4.17.21.tgz", "integrity": "{}"}}}}"#,
+            files.url, integrity
+        );
+        let registry = test_http::serve(vec![("/lodash/4.17.21", version.into_bytes())]);
+
+        let dest = std::env::temp_dir().join("dotdeps_npm_registry_tarball_test");
+        let _ = std::fs::remove_dir_all(&dest);
+
+        let downloaded = download_tarball(
+            &registry.url,
+            "lodash",
+            "4.17.21",
+            &LockedTarball::default(),
+            &dest,
+        )
+        .unwrap();
+        assert_eq!(downloaded.integrity, integrity);
+        assert!(dest.join("lodash.js").exists());
+
+        let _ = std::fs::remove_dir_all(&dest);
+    }
+}
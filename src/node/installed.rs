@@ -0,0 +1,84 @@
+//! Packages already installed into `node_modules`
+//!
+//! Looks for `node_modules/<name>/package.json` in the current directory and
+//! its parents (hoisted installs in monorepos), and uses the first one whose
+//! `version` matches. pnpm's `node_modules/<name>` is a symlink into
+//! `node_modules/.pnpm`; it is resolved so the real directory is recorded.
+
+use serde::Deserialize;
+use std::path::Path;
+
+use crate::installed::InstalledSource;
+
+#[derive(Deserialize)]
+struct PackageJson {
+    version: Option<String>,
+}
+
+/// Find `name@version` in the nearest `node_modules` that has it
+pub fn find_installed(name: &str, version: &str) -> Option<InstalledSource> {
+    let cwd = std::env::current_dir().ok()?;
+    find_in_node_modules(&cwd, name, version)
+}
+
+fn find_in_node_modules(start: &Path, name: &str, version: &str) -> Option<InstalledSource> {
+    start
+        .ancestors()
+        .map(|dir| dir.join("node_modules").join(name))
+        .find(|dir| installed_version(dir).as_deref() == Some(version))
+        .map(|dir| InstalledSource::dir(dir.canonicalize().unwrap_or(dir)))
+}
+
+/// `version` from a package directory's package.json
+fn installed_version(dir: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(dir.join("package.json")).ok()?;
+    serde_json::from_str::<PackageJson>(&content).ok()?.version
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn install(root: &Path, name: &str, version: &str) {
+        let dir = root.join("node_modules").join(name);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("package.json"),
+            format!(r#"{{"name": "{}", "version": "{}"}}"#, name, version),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_find_in_node_modules() {
+        let root = std::env::temp_dir().join("dotdeps_node_installed_test");
+        let _ = fs::remove_dir_all(&root);
+        let app = root.join("packages").join("app");
+        fs::create_dir_all(&app).unwrap();
+
+        // The workspace root has the hoisted copy, the app a nested one
+        install(&root, "lodash", "4.17.21");
+        install(&app, "lodash", "3.10.1");
+        install(&root, "@types/node", "20.11.0");
+
+        let root = root.canonicalize().unwrap();
+        let app = root.join("packages").join("app");
+
+        assert_eq!(
+            find_in_node_modules(&app, "lodash", "4.17.21"),
+            Some(InstalledSource::dir(root.join("node_modules/lodash")))
+        );
+        assert_eq!(
+            find_in_node_modules(&app, "lodash", "3.10.1"),
+            Some(InstalledSource::dir(app.join("node_modules/lodash")))
+        );
+        assert_eq!(
+            find_in_node_modules(&app, "@types/node", "20.11.0"),
+            Some(InstalledSource::dir(root.join("node_modules/@types/node")))
+        );
+        assert_eq!(find_in_node_modules(&app, "lodash", "4.0.0"), None);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -554,6 +554,8 @@ struct PackageLockEntry {
     version: Option<String>,
     /// Resolved URL - can be registry URL or git URL
     resolved: Option<String>,
+    /// SRI hash of the resolved tarball
+    integrity: Option<String>,
     dependencies: Option<HashMap<String, serde_json::Value>>,
     #[serde(rename = "devDependencies")]
     dev_dependencies: Option<HashMap<String, serde_json::Value>>,
@@ -566,6 +568,8 @@ struct PackageLockEntry {
 #[derive(Deserialize)]
 struct PackageLockDep {
     version: String,
+    resolved: Option<String>,
+    integrity: Option<String>,
 }
 
 /// Parse version from package-lock.json
@@ -801,6 +805,175 @@ fn strip_jsonc_trailing_commas(content: &str) -> String {
     result
 }
 
+// === Published tarballs ===
+
+/// Tarball URL and integrity a lockfile records for a package version
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LockedTarball {
+    /// `resolved` tarball URL (only http(s) URLs are kept)
+    pub resolved: Option<String>,
+    /// Subresource Integrity hash, e.g. `sha512-<base64>`
+    pub integrity: Option<String>,
+}
+
+impl LockedTarball {
+    fn new(resolved: Option<&str>, integrity: Option<&str>) -> Self {
+        Self {
+            resolved: resolved
+                .map(|url| url.split('#').next().unwrap_or(url))
+                .filter(|url| url.starts_with("https://") || url.starts_with("http://"))
+                .map(str::to_string),
+            integrity: integrity
+                .filter(|integrity| !integrity.is_empty())
+                .map(str::to_string),
+        }
+    }
+}
+
+/// Find the tarball the lockfile records for `package` at `version`
+///
+/// Returns an empty LockedTarball if there is no lockfile or it doesn't list
+/// that version.
+pub fn find_locked_tarball(package: &str, version: &str) -> LockedTarball {
+    let Ok(path) = find_lockfile_path() else {
+        return LockedTarball::default();
+    };
+    let Ok(content) = fs::read_to_string(&path) else {
+        return LockedTarball::default();
+    };
+
+    let locked = match path.file_name().and_then(|n| n.to_str()).unwrap_or("") {
+        "pnpm-lock.yaml" => pnpm_locked_tarball(&content, package, version),
+        "yarn.lock" => yarn_locked_tarball(&content, package, version),
+        "package-lock.json" => package_lock_locked_tarball(&content, package, version),
+        "bun.lock" => bun_locked_tarball(&content, package, version),
+        _ => None,
+    };
+    locked.unwrap_or_default()
+}
+
+fn pnpm_locked_tarball(content: &str, package: &str, version: &str) -> Option<LockedTarball> {
+    let lockfile: PnpmLockfile = serde_yml::from_str(content).ok()?;
+    let normalized_package = normalize_node_name(package);
+
+    lockfile
+        .packages?
+        .iter()
+        .find(|(key, _)| {
+            // lockfileVersion 6 keys start with `/`; peer suffixes look like `1.0.0(react@18.2.0)`
+            parse_pnpm_package_key(key.trim_start_matches('/')).is_some_and(|(name, v)| {
+                normalize_node_name(&name) == normalized_package
+                    && v.split('(').next() == Some(version)
+            })
+        })
+        .map(|(_, value)| {
+            let resolution = &value["resolution"];
+            LockedTarball::new(
+                resolution["tarball"].as_str(),
+                resolution["integrity"].as_str(),
+            )
+        })
+}
+
+fn yarn_locked_tarball(content: &str, package: &str, version: &str) -> Option<LockedTarball> {
+    let normalized_package = normalize_node_name(package);
+    let mut matches_package = false;
+    let mut fields: HashMap<&str, String> = HashMap::new();
+
+    let found = |matches_package: bool, fields: &HashMap<&str, String>| {
+        (matches_package && fields.get("version").map(String::as_str) == Some(version)).then(|| {
+            LockedTarball::new(
+                fields.get("resolved").map(String::as_str),
+                fields.get("integrity").map(String::as_str),
+            )
+        })
+    };
+
+    for line in content.lines() {
+        let line = line.trim_end();
+        if !line.starts_with(' ') && !line.starts_with('#') && line.ends_with(':') {
+            if let Some(locked) = found(matches_package, &fields) {
+                return Some(locked);
+            }
+            matches_package = parse_yarn_lock_header(line).iter().any(|spec| {
+                extract_package_name_from_yarn_spec(spec)
+                    .is_some_and(|name| normalize_node_name(&name) == normalized_package)
+            });
+            fields.clear();
+            continue;
+        }
+
+        // Entry fields are indented by two spaces: `  key "value"` (or `key: value` in yarn berry)
+        if let Some(field) = line.strip_prefix("  ")
+            && !field.starts_with(' ')
+            && let Some((key, value)) = field.split_once(' ')
+        {
+            let key = key.trim_end_matches(':');
+            if matches!(key, "version" | "resolved" | "integrity") {
+                fields.insert(key, value.trim().trim_matches('"').to_string());
+            }
+        }
+    }
+
+    found(matches_package, &fields)
+}
+
+fn package_lock_locked_tarball(
+    content: &str,
+    package: &str,
+    version: &str,
+) -> Option<LockedTarball> {
+    let lockfile: PackageLockfile = serde_json::from_str(content).ok()?;
+    let normalized_package = normalize_node_name(package);
+
+    // lockfileVersion 2/3: prefer the top-level install over nested ones
+    if let Some(packages) = &lockfile.packages {
+        let mut candidates: Vec<(&String, &PackageLockEntry)> = packages
+            .iter()
+            .filter(|(key, entry)| {
+                key.rsplit_once("node_modules/")
+                    .is_some_and(|(_, name)| normalize_node_name(name) == normalized_package)
+                    && entry.version.as_deref() == Some(version)
+            })
+            .collect();
+        candidates.sort_by_key(|(key, _)| key.len());
+        if let Some((_, entry)) = candidates.first() {
+            return Some(LockedTarball::new(
+                entry.resolved.as_deref(),
+                entry.integrity.as_deref(),
+            ));
+        }
+    }
+
+    // lockfileVersion 1
+    lockfile
+        .dependencies?
+        .iter()
+        .find(|(name, dep)| {
+            normalize_node_name(name) == normalized_package && dep.version == version
+        })
+        .map(|(_, dep)| LockedTarball::new(dep.resolved.as_deref(), dep.integrity.as_deref()))
+}
+
+fn bun_locked_tarball(content: &str, package: &str, version: &str) -> Option<LockedTarball> {
+    let lockfile: BunLockfile = serde_json::from_str(&strip_jsonc_trailing_commas(content)).ok()?;
+    let normalized_package = normalize_node_name(package);
+
+    // Entry format: ["name@version", "registry/tarball", {dependencies}, "integrity"]
+    lockfile.packages?.values().find_map(|value| {
+        let arr = value.as_array()?;
+        let name_version = arr.first()?.as_str()?;
+        let entry_version = extract_version_from_bun_entry(name_version)?;
+        let name = &name_version[..name_version.len() - entry_version.len() - 1];
+        (normalize_node_name(name) == normalized_package && entry_version == version).then(|| {
+            LockedTarball::new(
+                arr.get(1).and_then(|v| v.as_str()),
+                arr.get(3).and_then(|v| v.as_str()),
+            )
+        })
+    })
+}
+
 /// Normalize Node.js package name for comparison
 ///
 /// Node.js package names are case-sensitive on npm, but we normalize to lowercase
@@ -1092,6 +1265,105 @@ packages:
         );
     }
 
+    #[test]
+    fn test_package_lock_locked_tarball() {
+        let content = r#"{
+  "lockfileVersion": 3,
+  "packages": {
+    "": {"name": "app"},
+    "node_modules/lodash": {
+      "version": "4.17.21",
+      "resolved": "https://registry.npmjs.org/lodash/-/lodash-4.17.21.tgz",
+      "integrity": "sha512-v2kDEe57lecTulaDIuNTPy3Ry4gLGJ6Z1O3vE1krgXZNrsQ+LFTGHVxVjcXPs17LhbZVGedAJv8XZ1tvj5FvSg=="
+    },
+    "node_modules/a/node_modules/lodash": {
+      "version": "3.10.1",
+      "resolved": "https://registry.npmjs.org/lodash/-/lodash-3.10.1.tgz",
+      "integrity": "sha1-W/Rejkm6QYnhfUgnid/RW9FAt7Y="
+    },
+    "node_modules/local": {"version": "1.0.0", "resolved": "file:../local"}
+  }
+}"#;
+
+        let locked = package_lock_locked_tarball(content, "lodash", "4.17.21").unwrap();
+        assert_eq!(
+            locked.resolved.as_deref(),
+            Some("https://registry.npmjs.org/lodash/-/lodash-4.17.21.tgz")
+        );
+        assert!(locked.integrity.unwrap().starts_with("sha512-"));
+
+        let nested = package_lock_locked_tarball(content, "lodash", "3.10.1").unwrap();
+        assert_eq!(
+            nested.integrity.as_deref(),
+            Some("sha1-W/Rejkm6QYnhfUgnid/RW9FAt7Y=")
+        );
+
+        let local = package_lock_locked_tarball(content, "local", "1.0.0").unwrap();
+        assert_eq!(local, LockedTarball::default());
+        assert!(package_lock_locked_tarball(content, "lodash", "1.0.0").is_none());
+    }
+
+    #[test]
+    fn test_pnpm_and_yarn_locked_tarball() {
+        let pnpm = r#"
+lockfileVersion: '9.0'
+packages:
+  '@types/node@22.0.0':
+    resolution: {integrity: sha512-abc==}
+  react-dom@18.2.0(react@18.2.0):
+    resolution: {integrity: sha512-def==}
+"#;
+        assert_eq!(
+            pnpm_locked_tarball(pnpm, "@types/node", "22.0.0"),
+            Some(LockedTarball {
+                resolved: None,
+                integrity: Some("sha512-abc==".to_string()),
+            })
+        );
+        assert!(pnpm_locked_tarball(pnpm, "react-dom", "18.2.0").is_some());
+
+        let yarn = r#"# yarn lockfile v1
+
+"@babel/core@^7.24.0":
+  version "7.24.0"
+  resolved "https://registry.yarnpkg.com/@babel/core/-/core-7.24.0.tgz#56cbda6b185ae9d9bed369816a8f4423c5f2ff1b"
+  integrity sha512-fQfkg0Gjkza3nf0c7/w6Xf34BW4YvzNfACRLmmb7XRLa6XHdR+K9AlJlxneFfWYf6uhOzuzZVTjF/8KfndZANw==
+  dependencies:
+    "@ampproject/remapping" "^2.2.0"
+
+lodash@^4.17.0:
+  version "4.17.21"
+"#;
+        let locked = yarn_locked_tarball(yarn, "@babel/core", "7.24.0").unwrap();
+        assert_eq!(
+            locked.resolved.as_deref(),
+            Some("https://registry.yarnpkg.com/@babel/core/-/core-7.24.0.tgz")
+        );
+        assert!(locked.integrity.unwrap().starts_with("sha512-fQfkg0"));
+        assert_eq!(
+            yarn_locked_tarball(yarn, "lodash", "4.17.21"),
+            Some(LockedTarball::default())
+        );
+        assert!(yarn_locked_tarball(yarn, "lodash", "4.0.0").is_none());
+    }
+
+    #[test]
+    fn test_bun_locked_tarball() {
+        let content = r#"{
+  "lockfileVersion": 1,
+  "packages": {
+    "@types/node": ["@types/node@22.0.0", "", { "dependencies": {} }, "sha512-xyz=="],
+  },
+}"#;
+        assert_eq!(
+            bun_locked_tarball(content, "@types/node", "22.0.0"),
+            Some(LockedTarball {
+                resolved: None,
+                integrity: Some("sha512-xyz==".to_string()),
+            })
+        );
+    }
+
     #[test]
     fn test_parse_git_url_https() {
         let url = "git+https://github.com/org/repo.git#abc123";
@@ -1,10 +1,21 @@
 //! npm registry integration
 //!
-//! Fetches package metadata from npm to detect repository URLs.
-
-use serde::Deserialize;
+//! Fetches package metadata from npm (or a mirror such as Verdaccio) to
+//! detect repository URLs.
+//!
+//! Packages published from a monorepo declare their directory in the
+//! repository field (`{"url": "...", "directory": "packages/babel-core"}`);
+//! those are linked to that directory and also tried at changesets/lerna
+//! style tags (`@babel/core@7.24.0`).
+
+use crate::git::RepoLocation;
+use crate::metadata::Detected;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+/// Registry used when the config doesn't name a mirror
+pub const DEFAULT_REGISTRY_URL: &str = "https://registry.npmjs.org";
+
 #[derive(Error, Debug)]
 pub enum NpmError {
     #[error("Failed to fetch package info from npm: {0}")]
@@ -19,20 +30,19 @@ pub enum NpmError {
     Parse(String),
 }
 
-/// Detect the repository URL for a Node.js package via npm registry API
-pub fn detect_repo_url(package: &str) -> Result<String, NpmError> {
+/// Detect the repository of a Node.js package via the API of the npm registry at `registry`
+pub fn detect_repo(registry: &str, package: &str) -> Result<Detected, NpmError> {
+    let registry = registry.trim_end_matches('/');
     // npm registry URL - scoped packages need URL encoding for the slash
     let url = if package.starts_with('@') {
         // Encode the package name: @scope/name -> @scope%2fname
         let encoded = package.replace('/', "%2f");
-        format!("https://registry.npmjs.org/{}", encoded)
+        format!("{}/{}", registry, encoded)
     } else {
-        format!("https://registry.npmjs.org/{}", package)
+        format!("{}/{}", registry, package)
     };
 
-    let response = ureq::get(&url)
-        .call()
-        .map_err(|e| NpmError::Fetch(e.to_string()))?;
+    let response = crate::network::get(&url).map_err(|e| NpmError::Fetch(e.to_string()))?;
 
     let body = response
         .into_body()
@@ -42,27 +52,58 @@ pub fn detect_repo_url(package: &str) -> Result<String, NpmError> {
     let metadata: NpmMetadata =
         serde_json::from_str(&body).map_err(|e| NpmError::Parse(e.to_string()))?;
 
-    extract_repo_url(&metadata, package)
+    let url = extract_repo_url(&metadata, package)?;
+    Ok(Detected {
+        location: locate_in_repo(&url, repository_directory(&metadata).as_deref(), package),
+        fields: serde_json::to_value(&metadata).unwrap_or_default(),
+    })
 }
 
 /// npm registry JSON API response structure
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize)]
 struct NpmMetadata {
     repository: Option<Repository>,
     homepage: Option<String>,
 }
 
 /// Repository field can be a string or an object
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize)]
 #[serde(untagged)]
 enum Repository {
     String(String),
     Object(RepositoryObject),
 }
 
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize)]
 struct RepositoryObject {
     url: Option<String>,
+    /// Package directory within a monorepo
+    directory: Option<String>,
+}
+
+/// The monorepo directory from the repository field, if any
+///
+/// Directories that would leave the repository (`..`, absolute paths) are ignored.
+fn repository_directory(metadata: &NpmMetadata) -> Option<String> {
+    match &metadata.repository {
+        Some(Repository::Object(obj)) => obj
+            .directory
+            .as_deref()
+            .and_then(crate::git::normalize_subdir),
+        _ => None,
+    }
+}
+
+/// Build the location of a package that lives in `directory` of the repository at `url`
+fn locate_in_repo(url: &str, directory: Option<&str>, package: &str) -> RepoLocation {
+    match directory {
+        Some(directory) => RepoLocation {
+            subdir: Some(directory.to_string()),
+            tag_templates: vec![format!("{}@{{version}}", package)],
+            ..RepoLocation::root(url)
+        },
+        None => RepoLocation::root(url),
+    }
 }
 
 /// Extract repository URL from npm metadata
@@ -206,6 +247,20 @@ fn normalize_repo_url(url: &str) -> Option<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_http;
+
+    #[test]
+    fn test_detect_repo_from_mirror() {
+        let metadata = br#"{"repository": {"type": "git", "url": "git+https://github.com/babel/babel.git", "directory": "packages/babel-core"}}"#;
+        let server = test_http::serve(vec![("/@babel%2fcore", metadata.to_vec())]);
+
+        let location = detect_repo(&format!("{}/", server.url), "@babel/core")
+            .unwrap()
+            .location;
+        assert_eq!(location.url, "https://github.com/babel/babel.git");
+        assert_eq!(location.subdir.as_deref(), Some("packages/babel-core"));
+        assert_eq!(*server.requests.lock().unwrap(), vec!["/@babel%2fcore"]);
+    }
 
     #[test]
     fn test_is_known_git_host() {
@@ -322,6 +377,7 @@ mod tests {
         let metadata = NpmMetadata {
             repository: Some(Repository::Object(RepositoryObject {
                 url: Some("git+https://github.com/lodash/lodash.git".to_string()),
+                directory: None,
             })),
             homepage: None,
         };
@@ -351,4 +407,53 @@ mod tests {
         let result = extract_repo_url(&metadata, "lodash");
         assert!(matches!(result, Err(NpmError::RepoNotFound { .. })));
     }
+
+    #[test]
+    fn test_monorepo_package_location() {
+        let json = r#"{
+            "repository": {
+                "type": "git",
+                "url": "https://github.com/babel/babel.git",
+                "directory": "./packages/babel-core/"
+            }
+        }"#;
+        let metadata: NpmMetadata = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            repository_directory(&metadata).as_deref(),
+            Some("packages/babel-core")
+        );
+
+        let url = extract_repo_url(&metadata, "@babel/core").unwrap();
+        let location = locate_in_repo(
+            &url,
+            repository_directory(&metadata).as_deref(),
+            "@babel/core",
+        );
+        assert_eq!(location.url, "https://github.com/babel/babel.git");
+        assert_eq!(location.subdir.as_deref(), Some("packages/babel-core"));
+        assert_eq!(location.tag_templates, ["@babel/core@{version}"]);
+    }
+
+    #[test]
+    fn test_repository_directory_outside_repo() {
+        for directory in ["../../..", "packages/../../x"] {
+            let json = format!(
+                r#"{{"repository": {{"url": "https://github.com/a/b.git", "directory": "{}"}}}}"#,
+                directory
+            );
+            let metadata: NpmMetadata = serde_json::from_str(&json).unwrap();
+            assert_eq!(repository_directory(&metadata), None, "{}", directory);
+        }
+    }
+
+    #[test]
+    fn test_single_package_location() {
+        let json = r#"{"repository": "github:lodash/lodash"}"#;
+        let metadata: NpmMetadata = serde_json::from_str(json).unwrap();
+        assert_eq!(repository_directory(&metadata), None);
+        assert_eq!(
+            locate_in_repo("https://github.com/lodash/lodash.git", None, "lodash"),
+            RepoLocation::root("https://github.com/lodash/lodash.git")
+        );
+    }
 }
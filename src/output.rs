@@ -3,6 +3,7 @@
 //! Provides types for structured output that can be serialized to JSON
 //! for machine-readable output, or displayed as text for human consumption.
 
+use crate::cache::FetchStrategy;
 use crate::cli::Ecosystem;
 use serde::Serialize;
 
@@ -17,7 +18,21 @@ pub struct AddResult {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cloned_ref: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repo_url: Option<String>,
+    /// Root of the checkout when `path` links to a subdirectory of it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repo_root: Option<String>,
+    /// How the cache entry was obtained, when it isn't a git clone
+    /// (e.g. `installed` for sources copied from node_modules)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<FetchStrategy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub warning: Option<String>,
+    /// Existing tags closest to the version, when none matched it
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub nearest_tags: Vec<String>,
     #[serde(skip_serializing_if = "std::ops::Not::not")]
     pub dry_run: bool,
 }
@@ -44,6 +59,12 @@ pub struct ListEntry {
     pub ecosystem: String,
     pub package: String,
     pub version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolved_ref: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repo_url: Option<String>,
     #[serde(skip_serializing_if = "std::ops::Not::not")]
     pub broken: bool,
 }
@@ -63,6 +84,110 @@ pub struct ContextResult {
     pub context: Option<String>,
 }
 
+/// Result of a sync operation
+#[derive(Debug, Serialize)]
+pub struct SyncResult {
+    pub actions: Vec<SyncActionEntry>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub dry_run: bool,
+}
+
+/// A single action taken (or planned) during sync
+#[derive(Debug, Serialize)]
+pub struct SyncActionEntry {
+    pub ecosystem: String,
+    pub package: String,
+    pub action: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// A single cache entry in `cache` command output
+#[derive(Debug, Serialize)]
+pub struct CacheEntryOutput {
+    pub ecosystem: String,
+    pub package: String,
+    pub version: String,
+    pub path: String,
+    pub size: u64,
+    /// Last access time as a Unix timestamp (seconds)
+    pub last_accessed: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolved_ref: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// Projects whose .deps/ links to this entry
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub projects: Vec<String>,
+}
+
+/// Result of `cache ls`
+#[derive(Debug, Serialize)]
+pub struct CacheListResult {
+    pub entries: Vec<CacheEntryOutput>,
+    pub total_size: u64,
+}
+
+/// Result of `cache size`
+#[derive(Debug, Serialize)]
+pub struct CacheSizeResult {
+    pub path: String,
+    pub entries: usize,
+    pub total_size: u64,
+    /// Configured limit in bytes (None if unlimited)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+}
+
+/// Result of `cache prune`, `cache rm` and `cache gc`
+#[derive(Debug, Serialize)]
+pub struct CacheRemoveResult {
+    pub removed: Vec<CacheEntryOutput>,
+    pub freed: u64,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub dry_run: bool,
+}
+
+/// Result of `cache verify`
+#[derive(Debug, Serialize)]
+pub struct CacheVerifyResult {
+    pub entries: Vec<CacheVerifyEntry>,
+}
+
+/// Verification status of a single cache entry
+#[derive(Debug, Serialize)]
+pub struct CacheVerifyEntry {
+    pub ecosystem: String,
+    pub package: String,
+    pub version: String,
+    pub path: String,
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Result of `config show --resolved`
+#[derive(Debug, Serialize)]
+pub struct ConfigResolvedResult {
+    pub values: Vec<ConfigValueOutput>,
+}
+
+/// A configuration value and where it came from
+#[derive(Debug, Serialize)]
+pub struct ConfigValueOutput {
+    pub key: String,
+    pub value: serde_json::Value,
+    /// "default", "global", "project" or "env"
+    pub source: String,
+    /// Config file or environment variable that set the value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
+}
+
 /// Result of skipping a local dependency
 #[derive(Debug, Serialize)]
 pub struct SkipResult {
@@ -89,7 +214,12 @@ impl AddResult {
             path: path.to_string(),
             cached,
             cloned_ref: None,
+            commit: None,
+            repo_url: None,
+            repo_root: None,
+            strategy: None,
             warning: None,
+            nearest_tags: Vec::new(),
             dry_run: false,
         }
     }
@@ -99,11 +229,37 @@ impl AddResult {
         self
     }
 
+    pub fn with_commit(mut self, commit: &str) -> Self {
+        self.commit = Some(commit.to_string());
+        self
+    }
+
+    pub fn with_repo_url(mut self, repo_url: &str) -> Self {
+        self.repo_url = Some(repo_url.to_string());
+        self
+    }
+
+    pub fn with_repo_root(mut self, repo_root: &str) -> Self {
+        self.repo_root = Some(repo_root.to_string());
+        self
+    }
+
+    /// Label the result with a non-git fetch strategy (git clones stay unlabelled)
+    pub fn with_strategy(mut self, strategy: FetchStrategy) -> Self {
+        self.strategy = (strategy != FetchStrategy::Git).then_some(strategy);
+        self
+    }
+
     pub fn with_warning(mut self, warning: &str) -> Self {
         self.warning = Some(warning.to_string());
         self
     }
 
+    pub fn with_nearest_tags(mut self, nearest_tags: Vec<String>) -> Self {
+        self.nearest_tags = nearest_tags;
+        self
+    }
+
     pub fn with_dry_run(mut self) -> Self {
         self.dry_run = true;
         self
@@ -132,9 +288,24 @@ impl ListEntry {
             ecosystem: ecosystem.to_string(),
             package: package.to_string(),
             version: version.to_string(),
+            resolved_ref: None,
+            commit: None,
+            repo_url: None,
             broken,
         }
     }
+
+    pub fn with_provenance(
+        mut self,
+        resolved_ref: Option<&str>,
+        commit: Option<&str>,
+        repo_url: Option<&str>,
+    ) -> Self {
+        self.resolved_ref = resolved_ref.map(str::to_string);
+        self.commit = commit.map(str::to_string);
+        self.repo_url = repo_url.map(str::to_string);
+        self
+    }
 }
 
 impl SkipResult {
@@ -149,6 +320,34 @@ impl SkipResult {
     }
 }
 
+impl SyncActionEntry {
+    pub fn new(ecosystem: Ecosystem, package: &str, action: &str) -> Self {
+        Self {
+            ecosystem: ecosystem.to_string(),
+            package: package.to_string(),
+            action: action.to_string(),
+            version: None,
+            previous_version: None,
+            error: None,
+        }
+    }
+
+    pub fn with_version(mut self, version: &str) -> Self {
+        self.version = Some(version.to_string());
+        self
+    }
+
+    pub fn with_previous_version(mut self, version: &str) -> Self {
+        self.previous_version = Some(version.to_string());
+        self
+    }
+
+    pub fn with_error(mut self, error: &str) -> Self {
+        self.error = Some(error.to_string());
+        self
+    }
+}
+
 /// A single action taken during init
 #[derive(Debug, Serialize)]
 pub struct InitAction {
@@ -239,6 +438,78 @@ impl InitAction {
     }
 }
 
+impl CacheEntryOutput {
+    pub fn new(
+        ecosystem: &str,
+        package: &str,
+        version: &str,
+        path: &str,
+        size: u64,
+        last_accessed: u64,
+    ) -> Self {
+        Self {
+            ecosystem: ecosystem.to_string(),
+            package: package.to_string(),
+            version: version.to_string(),
+            path: path.to_string(),
+            size,
+            last_accessed,
+            resolved_ref: None,
+            commit: None,
+            projects: Vec::new(),
+        }
+    }
+
+    pub fn with_provenance(mut self, resolved_ref: &str, commit: Option<&str>) -> Self {
+        self.resolved_ref = Some(resolved_ref.to_string());
+        self.commit = commit.map(str::to_string);
+        self
+    }
+
+    pub fn with_projects(mut self, projects: Vec<String>) -> Self {
+        self.projects = projects;
+        self
+    }
+}
+
+impl CacheVerifyEntry {
+    pub fn new(ecosystem: &str, package: &str, version: &str, path: &str, status: &str) -> Self {
+        Self {
+            ecosystem: ecosystem.to_string(),
+            package: package.to_string(),
+            version: version.to_string(),
+            path: path.to_string(),
+            status: status.to_string(),
+            message: None,
+        }
+    }
+
+    pub fn with_message(mut self, message: &str) -> Self {
+        self.message = Some(message.to_string());
+        self
+    }
+}
+
+/// Format a byte count for text output (e.g., `4.2 MB`)
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next in &UNITS[1..] {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        unit = next;
+    }
+    format!("{:.1} {}", value, unit)
+}
+
 /// Print JSON output to stdout
 pub fn print_json<T: Serialize>(value: &T) {
     match serde_json::to_string_pretty(value) {
@@ -249,3 +520,16 @@ pub fn print_json<T: Serialize>(value: &T) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(4 * 1024 * 1024 + 200 * 1024), "4.2 MB");
+        assert_eq!(format_size(5 * 1024 * 1024 * 1024), "5.0 GB");
+    }
+}
@@ -0,0 +1,156 @@
+//! Registry of projects that link into the cache
+//!
+//! `deps::link` records the project directory in `~/.cache/dotdeps/projects.json`
+//! so `dotdeps cache ls` can show which projects use each cache entry.
+//! Projects whose `.deps/` no longer exists are dropped on the next update.
+
+use crate::cache::{self, CacheError};
+use crate::lock::{self, CacheLock};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Registry filename inside the cache base directory
+pub const REGISTRY_FILE: &str = "projects.json";
+
+/// Contents of `projects.json`
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Registry {
+    #[serde(default)]
+    projects: Vec<PathBuf>,
+}
+
+/// Returns the registry path: `~/.cache/dotdeps/projects.json`
+pub fn registry_path() -> Result<PathBuf, CacheError> {
+    Ok(cache::base_dir()?.join(REGISTRY_FILE))
+}
+
+/// Record a project directory as using the cache
+pub fn register(project_dir: &Path) -> Result<(), CacheError> {
+    let path = registry_path()?;
+    let project_dir = project_dir
+        .canonicalize()
+        .unwrap_or_else(|_| project_dir.to_path_buf());
+
+    let _lock = CacheLock::acquire(&lock::lock_path_for(&path))?;
+
+    let mut registry = read_registry(&path);
+    registry.projects.retain(|p| p.join(".deps").is_dir());
+    if registry.projects.contains(&project_dir) {
+        return Ok(());
+    }
+    registry.projects.push(project_dir);
+    registry.projects.sort();
+
+    let json = serde_json::to_string_pretty(&registry).expect("registry is always serializable");
+    let temp_path = path.with_extension(format!("json.tmp-{}", std::process::id()));
+    fs::write(&temp_path, json + "\n")
+        .and_then(|_| fs::rename(&temp_path, &path))
+        .map_err(|source| {
+            let _ = fs::remove_file(&temp_path);
+            CacheError::WriteRegistry { path, source }
+        })
+}
+
+/// Registered projects that still have a `.deps/` directory
+pub fn list() -> Result<Vec<PathBuf>, CacheError> {
+    let registry = read_registry(&registry_path()?);
+    Ok(registry
+        .projects
+        .into_iter()
+        .filter(|p| p.join(".deps").is_dir())
+        .collect())
+}
+
+/// Map each linked cache entry (canonical path) to the projects linking to it
+pub fn links_by_entry() -> Result<HashMap<PathBuf, Vec<PathBuf>>, CacheError> {
+    let mut links: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+
+    for project in list()? {
+        let mut targets = Vec::new();
+        collect_link_targets(&project.join(".deps"), &mut targets);
+        for target in targets {
+            let users = links.entry(target).or_default();
+            if !users.contains(&project) {
+                users.push(project.clone());
+            }
+        }
+    }
+
+    Ok(links)
+}
+
+/// Recursively collect the canonical targets of symlinks under `dir`
+fn collect_link_targets(dir: &Path, targets: &mut Vec<PathBuf>) {
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in read_dir.flatten() {
+        let path = entry.path();
+        let Ok(metadata) = path.symlink_metadata() else {
+            continue;
+        };
+
+        if metadata.is_symlink() {
+            if let Ok(target) = path.canonicalize() {
+                targets.push(target);
+            }
+        } else if metadata.is_dir() {
+            collect_link_targets(&path, targets);
+        }
+    }
+}
+
+/// Read the registry, treating a missing or unreadable file as empty
+fn read_registry(path: &Path) -> Registry {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_link_targets() {
+        let dir = std::env::temp_dir().join("dotdeps_projects_links_test");
+        let _ = fs::remove_dir_all(&dir);
+        let cache_entry = dir.join("cache/python/requests/2.31.0");
+        let deps = dir.join("project/.deps/node/@org");
+        fs::create_dir_all(&cache_entry).unwrap();
+        fs::create_dir_all(&deps).unwrap();
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(&cache_entry, deps.join("pkg")).unwrap();
+
+            let mut targets = Vec::new();
+            collect_link_targets(&dir.join("project/.deps"), &mut targets);
+            assert_eq!(targets, vec![cache_entry.canonicalize().unwrap()]);
+        }
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_read_registry_missing_or_invalid() {
+        let path = std::env::temp_dir().join("dotdeps_projects_registry_test.json");
+        let _ = fs::remove_file(&path);
+        assert!(read_registry(&path).projects.is_empty());
+
+        fs::write(&path, "not json").unwrap();
+        assert!(read_registry(&path).projects.is_empty());
+
+        fs::write(&path, r#"{"projects": ["/work/app"]}"#).unwrap();
+        assert_eq!(
+            read_registry(&path).projects,
+            vec![PathBuf::from("/work/app")]
+        );
+
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -3,9 +3,21 @@
 //! Handles:
 //! - Lockfile parsing: poetry.lock, uv.lock, requirements.txt, pyproject.toml
 //! - PyPI repository URL detection via registry API
+//! - Downloading the published sdist or wheel, verified against lockfile hashes
+//! - Packages already installed in a virtualenv
+//! - PEP 440 version ordering, for version-scoped overrides
 
+mod download;
+mod installed;
 mod lockfile;
 mod pypi;
+mod version;
 
-pub use lockfile::{LockfileError, find_lockfile_path, find_version, list_direct_dependencies};
-pub use pypi::detect_repo_url;
+pub use download::download_package;
+pub use installed::find_installed;
+pub use lockfile::{
+    FileHash, LockfileError, find_file_hashes, find_lockfile_path, find_version,
+    list_direct_dependencies,
+};
+pub use pypi::{DEFAULT_REGISTRY_URL, detect_repo};
+pub use version::compare_versions;
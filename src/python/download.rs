@@ -0,0 +1,324 @@
+//! Published distribution downloads from PyPI
+//!
+//! Fetches the exact files that get installed, as an alternative to cloning
+//! the repository:
+//! - `<base>/pypi/<name>/<version>/json` - release metadata with file URLs and digests
+//! - the sdist (`.tar.gz` or `.zip`, rooted at `<name>-<version>/`), or else a
+//!   pure-Python wheel (`*-none-any.whl`, a zip of the installed files)
+//!
+//! Downloads are verified against the hashes in poetry.lock/uv.lock when the
+//! lockfile records them, otherwise against the digest PyPI reports.
+
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use thiserror::Error;
+
+use super::lockfile::FileHash;
+use crate::archive::{self, ArchiveError};
+
+#[derive(Error, Debug)]
+pub enum DownloadError {
+    #[error(transparent)]
+    Archive(#[from] ArchiveError),
+
+    #[error("Failed to parse PyPI response from {url}: {message}")]
+    Parse { url: String, message: String },
+
+    #[error(
+        "No sdist or pure-Python wheel of {package} {version} on PyPI{}",
+        if *.locked { " matches the lockfile hashes" } else { "" }
+    )]
+    NoFile {
+        package: String,
+        version: String,
+        locked: bool,
+    },
+
+    #[error("Hash mismatch for {url}: expected {expected}, downloaded file has {actual}")]
+    Checksum {
+        url: String,
+        expected: String,
+        actual: String,
+    },
+}
+
+/// Result of downloading a distribution
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageDownload {
+    /// URL the file was downloaded from
+    pub url: String,
+    /// File name of the distribution (e.g., `requests-2.31.0.tar.gz`)
+    pub file_name: String,
+    /// SHA-256 of the downloaded file
+    pub checksum: String,
+}
+
+/// Release metadata from `/pypi/<name>/<version>/json`
+#[derive(Debug, Deserialize)]
+struct ReleaseInfo {
+    urls: Vec<ReleaseFile>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ReleaseFile {
+    filename: String,
+    url: String,
+    packagetype: String,
+    #[serde(default)]
+    digests: Digests,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct Digests {
+    sha256: Option<String>,
+}
+
+/// How a distribution file is laid out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Layout {
+    /// Gzipped tarball with a single top-level directory
+    TarGz,
+    /// Zip with a single top-level directory (old sdists)
+    ZipSdist,
+    /// Wheel: zip of the installed files, no top-level directory
+    Wheel,
+}
+
+/// Download the sdist (or pure-Python wheel) of `package` at `version` and unpack it into `dest`
+///
+/// `locked` are the hashes the lockfile records for this release. If any are
+/// given, only files listed there are considered and the download must match.
+pub fn download_package(
+    base: &str,
+    package: &str,
+    version: &str,
+    locked: &[FileHash],
+    dest: &Path,
+) -> Result<PackageDownload, DownloadError> {
+    let info_url = format!(
+        "{}/pypi/{}/{}/json",
+        base.trim_end_matches('/'),
+        package,
+        version
+    );
+    let body = archive::fetch(&info_url)?;
+    let release: ReleaseInfo = serde_json::from_slice(&body).map_err(|e| DownloadError::Parse {
+        url: info_url.clone(),
+        message: e.to_string(),
+    })?;
+
+    let (file, layout) =
+        select_file(&release.urls, locked).ok_or_else(|| DownloadError::NoFile {
+            package: package.to_string(),
+            version: version.to_string(),
+            locked: !locked.is_empty(),
+        })?;
+
+    let expected = locked
+        .iter()
+        .find(|hash| hash.filename == file.filename)
+        .map(|hash| hash.sha256.clone())
+        .or_else(|| file.digests.sha256.clone());
+
+    let body = archive::fetch(&file.url)?;
+    let checksum = format!("{:x}", Sha256::digest(&body));
+    if let Some(expected) = expected
+        && !expected.eq_ignore_ascii_case(&checksum)
+    {
+        return Err(DownloadError::Checksum {
+            url: file.url,
+            expected,
+            actual: checksum,
+        });
+    }
+
+    unpack(&body, layout, dest).map_err(|source| ArchiveError::Unpack {
+        url: file.url.clone(),
+        source,
+    })?;
+
+    Ok(PackageDownload {
+        url: file.url,
+        file_name: file.filename,
+        checksum,
+    })
+}
+
+/// Pick the file to download: an sdist, else a pure-Python wheel
+///
+/// If the lockfile recorded hashes, only files it lists are eligible.
+fn select_file(files: &[ReleaseFile], locked: &[FileHash]) -> Option<(ReleaseFile, Layout)> {
+    let eligible = |file: &&ReleaseFile| {
+        locked.is_empty() || locked.iter().any(|hash| hash.filename == file.filename)
+    };
+
+    let sdist = files
+        .iter()
+        .filter(eligible)
+        .filter(|file| file.packagetype == "sdist")
+        .find_map(|file| {
+            let name = file.filename.to_lowercase();
+            let layout = if name.ends_with(".tar.gz") {
+                Layout::TarGz
+            } else if name.ends_with(".zip") {
+                Layout::ZipSdist
+            } else {
+                return None;
+            };
+            Some((file.clone(), layout))
+        });
+
+    sdist.or_else(|| {
+        files
+            .iter()
+            .filter(eligible)
+            .find(|file| {
+                file.packagetype == "bdist_wheel" && file.filename.ends_with("-none-any.whl")
+            })
+            .map(|file| (file.clone(), Layout::Wheel))
+    })
+}
+
+/// Unpack a distribution into `dest`, dropping the sdist's top-level directory
+fn unpack(body: &[u8], layout: Layout, dest: &Path) -> std::io::Result<usize> {
+    match layout {
+        Layout::TarGz => archive::unpack_tar_gz(body, 1, dest),
+        Layout::ZipSdist => archive::unpack_zip(body, 1, dest),
+        Layout::Wheel => archive::unpack_zip(body, 0, dest),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archive::fixtures::tar_gz;
+    use crate::test_http;
+    use std::io::{Cursor, Write};
+
+    fn wheel(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in files {
+            writer
+                .start_file(*name, zip::write::FileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn release_file(filename: &str, packagetype: &str) -> ReleaseFile {
+        ReleaseFile {
+            filename: filename.to_string(),
+            url: format!("https://files.example.com/{}", filename),
+            packagetype: packagetype.to_string(),
+            digests: Digests::default(),
+        }
+    }
+
+    fn sha256(body: &[u8]) -> String {
+        format!("{:x}", Sha256::digest(body))
+    }
+
+    #[test]
+    fn test_select_file() {
+        let files = [
+            release_file(
+                "pkg-1.0-cp312-cp312-manylinux_2_17_x86_64.whl",
+                "bdist_wheel",
+            ),
+            release_file("pkg-1.0-py3-none-any.whl", "bdist_wheel"),
+            release_file("pkg-1.0.tar.gz", "sdist"),
+        ];
+
+        let (file, layout) = select_file(&files, &[]).unwrap();
+        assert_eq!(file.filename, "pkg-1.0.tar.gz");
+        assert_eq!(layout, Layout::TarGz);
+
+        let locked = [FileHash {
+            filename: "pkg-1.0-py3-none-any.whl".to_string(),
+            sha256: "00".to_string(),
+        }];
+        let (file, layout) = select_file(&files, &locked).unwrap();
+        assert_eq!(file.filename, "pkg-1.0-py3-none-any.whl");
+        assert_eq!(layout, Layout::Wheel);
+
+        assert!(select_file(&files[..1], &[]).is_none());
+    }
+
+    #[test]
+    fn test_download_sdist_verified_against_lockfile() {
+        let body = tar_gz(
+            "demo-1.0",
+            &[
+                ("pyproject.toml", "[project]\nname = \"demo\"\n"),
+                ("src/demo/__init__.py", "VERSION = '1.0'\n"),
+            ],
+        );
+        let locked = [FileHash {
+            filename: "demo-1.0.tar.gz".to_string(),
+            sha256: sha256(&body),
+        }];
+        let server = test_http::serve(vec![("/files/demo-1.0.tar.gz", body)]);
+        let info = format!(
+            r#"{{"urls": [{{"filename": "demo-1.0.tar.gz", "url": "{}/files/demo-1.0.tar.gz", "packagetype": "sdist", "digests": {{}}}}]}}"#,
+            server.url
+        );
+        let server_info = test_http::serve(vec![("/pypi/demo/1.0/json", info.into_bytes())]);
+
+        let dest = std::env::temp_dir().join("dotdeps_pypi_sdist_test");
+        let _ = std::fs::remove_dir_all(&dest);
+
+        let downloaded = download_package(&server_info.url, "demo", "1.0", &locked, &dest).unwrap();
+        assert_eq!(downloaded.file_name, "demo-1.0.tar.gz");
+        assert_eq!(downloaded.checksum, locked[0].sha256);
+        assert_eq!(
+            std::fs::read_to_string(dest.join("src/demo/__init__.py")).unwrap(),
+            "VERSION = '1.0'\n"
+        );
+
+        let wrong = [FileHash {
+            filename: "demo-1.0.tar.gz".to_string(),
+            sha256: "0".repeat(64),
+        }];
+        let mismatch = download_package(&server_info.url, "demo", "1.0", &wrong, &dest);
+        assert!(matches!(mismatch, Err(DownloadError::Checksum { .. })));
+
+        let other = [FileHash {
+            filename: "demo-1.0.zip".to_string(),
+            sha256: "0".repeat(64),
+        }];
+        let unlisted = download_package(&server_info.url, "demo", "1.0", &other, &dest);
+        assert!(matches!(
+            unlisted,
+            Err(DownloadError::NoFile { locked: true, .. })
+        ));
+
+        let _ = std::fs::remove_dir_all(&dest);
+    }
+
+    #[test]
+    fn test_download_wheel_verified_against_pypi_digest() {
+        let body = wheel(&[
+            ("demo/__init__.py", "X = 1\n"),
+            ("demo-2.0.dist-info/METADATA", "Name: demo\n"),
+        ]);
+        let digest = sha256(&body);
+        let server = test_http::serve(vec![("/files/demo-2.0-py3-none-any.whl", body)]);
+        let info = format!(
+            r#"{{"urls": [{{"filename": "demo-2.0-py3-none-any.whl", "url": "{}/files/demo-2.0-py3-none-any.whl", "packagetype": "bdist_wheel", "digests": {{"sha256": "{}"}}}}]}}"#,
+            server.url, digest
+        );
+        let server_info = test_http::serve(vec![("/pypi/demo/2.0/json", info.into_bytes())]);
+
+        let dest = std::env::temp_dir().join("dotdeps_pypi_wheel_test");
+        let _ = std::fs::remove_dir_all(&dest);
+
+        let downloaded = download_package(&server_info.url, "demo", "2.0", &[], &dest).unwrap();
+        assert_eq!(downloaded.checksum, digest);
+        assert!(dest.join("demo").join("__init__.py").exists());
+        assert!(dest.join("demo-2.0.dist-info").join("METADATA").exists());
+
+        let _ = std::fs::remove_dir_all(&dest);
+    }
+}
@@ -0,0 +1,134 @@
+//! Packages already installed into a virtualenv
+//!
+//! The virtualenv is `$VIRTUAL_ENV`, or else the nearest `.venv` or `venv`
+//! directory. Installed distributions share `lib/python*/site-packages`, so a
+//! package's files are taken from the RECORD of its dist-info directory:
+//! ```text
+//! site-packages/requests-2.31.0.dist-info/RECORD
+//!   requests/__init__.py,sha256=...,4965
+//!   requests/api.py,sha256=...,6449
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::lockfile::normalize_python_name;
+use crate::installed::InstalledSource;
+use crate::lockfile::find_nearest_file;
+
+/// Virtualenv directory names looked for when `VIRTUAL_ENV` is unset
+const VENV_DIRS: &[&str] = &[".venv", "venv"];
+
+/// Find `package==version` in the active or nearest virtualenv
+pub fn find_installed(package: &str, version: &str) -> Option<InstalledSource> {
+    let venv = std::env::var_os("VIRTUAL_ENV")
+        .map(PathBuf::from)
+        .or_else(|| find_nearest_file(VENV_DIRS))?;
+
+    site_packages_dirs(&venv)
+        .into_iter()
+        .find_map(|site_packages| find_in_site_packages(&site_packages, package, version))
+}
+
+/// `lib/python3.*/site-packages` (or `Lib/site-packages` on Windows) of a virtualenv
+fn site_packages_dirs(venv: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = fs::read_dir(venv.join("lib"))
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("python"))
+        .map(|entry| entry.path().join("site-packages"))
+        .collect();
+    dirs.push(venv.join("Lib").join("site-packages"));
+    dirs.retain(|dir| dir.is_dir());
+    dirs.sort();
+    dirs
+}
+
+/// Find the dist-info of `package==version` and list the files its RECORD names
+fn find_in_site_packages(
+    site_packages: &Path,
+    package: &str,
+    version: &str,
+) -> Option<InstalledSource> {
+    let wanted = normalize_python_name(package);
+    let dist_info = fs::read_dir(site_packages)
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .find(|dir_name| {
+            dir_name
+                .strip_suffix(".dist-info")
+                .and_then(|stem| stem.rsplit_once('-'))
+                .is_some_and(|(name, v)| normalize_python_name(name) == wanted && v == version)
+        })?;
+
+    let record = fs::read_to_string(site_packages.join(&dist_info).join("RECORD")).ok()?;
+    let files = parse_record(&record);
+    (!files.is_empty()).then(|| InstalledSource::files(site_packages.to_path_buf(), files))
+}
+
+/// Paths listed in a RECORD file, without compiled bytecode
+///
+/// RECORD is CSV (`path,hash,size`); paths with commas are quoted.
+fn parse_record(record: &str) -> Vec<PathBuf> {
+    record
+        .lines()
+        .filter_map(|line| match line.strip_prefix('"') {
+            Some(quoted) => quoted.split_once('"').map(|(path, _)| path),
+            None => line.split(',').next(),
+        })
+        .filter(|path| !path.is_empty() && !path.ends_with(".pyc"))
+        .map(PathBuf::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_record() {
+        let record = "requests/__init__.py,sha256=abc,4965\n\
+                      requests/__pycache__/api.cpython-312.pyc,,\n\
+                      \"odd,name.py\",sha256=def,1\n\
+                      ../../../bin/normalizer,sha256=ghi,2\n";
+        assert_eq!(
+            parse_record(record),
+            vec![
+                PathBuf::from("requests/__init__.py"),
+                PathBuf::from("odd,name.py"),
+                PathBuf::from("../../../bin/normalizer"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_in_site_packages() {
+        let venv = std::env::temp_dir().join("dotdeps_python_installed_test");
+        let _ = fs::remove_dir_all(&venv);
+        let site_packages = venv.join("lib").join("python3.12").join("site-packages");
+        let dist_info = site_packages.join("PyYAML-6.0.1.dist-info");
+        fs::create_dir_all(&dist_info).unwrap();
+        fs::write(
+            dist_info.join("RECORD"),
+            "yaml/__init__.py,sha256=abc,12311\nPyYAML-6.0.1.dist-info/RECORD,,\n",
+        )
+        .unwrap();
+
+        assert_eq!(site_packages_dirs(&venv), vec![site_packages.clone()]);
+        assert_eq!(
+            find_in_site_packages(&site_packages, "pyyaml", "6.0.1"),
+            Some(InstalledSource::files(
+                site_packages.clone(),
+                vec![
+                    PathBuf::from("yaml/__init__.py"),
+                    PathBuf::from("PyYAML-6.0.1.dist-info/RECORD"),
+                ]
+            ))
+        );
+        assert_eq!(find_in_site_packages(&site_packages, "pyyaml", "6.0"), None);
+
+        let _ = fs::remove_dir_all(&venv);
+    }
+}
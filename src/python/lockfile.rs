@@ -267,6 +267,8 @@ fn is_poetry_path_dependency(value: &toml::Value) -> bool {
 #[derive(Deserialize)]
 struct TomlLockfile {
     package: Option<Vec<TomlPackage>>,
+    /// Lockfile metadata; poetry < 1.5 keeps file hashes here
+    metadata: Option<PoetryMetadata>,
 }
 
 #[derive(Deserialize)]
@@ -275,6 +277,42 @@ struct TomlPackage {
     version: String,
     /// Source information for non-registry packages (git, directory, url)
     source: Option<TomlPackageSource>,
+    /// Distribution files with hashes (poetry.lock)
+    files: Option<Vec<PoetryFile>>,
+    /// Source distribution (uv.lock)
+    sdist: Option<UvFile>,
+    /// Wheels (uv.lock)
+    wheels: Option<Vec<UvFile>>,
+}
+
+/// `[metadata]` of older poetry.lock files
+#[derive(Deserialize)]
+struct PoetryMetadata {
+    /// Distribution files per package name
+    files: Option<std::collections::HashMap<String, Vec<PoetryFile>>>,
+}
+
+/// `{file = "requests-2.31.0.tar.gz", hash = "sha256:..."}` in poetry.lock
+#[derive(Deserialize)]
+struct PoetryFile {
+    file: String,
+    hash: String,
+}
+
+/// `{url = "https://files.pythonhosted.org/.../requests-2.31.0.tar.gz", hash = "sha256:..."}` in uv.lock
+#[derive(Deserialize)]
+struct UvFile {
+    url: Option<String>,
+    hash: Option<String>,
+}
+
+/// SHA-256 a lockfile records for one distribution file of a package
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileHash {
+    /// File name, e.g. `requests-2.31.0-py3-none-any.whl`
+    pub filename: String,
+    /// Hex-encoded SHA-256
+    pub sha256: String,
 }
 
 /// Source information for a package
@@ -357,6 +395,88 @@ fn extract_version_info_from_source(
     }
 }
 
+/// Find the file hashes the lockfile records for `package` at `version`
+///
+/// Only poetry.lock and uv.lock record per-file hashes; returns an empty list
+/// for other lockfiles or if the package isn't locked at that version.
+pub fn find_file_hashes(package: &str, version: &str) -> Vec<FileHash> {
+    let Ok(path) = find_lockfile_path() else {
+        return Vec::new();
+    };
+    match path.file_name().and_then(|n| n.to_str()) {
+        Some("poetry.lock" | "uv.lock") => {
+            parse_file_hashes(&path, package, version).unwrap_or_default()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Parse the file hashes of `package` at `version` from poetry.lock or uv.lock
+fn parse_file_hashes(
+    path: &Path,
+    package: &str,
+    version: &str,
+) -> Result<Vec<FileHash>, LockfileError> {
+    let content = fs::read_to_string(path).map_err(|source| LockfileError::ReadFile {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    let lockfile: TomlLockfile = toml::from_str(&content).map_err(|e| LockfileError::Parse {
+        path: path.to_path_buf(),
+        details: e.to_string(),
+    })?;
+
+    let normalized_package = normalize_python_name(package);
+    let Some(pkg) = lockfile
+        .package
+        .unwrap_or_default()
+        .into_iter()
+        .find(|pkg| {
+            normalize_python_name(&pkg.name) == normalized_package && pkg.version == version
+        })
+    else {
+        return Ok(Vec::new());
+    };
+
+    let legacy_files = lockfile
+        .metadata
+        .and_then(|metadata| metadata.files)
+        .and_then(|files| {
+            files
+                .into_iter()
+                .find(|(name, _)| normalize_python_name(name) == normalized_package)
+        })
+        .map(|(_, files)| files);
+
+    let poetry = pkg
+        .files
+        .or(legacy_files)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|f| file_hash(&f.file, &f.hash));
+    let uv = pkg
+        .sdist
+        .into_iter()
+        .chain(pkg.wheels.unwrap_or_default())
+        .filter_map(|f| {
+            let url = f.url?;
+            let filename = url.rsplit('/').next()?.to_string();
+            file_hash(&filename, &f.hash?)
+        });
+
+    Ok(poetry.chain(uv).collect())
+}
+
+/// Build a FileHash from a `sha256:<hex>` hash (other algorithms are skipped)
+fn file_hash(filename: &str, hash: &str) -> Option<FileHash> {
+    let sha256 = hash.strip_prefix("sha256:")?;
+    Some(FileHash {
+        filename: filename.to_string(),
+        sha256: sha256.to_lowercase(),
+    })
+}
+
 // === requirements.txt Parsing ===
 
 /// Parse version from requirements.txt
@@ -599,7 +719,7 @@ fn strip_version_constraint(version: &str) -> String {
 /// Normalize Python package name for comparison
 ///
 /// Python package names are case-insensitive and treat - and _ as equivalent
-fn normalize_python_name(name: &str) -> String {
+pub(super) fn normalize_python_name(name: &str) -> String {
     name.to_lowercase().replace('-', "_")
 }
 
@@ -744,6 +864,85 @@ version = "2.0.7"
         assert_eq!(packages[0].version, "2.31.0");
     }
 
+    #[test]
+    fn test_parse_file_hashes_poetry() {
+        let content = r#"
+[[package]]
+name = "requests"
+version = "2.31.0"
+files = [
+    {file = "requests-2.31.0-py3-none-any.whl", hash = "sha256:58CD2187C01E70E6E26505BCA751777AA9F2EE0B7F4300988B709F44E013003F"},
+    {file = "requests-2.31.0.tar.gz", hash = "sha256:942c5a758f98d790eaed1a29cb6eefc7ffb0d1cf7af05c3d2791656dbd6ad1e1"},
+]
+
+[[package]]
+name = "six"
+version = "1.16.0"
+
+[metadata]
+lock-version = "1.1"
+
+[metadata.files]
+six = [
+    {file = "six-1.16.0.tar.gz", hash = "sha256:1e61c37477a1626458e36f7b1d82aa5c9b094fa4802892072e49de9c60c4c926"},
+    {file = "six-1.16.0.zip", hash = "md5:0123"},
+]
+"#;
+        let path = write_temp_file("poetry.lock", content);
+
+        let hashes = parse_file_hashes(&path, "Requests", "2.31.0").unwrap();
+        assert_eq!(
+            hashes,
+            [
+                FileHash {
+                    filename: "requests-2.31.0-py3-none-any.whl".to_string(),
+                    sha256: "58cd2187c01e70e6e26505bca751777aa9f2ee0b7f4300988b709f44e013003f"
+                        .to_string(),
+                },
+                FileHash {
+                    filename: "requests-2.31.0.tar.gz".to_string(),
+                    sha256: "942c5a758f98d790eaed1a29cb6eefc7ffb0d1cf7af05c3d2791656dbd6ad1e1"
+                        .to_string(),
+                },
+            ]
+        );
+
+        let legacy = parse_file_hashes(&path, "six", "1.16.0").unwrap();
+        assert_eq!(legacy.len(), 1);
+        assert_eq!(legacy[0].filename, "six-1.16.0.tar.gz");
+
+        assert!(
+            parse_file_hashes(&path, "requests", "2.0.0")
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_parse_file_hashes_uv() {
+        let content = r#"
+version = 1
+
+[[package]]
+name = "idna"
+version = "3.7"
+source = { registry = "https://pypi.org/simple" }
+sdist = { url = "https://files.pythonhosted.org/packages/21/ed/idna-3.7.tar.gz", hash = "sha256:028ff3aadf0609c1fd278d8ea3089299412a7a8b9bd005dd08b9f8285bcb5cfc", size = 189575 }
+wheels = [
+    { url = "https://files.pythonhosted.org/packages/e5/3e/idna-3.7-py3-none-any.whl", hash = "sha256:82fee1fc78add43492d3a1898bfa6d8a904cc97d8427f683ed8e798d07761aa0", size = 66836 },
+]
+"#;
+        let path = write_temp_file("uv.lock", content);
+
+        let hashes = parse_file_hashes(&path, "idna", "3.7").unwrap();
+        let filenames: Vec<&str> = hashes.iter().map(|h| h.filename.as_str()).collect();
+        assert_eq!(filenames, ["idna-3.7.tar.gz", "idna-3.7-py3-none-any.whl"]);
+        assert_eq!(
+            hashes[0].sha256,
+            "028ff3aadf0609c1fd278d8ea3089299412a7a8b9bd005dd08b9f8285bcb5cfc"
+        );
+    }
+
     #[test]
     fn test_parse_toml_lockfile_git_dependency() {
         let content = r#"
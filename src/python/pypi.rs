@@ -1,11 +1,17 @@
 //! PyPI registry integration
 //!
-//! Fetches package metadata from PyPI to detect repository URLs.
+//! Fetches package metadata from PyPI (or a mirror such as devpi) to detect
+//! repository URLs.
 
-use serde::Deserialize;
+use crate::git::RepoLocation;
+use crate::metadata::Detected;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use thiserror::Error;
 
+/// PyPI instance used when the config doesn't name a mirror
+pub const DEFAULT_REGISTRY_URL: &str = "https://pypi.org";
+
 #[derive(Error, Debug)]
 pub enum PyPiError {
     #[error("Failed to fetch package info from PyPI: {0}")]
@@ -20,13 +26,11 @@ pub enum PyPiError {
     Parse(String),
 }
 
-/// Detect the repository URL for a Python package via PyPI API
-pub fn detect_repo_url(package: &str) -> Result<String, PyPiError> {
-    let url = format!("https://pypi.org/pypi/{}/json", package);
+/// Detect the repository of a Python package via the JSON API of the PyPI instance at `registry`
+pub fn detect_repo(registry: &str, package: &str) -> Result<Detected, PyPiError> {
+    let url = format!("{}/pypi/{}/json", registry.trim_end_matches('/'), package);
 
-    let response = ureq::get(&url)
-        .call()
-        .map_err(|e| PyPiError::Fetch(e.to_string()))?;
+    let response = crate::network::get(&url).map_err(|e| PyPiError::Fetch(e.to_string()))?;
 
     let body = response
         .into_body()
@@ -36,16 +40,20 @@ pub fn detect_repo_url(package: &str) -> Result<String, PyPiError> {
     let metadata: PyPiMetadata =
         serde_json::from_str(&body).map_err(|e| PyPiError::Parse(e.to_string()))?;
 
-    extract_repo_url(&metadata, package)
+    let url = extract_repo_url(&metadata, package)?;
+    Ok(Detected {
+        location: RepoLocation::root(&url),
+        fields: serde_json::to_value(&metadata).unwrap_or_default(),
+    })
 }
 
 /// PyPI JSON API response structure
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize)]
 struct PyPiMetadata {
     info: PackageInfo,
 }
 
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize)]
 struct PackageInfo {
     project_urls: Option<HashMap<String, String>>,
     home_page: Option<String>,
@@ -137,6 +145,19 @@ fn normalize_git_url(url: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_http;
+
+    #[test]
+    fn test_detect_repo_from_mirror() {
+        let metadata =
+            br#"{"info": {"project_urls": {"Source": "https://github.com/psf/requests"}}}"#;
+        let server = test_http::serve(vec![("/pypi/requests/json", metadata.to_vec())]);
+
+        assert_eq!(
+            detect_repo(&server.url, "requests").unwrap().location.url,
+            "https://github.com/psf/requests.git"
+        );
+    }
 
     #[test]
     fn test_is_git_repo_url() {
@@ -0,0 +1,125 @@
+//! Python version ordering (PEP 440)
+//!
+//! `1.0.dev1 < 1.0a1 < 1.0b2 < 1.0rc1 < 1.0 == 1.0.0 < 1.0.post1`. Epochs
+//! (`1!2.0`) sort above everything without one, and local versions (`+cpu`)
+//! are ignored.
+
+use std::cmp::Ordering;
+
+/// Compare two versions by PEP 440 precedence
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    sort_key(a).cmp(&sort_key(b))
+}
+
+/// Fields of a version in comparison order
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct SortKey {
+    epoch: u64,
+    /// Release numbers without trailing zeros
+    release: Vec<u64>,
+    /// (0, 0) for dev releases of a final version, (phase, n) for a/b/rc,
+    /// (4, 0) for none
+    pre: (u8, u64),
+    /// (0, 0) for none, (1, n) for a post-release
+    post: (u8, u64),
+    /// (0, n) for a dev release, (1, 0) for none
+    dev: (u8, u64),
+}
+
+fn sort_key(version: &str) -> SortKey {
+    let version = version.trim().to_lowercase();
+    let version = version.strip_prefix('v').unwrap_or(&version);
+    let version = version.split_once('+').map_or(version, |(v, _)| v);
+    let (epoch, rest) = match version.split_once('!') {
+        Some((epoch, rest)) => (epoch.parse().unwrap_or(0), rest),
+        None => (0, version),
+    };
+
+    let release_len = rest
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(rest.len());
+    let mut release: Vec<u64> = rest[..release_len]
+        .split('.')
+        .filter(|part| !part.is_empty())
+        .map(|part| part.parse().unwrap_or(0))
+        .collect();
+    while release.last() == Some(&0) {
+        release.pop();
+    }
+
+    let (mut pre, mut post, mut dev) = (None, None, None);
+    let mut rest = &rest[release_len..];
+    loop {
+        let after_sep = rest.trim_start_matches(['.', '-', '_']);
+        let had_separator = after_sep.len() < rest.len();
+        let word_len = after_sep
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(after_sep.len());
+        let (word, after_word) = after_sep.split_at(word_len);
+        let after_word = after_word.trim_start_matches(['.', '-', '_']);
+        let digits_len = after_word
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(after_word.len());
+        let number = after_word[..digits_len].parse().unwrap_or(0);
+
+        match word {
+            "a" | "alpha" => pre = Some((1, number)),
+            "b" | "beta" => pre = Some((2, number)),
+            "c" | "rc" | "pre" | "preview" => pre = Some((3, number)),
+            "post" | "rev" | "r" => post = Some(number),
+            "dev" => dev = Some(number),
+            // `1.0-1` is a post-release
+            "" if had_separator && digits_len > 0 => post = Some(number),
+            _ => break,
+        }
+        rest = &after_word[digits_len..];
+    }
+
+    SortKey {
+        epoch,
+        release,
+        pre: match (pre, post, dev) {
+            (Some(pre), _, _) => pre,
+            (None, None, Some(_)) => (0, 0),
+            _ => (4, 0),
+        },
+        post: post.map_or((0, 0), |n| (1, n)),
+        dev: dev.map_or((1, 0), |n| (0, n)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_versions() {
+        let ordered = [
+            "1.0.dev1",
+            "1.0a1",
+            "1.0a2.dev1",
+            "1.0a2",
+            "1.0b1",
+            "1.0rc1",
+            "1.0",
+            "1.0.post1",
+            "1.0.1",
+            "1.10",
+            "1!0.5",
+        ];
+        for pair in ordered.windows(2) {
+            assert_eq!(
+                compare_versions(pair[0], pair[1]),
+                Ordering::Less,
+                "{} < {}",
+                pair[0],
+                pair[1]
+            );
+        }
+
+        assert_eq!(compare_versions("1.0", "1.0.0"), Ordering::Equal);
+        assert_eq!(compare_versions("v2.31.0", "2.31.0+cpu"), Ordering::Equal);
+        assert_eq!(compare_versions("1.0-1", "1.0.post1"), Ordering::Equal);
+        assert_eq!(compare_versions("1.0RC1", "1.0c1"), Ordering::Equal);
+    }
+}
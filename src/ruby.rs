@@ -3,9 +3,21 @@
 //! Handles:
 //! - Lockfile parsing: Gemfile.lock
 //! - RubyGems repository URL detection via registry API
+//! - Downloading the published `.gem`, verified against Gemfile.lock's CHECKSUMS
+//! - Gems already installed by Bundler
+//! - `Gem::Version` ordering, for version-scoped overrides
 
+mod download;
+mod installed;
 mod lockfile;
 mod rubygems;
+mod version;
 
-pub use lockfile::{LockfileError, find_lockfile_path, find_version, list_direct_dependencies};
-pub use rubygems::detect_repo_url;
+pub use download::{download_gem, gem_source};
+pub use installed::find_installed;
+pub use lockfile::{
+    LockedGem, LockfileError, find_locked_gem, find_lockfile_path, find_version,
+    list_direct_dependencies,
+};
+pub use rubygems::{DEFAULT_REGISTRY_URL, canonical_name, detect_repo};
+pub use version::compare_versions;
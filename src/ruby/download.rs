@@ -0,0 +1,163 @@
+//! Published gem downloads
+//!
+//! Fetches the `.gem` file from a gem source as an alternative to cloning the
+//! repository, for gems whose metadata has no usable `source_code_uri`:
+//! - `<source>/gems/<name>-<version>.gem` (source: https://rubygems.org)
+//!
+//! A `.gem` is an uncompressed tarball holding `metadata.gz`, `checksums.yaml.gz`
+//! and `data.tar.gz`; the gem's files are in `data.tar.gz`, without a leading
+//! directory. The SHA-256 of the `.gem` is what Gemfile.lock records under CHECKSUMS.
+
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::path::Path;
+use thiserror::Error;
+
+use crate::archive::{self, ArchiveError};
+
+/// Gem source used when the config doesn't name one
+pub const DEFAULT_GEM_SOURCE: &str = "https://rubygems.org";
+
+#[derive(Error, Debug)]
+pub enum DownloadError {
+    #[error(transparent)]
+    Archive(#[from] ArchiveError),
+
+    #[error(
+        "Checksum mismatch for {url}: Gemfile.lock has {expected}, downloaded file has {actual}"
+    )]
+    Checksum {
+        url: String,
+        expected: String,
+        actual: String,
+    },
+}
+
+/// Result of downloading a gem
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GemDownload {
+    /// URL the gem was downloaded from
+    pub url: String,
+    /// File name of the downloaded gem (e.g., `rails-7.1.0.gem`)
+    pub file_name: String,
+    /// SHA-256 of the downloaded file
+    pub checksum: String,
+}
+
+/// Pick the gem source: config first, then rubygems.org
+pub fn gem_source(configured: Option<&str>) -> String {
+    configured
+        .unwrap_or(DEFAULT_GEM_SOURCE)
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// Download `name@version` from `source` and unpack its files into `dest`
+///
+/// `name` must be spelled as published (download paths are case-sensitive).
+/// If `expected_checksum` is given (from Gemfile.lock), the file must match it.
+pub fn download_gem(
+    source: &str,
+    name: &str,
+    version: &str,
+    expected_checksum: Option<&str>,
+    dest: &Path,
+) -> Result<GemDownload, DownloadError> {
+    let file_name = format!("{}-{}.gem", name, version);
+    let url = format!("{}/gems/{}", source.trim_end_matches('/'), file_name);
+
+    let body = archive::fetch(&url)?;
+    let checksum = format!("{:x}", Sha256::digest(&body));
+    if let Some(expected) = expected_checksum
+        && !expected.eq_ignore_ascii_case(&checksum)
+    {
+        return Err(DownloadError::Checksum {
+            url,
+            expected: expected.to_string(),
+            actual: checksum,
+        });
+    }
+
+    unpack_gem(&body, dest).map_err(|source| ArchiveError::Unpack {
+        url: url.clone(),
+        source,
+    })?;
+
+    Ok(GemDownload {
+        url,
+        file_name,
+        checksum,
+    })
+}
+
+/// Unpack the `data.tar.gz` inside a `.gem` into `dest`
+fn unpack_gem(body: &[u8], dest: &Path) -> std::io::Result<()> {
+    let mut outer = tar::Archive::new(body);
+    for entry in outer.entries()? {
+        let mut entry = entry?;
+        if entry.path()?.as_ref() != Path::new("data.tar.gz") {
+            continue;
+        }
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data)?;
+        // The gem's files are at the top of data.tar.gz
+        return archive::unpack_tar_gz(&data, 0, dest).map(|_| ());
+    }
+
+    Err(std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        "no data.tar.gz in gem",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archive::fixtures::{append_file, tar_gz};
+    use crate::test_http;
+
+    fn gem_file(files: &[(&str, &str)]) -> Vec<u8> {
+        let data = tar_gz("", files);
+        let mut gem = tar::Builder::new(Vec::new());
+        append_file(&mut gem, "metadata.gz", b"");
+        append_file(&mut gem, "data.tar.gz", &data);
+        gem.into_inner().unwrap()
+    }
+
+    #[test]
+    fn test_gem_source() {
+        assert_eq!(gem_source(None), DEFAULT_GEM_SOURCE);
+        assert_eq!(
+            gem_source(Some("https://gems.example.com/")),
+            "https://gems.example.com"
+        );
+    }
+
+    #[test]
+    fn test_download_gem_from_local_server() {
+        let body = gem_file(&[
+            ("lib/rack.rb", "module Rack; end\n"),
+            ("README.md", "# Rack\n"),
+        ]);
+        let checksum = format!("{:x}", Sha256::digest(&body));
+        let server = test_http::serve(vec![("/gems/rack-2.2.8.gem", body)]);
+
+        let dest = std::env::temp_dir().join("dotdeps_gem_download_test");
+        let _ = std::fs::remove_dir_all(&dest);
+
+        let downloaded =
+            download_gem(&server.url, "rack", "2.2.8", Some(&checksum), &dest).unwrap();
+        assert_eq!(downloaded.file_name, "rack-2.2.8.gem");
+        assert_eq!(downloaded.checksum, checksum);
+        assert_eq!(
+            std::fs::read_to_string(dest.join("lib").join("rack.rb")).unwrap(),
+            "module Rack; end\n"
+        );
+        assert!(!dest.join("metadata.gz").exists());
+
+        let mismatch = download_gem(&server.url, "rack", "2.2.8", Some(&"0".repeat(64)), &dest);
+        assert!(matches!(mismatch, Err(DownloadError::Checksum { .. })));
+
+        let _ = std::fs::remove_dir_all(&dest);
+    }
+}
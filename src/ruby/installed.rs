@@ -0,0 +1,61 @@
+//! Gems already installed by Bundler
+//!
+//! `bundle info --path <gem>` prints the directory the bundle's copy of a gem
+//! was installed into, e.g. `~/.gem/ruby/3.3.0/gems/rails-7.1.0`. It is used
+//! when the directory is the resolved version (platform builds such as
+//! `nokogiri-1.16.0-x86_64-linux` included). Outside a bundle (no
+//! Gemfile.lock), Bundler isn't run at all.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use super::lockfile::find_lockfile_path;
+use crate::installed::InstalledSource;
+
+/// Find `name@version` among the current bundle's installed gems
+pub fn find_installed(name: &str, version: &str) -> Option<InstalledSource> {
+    let lockfile = find_lockfile_path().ok()?;
+    let bundle_dir = lockfile.parent()?;
+
+    let output = Command::new("bundle")
+        .args(["info", "--path", name])
+        .current_dir(bundle_dir)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let dir = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    (is_gem_dir(&dir, name, version) && dir.is_dir()).then(|| InstalledSource::dir(dir))
+}
+
+/// Whether `dir` is named `<name>-<version>` or `<name>-<version>-<platform>`
+fn is_gem_dir(dir: &Path, name: &str, version: &str) -> bool {
+    let Some(dir_name) = dir.file_name().map(|n| n.to_string_lossy().to_lowercase()) else {
+        return false;
+    };
+    let expected = format!("{}-{}", name.to_lowercase(), version);
+    dir_name == expected || dir_name.starts_with(&format!("{}-", expected))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_gem_dir() {
+        let gems = Path::new("/home/me/.gem/ruby/3.3.0/gems");
+        assert!(is_gem_dir(&gems.join("rails-7.1.0"), "rails", "7.1.0"));
+        assert!(is_gem_dir(
+            &gems.join("nokogiri-1.16.0-x86_64-linux"),
+            "nokogiri",
+            "1.16.0"
+        ));
+        assert!(!is_gem_dir(&gems.join("rails-7.1.1"), "rails", "7.1.0"));
+        assert!(!is_gem_dir(&gems.join("rails-7.1.0.1"), "rails", "7.1.0"));
+        assert!(!is_gem_dir(Path::new("/"), "rails", "7.1.0"));
+    }
+}
@@ -13,8 +13,27 @@
 //!       rack
 //! ```
 //!
-//! Note: Git gems appear under GIT sections, not GEM sections.
-//! This implementation currently only parses GEM sections.
+//! Gems from git and local paths appear under GIT and PATH sections, which
+//! record where they come from before their specs:
+//! ```
+//! GIT
+//!   remote: https://github.com/rails/rails.git
+//!   revision: 4b9b2a6e1f0c3d5e7a8b9c0d1e2f3a4b5c6d7e8f
+//!   branch: main
+//!   specs:
+//!     rails (7.2.0.alpha)
+//!
+//! PATH
+//!   remote: engines/billing
+//!   specs:
+//!     billing (0.1.0)
+//! ```
+//!
+//! Bundler 2.5+ also writes a CHECKSUMS section with the SHA-256 of each `.gem`:
+//! ```
+//! CHECKSUMS
+//!   rails (7.1.0) sha256=...
+//! ```
 
 use crate::cli::VersionInfo;
 use crate::lockfile::find_nearest_file;
@@ -90,42 +109,140 @@ fn parse_version_from_lockfile(path: &Path, package: &str) -> Result<VersionInfo
         source,
     })?;
 
+    parse_version_from_content(&content, package).ok_or_else(|| LockfileError::VersionNotFound {
+        package: package.to_string(),
+    })
+}
+
+/// Find a gem in the specs of the GEM, GIT and PATH sections
+///
+/// GIT gems resolve to their remote and pinned revision, PATH gems to their
+/// local directory.
+fn parse_version_from_content(content: &str, package: &str) -> Option<VersionInfo> {
     let normalized_package = normalize_gem_name(package);
 
-    // State machine to track if we're inside a GEM section's specs
+    // State machine to track the current section and whether we're inside its specs
+    let mut section = "";
+    let mut remote = None;
+    let mut revision = None;
     let mut in_specs = false;
 
     for line in content.lines() {
-        // Detect start of specs section
-        if line.trim() == "specs:" {
-            in_specs = true;
-            continue;
-        }
-
         // Reset state when we hit a new top-level section
         if !line.starts_with(' ') && !line.is_empty() {
+            section = line.trim();
+            remote = None;
+            revision = None;
             in_specs = false;
             continue;
         }
 
+        let trimmed = line.trim();
+
+        // Detect start of specs section
+        if trimmed == "specs:" {
+            in_specs = true;
+            continue;
+        }
+
         if !in_specs {
+            if let Some(value) = trimmed.strip_prefix("remote:") {
+                remote = Some(value.trim().to_string());
+            } else if let Some(value) = trimmed.strip_prefix("revision:") {
+                revision = Some(value.trim().to_string());
+            }
             continue;
         }
 
         // Parse gem line: "    gem_name (version)"
         // Gems are indented with 4 spaces, their dependencies with 6 spaces
-        if let Some(gem_info) = parse_gem_line(line)
-            && normalize_gem_name(&gem_info.name) == normalized_package
-        {
-            return Ok(VersionInfo::Version(gem_info.version));
+        let Some(gem_info) = parse_gem_line(line) else {
+            continue;
+        };
+        if normalize_gem_name(&gem_info.name) != normalized_package {
+            continue;
         }
+
+        return match (section, &remote, &revision) {
+            ("GIT", Some(url), Some(commit)) => Some(VersionInfo::Git {
+                url: url.clone(),
+                commit: commit.clone(),
+            }),
+            ("PATH", Some(path), _) => Some(VersionInfo::LocalPath { path: path.clone() }),
+            _ => Some(VersionInfo::Version(gem_info.version)),
+        };
     }
 
-    Err(LockfileError::VersionNotFound {
-        package: package.to_string(),
+    None
+}
+
+/// A gem version as Gemfile.lock records it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockedGem {
+    /// Name as published, with its capitalization (e.g., `RedCloth`)
+    pub name: String,
+    /// SHA-256 of the `.gem` file, from the CHECKSUMS section written by Bundler 2.5+
+    pub checksum: Option<String>,
+}
+
+/// Find the entry Gemfile.lock records for a gem at `version`
+///
+/// Returns None if there is no lockfile or its specs don't list this exact version.
+pub fn find_locked_gem(package: &str, version: &str) -> Option<LockedGem> {
+    let lockfile = find_lockfile_path().ok()?;
+    let content = fs::read_to_string(lockfile).ok()?;
+    parse_locked_gem_from_content(&content, package, version)
+}
+
+/// Find a gem's name in the specs and its checksum in the CHECKSUMS section
+fn parse_locked_gem_from_content(content: &str, package: &str, version: &str) -> Option<LockedGem> {
+    let normalized_package = normalize_gem_name(package);
+    let name = content
+        .lines()
+        .filter_map(parse_gem_line)
+        .find(|gem| normalize_gem_name(&gem.name) == normalized_package && gem.version == version)?
+        .name;
+
+    Some(LockedGem {
+        name,
+        checksum: parse_checksum_from_content(content, package, version),
     })
 }
 
+/// Parse a gem's checksum from the CHECKSUMS section
+///
+/// Entries are `  <name> (<version>[-<platform>]) sha256=<hex>`; platform
+/// builds don't match the plain version.
+fn parse_checksum_from_content(content: &str, package: &str, version: &str) -> Option<String> {
+    let wanted = format!("{} ({})", normalize_gem_name(package), version);
+    let mut in_checksums = false;
+
+    for line in content.lines() {
+        if !line.starts_with(' ') && !line.is_empty() {
+            in_checksums = line.trim() == "CHECKSUMS";
+            continue;
+        }
+        if !in_checksums {
+            continue;
+        }
+
+        let trimmed = line.trim();
+        let Some(close_paren) = trimmed.find(')') else {
+            continue;
+        };
+        let (gem, checksums) = trimmed.split_at(close_paren + 1);
+        if normalize_gem_name(gem) != wanted {
+            continue;
+        }
+        return checksums
+            .split(',')
+            .find_map(|checksum| checksum.trim().strip_prefix("sha256="))
+            .map(|hex| hex.to_lowercase());
+    }
+
+    None
+}
+
 fn parse_dependency_section_gems(content: &str) -> Vec<String> {
     let mut deps = Vec::new();
     let mut in_deps = false;
@@ -423,6 +540,113 @@ PLATFORMS
         assert!(found_gems.contains(&("rack".to_string(), "2.2.8".to_string())));
     }
 
+    #[test]
+    fn test_parse_version_from_git_and_path_sections() {
+        let content = r#"GIT
+  remote: https://github.com/rails/rails.git
+  revision: 4b9b2a6e1f0c3d5e7a8b9c0d1e2f3a4b5c6d7e8f
+  branch: main
+  specs:
+    actionpack (7.2.0.alpha)
+      rack (>= 2.2.4)
+    rails (7.2.0.alpha)
+      actionpack (= 7.2.0.alpha)
+
+PATH
+  remote: engines/billing
+  specs:
+    billing (0.1.0)
+      rails
+
+GEM
+  remote: https://rubygems.org/
+  specs:
+    rack (2.2.8)
+
+DEPENDENCIES
+  billing!
+  rails!
+"#;
+
+        let git = VersionInfo::Git {
+            url: "https://github.com/rails/rails.git".to_string(),
+            commit: "4b9b2a6e1f0c3d5e7a8b9c0d1e2f3a4b5c6d7e8f".to_string(),
+        };
+        assert_eq!(
+            parse_version_from_content(content, "rails"),
+            Some(git.clone())
+        );
+        assert_eq!(parse_version_from_content(content, "ActionPack"), Some(git));
+        assert_eq!(
+            parse_version_from_content(content, "billing"),
+            Some(VersionInfo::LocalPath {
+                path: "engines/billing".to_string()
+            })
+        );
+        assert_eq!(
+            parse_version_from_content(content, "rack"),
+            Some(VersionInfo::Version("2.2.8".to_string()))
+        );
+        assert_eq!(parse_version_from_content(content, "missing"), None);
+    }
+
+    #[test]
+    fn test_parse_checksum_from_content() {
+        let content = r#"GEM
+  remote: https://rubygems.org/
+  specs:
+    nokogiri (1.16.0)
+    nokogiri (1.16.0-x86_64-linux)
+    rack (2.2.8)
+
+CHECKSUMS
+  nokogiri (1.16.0) sha256=AAAA1111
+  nokogiri (1.16.0-x86_64-linux) sha256=bbbb2222
+  rack (2.2.8)
+
+BUNDLED WITH
+   2.5.3
+"#;
+        assert_eq!(
+            parse_checksum_from_content(content, "Nokogiri", "1.16.0"),
+            Some("aaaa1111".to_string())
+        );
+        assert_eq!(parse_checksum_from_content(content, "rack", "2.2.8"), None);
+        assert_eq!(parse_checksum_from_content(content, "rack", "3.0.0"), None);
+    }
+
+    #[test]
+    fn test_parse_locked_gem_from_content() {
+        let content = r#"GEM
+  remote: https://rubygems.org/
+  specs:
+    RedCloth (4.3.2)
+    rack (2.2.8)
+
+CHECKSUMS
+  RedCloth (4.3.2) sha256=cccc3333
+  rack (2.2.8)
+"#;
+        assert_eq!(
+            parse_locked_gem_from_content(content, "redcloth", "4.3.2"),
+            Some(LockedGem {
+                name: "RedCloth".to_string(),
+                checksum: Some("cccc3333".to_string()),
+            })
+        );
+        assert_eq!(
+            parse_locked_gem_from_content(content, "rack", "2.2.8"),
+            Some(LockedGem {
+                name: "rack".to_string(),
+                checksum: None,
+            })
+        );
+        assert_eq!(
+            parse_locked_gem_from_content(content, "rack", "3.0.0"),
+            None
+        );
+    }
+
     #[test]
     fn test_parse_dependency_section_gems() {
         let content = r#"GEM
@@ -1,12 +1,18 @@
 //! RubyGems registry integration
 //!
-//! Fetches gem metadata from RubyGems.org to detect repository URLs.
+//! Fetches gem metadata from RubyGems.org (or a mirror such as Gemstash) to
+//! detect repository URLs.
 //!
 //! API endpoint: https://rubygems.org/api/v1/gems/{name}.json
 
-use serde::Deserialize;
+use crate::git::RepoLocation;
+use crate::metadata::Detected;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+/// Registry used when the config doesn't name a mirror
+pub const DEFAULT_REGISTRY_URL: &str = "https://rubygems.org";
+
 #[derive(Error, Debug)]
 pub enum RubyGemsError {
     #[error("Failed to fetch gem info from RubyGems: {0}")]
@@ -21,29 +27,47 @@ pub enum RubyGemsError {
     Parse(String),
 }
 
-/// Detect the repository URL for a Ruby gem via RubyGems.org API
-pub fn detect_repo_url(package: &str) -> Result<String, RubyGemsError> {
-    let url = format!("https://rubygems.org/api/v1/gems/{}.json", package);
+/// Detect the repository of a Ruby gem via the RubyGems API at `registry`
+pub fn detect_repo(registry: &str, package: &str) -> Result<Detected, RubyGemsError> {
+    let metadata = fetch_metadata(registry, package)?;
+    let url = extract_repo_url(&metadata, package)?;
+    Ok(Detected {
+        location: RepoLocation::root(&url),
+        fields: serde_json::to_value(&metadata).unwrap_or_default(),
+    })
+}
+
+/// The name `package` was published under, with its capitalization (e.g., `RedCloth`)
+///
+/// Download paths are case-sensitive. Returns None if the registry can't be asked.
+pub fn canonical_name(registry: &str, package: &str) -> Option<String> {
+    fetch_metadata(registry, package).ok()?.name
+}
+
+/// Fetch a gem's metadata from the RubyGems API at `registry`
+fn fetch_metadata(registry: &str, package: &str) -> Result<RubyGemsResponse, RubyGemsError> {
+    let url = format!(
+        "{}/api/v1/gems/{}.json",
+        registry.trim_end_matches('/'),
+        package
+    );
 
-    let response = ureq::get(&url)
-        .header("User-Agent", "dotdeps (https://github.com/dotdeps/dotdeps)")
-        .call()
-        .map_err(|e| RubyGemsError::Fetch(e.to_string()))?;
+    let response = crate::network::get(&url).map_err(|e| RubyGemsError::Fetch(e.to_string()))?;
 
     let body = response
         .into_body()
         .read_to_string()
         .map_err(|e| RubyGemsError::Parse(e.to_string()))?;
 
-    let metadata: RubyGemsResponse =
-        serde_json::from_str(&body).map_err(|e| RubyGemsError::Parse(e.to_string()))?;
-
-    extract_repo_url(&metadata, package)
+    serde_json::from_str(&body).map_err(|e| RubyGemsError::Parse(e.to_string()))
 }
 
 /// RubyGems.org JSON API response structure
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize)]
 struct RubyGemsResponse {
+    /// Name as published
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
     source_code_uri: Option<String>,
     homepage_uri: Option<String>,
 }
@@ -168,6 +192,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_canonical_name() {
+        let server = crate::test_http::serve(vec![(
+            "/api/v1/gems/redcloth.json",
+            br#"{"name": "RedCloth", "source_code_uri": "https://github.com/jgarber/redcloth"}"#
+                .to_vec(),
+        )]);
+        assert_eq!(
+            canonical_name(&server.url, "redcloth").as_deref(),
+            Some("RedCloth")
+        );
+        assert_eq!(canonical_name(&server.url, "missing"), None);
+    }
+
     #[test]
     fn test_normalize_git_url_strips_releases_path() {
         assert_eq!(
@@ -179,6 +217,7 @@ mod tests {
     #[test]
     fn test_extract_repo_url_from_source_code_uri() {
         let metadata = RubyGemsResponse {
+            name: None,
             source_code_uri: Some("https://github.com/rails/rails".to_string()),
             homepage_uri: Some("https://rubyonrails.org".to_string()),
         };
@@ -190,6 +229,7 @@ mod tests {
     #[test]
     fn test_extract_repo_url_from_homepage_fallback() {
         let metadata = RubyGemsResponse {
+            name: None,
             source_code_uri: None,
             homepage_uri: Some("https://github.com/example/gem".to_string()),
         };
@@ -201,6 +241,7 @@ mod tests {
     #[test]
     fn test_extract_repo_url_not_found() {
         let metadata = RubyGemsResponse {
+            name: None,
             source_code_uri: None,
             homepage_uri: Some("https://example.com".to_string()),
         };
@@ -212,6 +253,7 @@ mod tests {
     #[test]
     fn test_extract_repo_url_empty_source_code_uri() {
         let metadata = RubyGemsResponse {
+            name: None,
             source_code_uri: Some("".to_string()),
             homepage_uri: Some("https://github.com/example/gem".to_string()),
         };
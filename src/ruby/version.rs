@@ -0,0 +1,97 @@
+//! Ruby version ordering, as `Gem::Version` compares
+//!
+//! Versions are split into numeric and alphabetic segments (`1.0.0.rc1` ->
+//! `1 0 0 rc 1`). A version with a letter is a prerelease: alphabetic segments
+//! sort before numeric ones, so `1.0.0.rc1 < 1.0.0 < 1.0.0.1`. Missing segments
+//! count as zero (`1.0 == 1`).
+
+use std::cmp::Ordering;
+
+#[derive(Debug, PartialEq, Eq)]
+enum Segment<'a> {
+    Number(u64),
+    Letters(&'a str),
+}
+
+/// Compare two gem versions
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (a, b) = (segments(a), segments(b));
+    let zero = Segment::Number(0);
+    (0..a.len().max(b.len()))
+        .map(
+            |i| match (a.get(i).unwrap_or(&zero), b.get(i).unwrap_or(&zero)) {
+                (Segment::Number(x), Segment::Number(y)) => x.cmp(y),
+                (Segment::Letters(x), Segment::Letters(y)) => x.cmp(y),
+                (Segment::Letters(_), Segment::Number(_)) => Ordering::Less,
+                (Segment::Number(_), Segment::Letters(_)) => Ordering::Greater,
+            },
+        )
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Numeric and alphabetic runs of a version; `-` marks a prerelease like `.pre.`
+fn segments(version: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut rest = version.trim();
+    while let Some(start) = rest.find(|c: char| c.is_ascii_alphanumeric() || c == '-') {
+        rest = &rest[start..];
+        if let Some(after) = rest.strip_prefix('-') {
+            segments.push(Segment::Letters("pre"));
+            rest = after;
+            continue;
+        }
+        let numeric = rest.starts_with(|c: char| c.is_ascii_digit());
+        let len = rest
+            .find(|c: char| {
+                if numeric {
+                    !c.is_ascii_digit()
+                } else {
+                    !c.is_ascii_alphabetic()
+                }
+            })
+            .unwrap_or(rest.len());
+        let (segment, after) = rest.split_at(len);
+        segments.push(if numeric {
+            Segment::Number(segment.parse().unwrap_or(u64::MAX))
+        } else {
+            Segment::Letters(segment)
+        });
+        rest = after;
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_versions() {
+        let ordered = [
+            "1.0.0.a",
+            "1.0.0.beta2",
+            "1.0.0.rc1",
+            "1.0.0",
+            "1.0.0.1",
+            "1.0.10",
+            "2.0",
+        ];
+        for pair in ordered.windows(2) {
+            assert_eq!(
+                compare_versions(pair[0], pair[1]),
+                Ordering::Less,
+                "{} < {}",
+                pair[0],
+                pair[1]
+            );
+        }
+
+        assert_eq!(compare_versions("1.0", "1"), Ordering::Equal);
+        assert_eq!(
+            compare_versions("1.0.0-rc1", "1.0.0.pre.rc1"),
+            Ordering::Equal
+        );
+        assert_eq!(compare_versions("7.1.0rc1", "7.1.0.rc1"), Ordering::Equal);
+    }
+}
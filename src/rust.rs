@@ -3,9 +3,21 @@
 //! Handles:
 //! - Lockfile parsing: Cargo.lock
 //! - crates.io repository URL detection via registry API
+//! - Locating workspace member crates inside a cloned repository
+//! - Downloading published `.crate` files, verified against Cargo.lock checksums
+//! - Crates already unpacked in `~/.cargo/registry/src`
 
 mod crates_io;
+mod download;
+mod installed;
 mod lockfile;
+mod workspace;
 
-pub use crates_io::detect_repo_url;
-pub use lockfile::{LockfileError, find_lockfile_path, find_version, list_direct_dependencies};
+pub use crates_io::{DEFAULT_REGISTRY_URL, canonical_name, detect_repo};
+pub use download::{download_crate, download_url};
+pub use installed::find_installed;
+pub use lockfile::{
+    LockedCrate, LockfileError, find_locked_crate, find_lockfile_path, find_version,
+    list_direct_dependencies,
+};
+pub use workspace::find_crate_dir;
@@ -1,10 +1,18 @@
 //! crates.io registry integration
 //!
-//! Fetches crate metadata from crates.io to detect repository URLs.
+//! Fetches crate metadata from crates.io (or a mirror serving its API) to
+//! detect repository URLs.
 
-use serde::Deserialize;
+use crate::git::RepoLocation;
+use crate::metadata::Detected;
+use serde::{Deserialize, Serialize};
+
+use super::lockfile::normalize_crate_name;
 use thiserror::Error;
 
+/// Registry used when the config doesn't name a mirror
+pub const DEFAULT_REGISTRY_URL: &str = "https://crates.io";
+
 #[derive(Error, Debug)]
 pub enum CratesIoError {
     #[error("Failed to fetch crate info from crates.io: {0}")]
@@ -19,35 +27,83 @@ pub enum CratesIoError {
     Parse(String),
 }
 
-/// Detect the repository URL for a Rust crate via crates.io API
-pub fn detect_repo_url(package: &str) -> Result<String, CratesIoError> {
-    let url = format!("https://crates.io/api/v1/crates/{}", package);
+/// Detect the repository of a Rust crate via the crates.io API at `registry`
+///
+/// The crate's directory inside the repository is only known after cloning
+/// (see `find_crate_dir`), so the location is always the repository root.
+pub fn detect_repo(registry: &str, package: &str) -> Result<Detected, CratesIoError> {
+    let metadata = fetch_metadata(registry, package)?;
+    let url = extract_repo_url(&metadata, package)?;
+    Ok(Detected {
+        location: locate_crate(&url, package),
+        fields: serde_json::to_value(&metadata).unwrap_or_default(),
+    })
+}
+
+/// The name `package` was published under, with its capitalization (e.g., `Inflector`)
+///
+/// Lookups by name ignore case, but download paths don't. Returns None if the
+/// registry can't be asked.
+pub fn canonical_name(registry: &str, package: &str) -> Option<String> {
+    fetch_metadata(registry, package).ok()?.crate_info.name
+}
+
+/// Fetch a crate's metadata from the crates.io API at `registry`
+fn fetch_metadata(registry: &str, package: &str) -> Result<CratesIoResponse, CratesIoError> {
+    let url = format!(
+        "{}/api/v1/crates/{}",
+        registry.trim_end_matches('/'),
+        package
+    );
 
-    let response = ureq::get(&url)
-        .header("User-Agent", "dotdeps (https://github.com/dotdeps/dotdeps)")
-        .call()
-        .map_err(|e| CratesIoError::Fetch(e.to_string()))?;
+    let response = crate::network::get(&url).map_err(|e| CratesIoError::Fetch(e.to_string()))?;
 
     let body = response
         .into_body()
         .read_to_string()
         .map_err(|e| CratesIoError::Parse(e.to_string()))?;
 
-    let metadata: CratesIoResponse =
-        serde_json::from_str(&body).map_err(|e| CratesIoError::Parse(e.to_string()))?;
+    serde_json::from_str(&body).map_err(|e| CratesIoError::Parse(e.to_string()))
+}
+
+/// Location of `package` in the repository at `url`
+///
+/// Crates whose name differs from the repository name (`tokio-util` in
+/// `tokio-rs/tokio`) are usually workspace members released with per-crate
+/// tags, so `<crate>-v<version>` and `<crate>@<version>` are tried before the
+/// repository-wide `v<version>`.
+fn locate_crate(url: &str, package: &str) -> RepoLocation {
+    let repo_name = url
+        .trim_end_matches(".git")
+        .rsplit('/')
+        .next()
+        .unwrap_or(url);
+    if normalize_crate_name(repo_name) == normalize_crate_name(package) {
+        return RepoLocation::root(url);
+    }
 
-    extract_repo_url(&metadata, package)
+    RepoLocation {
+        tag_templates: vec![
+            format!("{}-v{{version}}", package),
+            format!("{}@{{version}}", package),
+            format!("{}-{{version}}", package),
+        ],
+        ..RepoLocation::root(url)
+    }
 }
 
 /// crates.io JSON API response structure
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize)]
 struct CratesIoResponse {
     #[serde(rename = "crate")]
     crate_info: CrateInfo,
 }
 
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize)]
 struct CrateInfo {
+    /// Name as published
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
     repository: Option<String>,
     homepage: Option<String>,
 }
@@ -105,6 +161,19 @@ fn normalize_git_url(url: &str) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_canonical_name() {
+        let server = crate::test_http::serve(vec![(
+            "/api/v1/crates/inflector",
+            br#"{"crate": {"name": "Inflector", "repository": "https://github.com/whatisinternet/inflector"}}"#.to_vec(),
+        )]);
+        assert_eq!(
+            canonical_name(&server.url, "inflector").as_deref(),
+            Some("Inflector")
+        );
+        assert_eq!(canonical_name(&server.url, "missing"), None);
+    }
+
     #[test]
     fn test_is_git_repo_url() {
         assert!(is_git_repo_url("https://github.com/serde-rs/serde"));
@@ -131,10 +200,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_locate_crate() {
+        assert_eq!(
+            locate_crate("https://github.com/serde-rs/serde.git", "serde"),
+            RepoLocation::root("https://github.com/serde-rs/serde.git")
+        );
+        assert_eq!(
+            locate_crate("https://github.com/bevyengine/bevy.git", "bevy"),
+            RepoLocation::root("https://github.com/bevyengine/bevy.git")
+        );
+
+        let location = locate_crate("https://github.com/tokio-rs/tokio.git", "tokio-util");
+        assert_eq!(location.url, "https://github.com/tokio-rs/tokio.git");
+        assert_eq!(location.subdir, None);
+        assert_eq!(
+            location.tag_templates,
+            [
+                "tokio-util-v{version}",
+                "tokio-util@{version}",
+                "tokio-util-{version}"
+            ]
+        );
+    }
+
     #[test]
     fn test_extract_repo_url_from_repository() {
         let metadata = CratesIoResponse {
             crate_info: CrateInfo {
+                name: None,
                 repository: Some("https://github.com/serde-rs/serde".to_string()),
                 homepage: Some("https://serde.rs".to_string()),
             },
@@ -148,6 +242,7 @@ mod tests {
     fn test_extract_repo_url_from_homepage_fallback() {
         let metadata = CratesIoResponse {
             crate_info: CrateInfo {
+                name: None,
                 repository: None,
                 homepage: Some("https://github.com/example/pkg".to_string()),
             },
@@ -161,6 +256,7 @@ mod tests {
     fn test_extract_repo_url_not_found() {
         let metadata = CratesIoResponse {
             crate_info: CrateInfo {
+                name: None,
                 repository: None,
                 homepage: Some("https://example.com".to_string()),
             },
@@ -0,0 +1,196 @@
+//! Published crate downloads
+//!
+//! Fetches the `.crate` file uploaded to crates.io (a gzipped tarball rooted
+//! at `<name>-<version>/`) as an alternative to cloning the repository:
+//! - `<base>/<name>/<name>-<version>.crate` (base: https://static.crates.io/crates)
+//!
+//! The SHA-256 of the file is the `checksum` Cargo.lock records for the crate.
+//! Crates packaged from a git checkout include `.cargo_vcs_info.json` with the
+//! commit they were built from.
+
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use thiserror::Error;
+
+use crate::archive::{self, ArchiveError};
+
+/// Download location used when the config doesn't name one
+pub const DEFAULT_DOWNLOAD_URL: &str = "https://static.crates.io/crates";
+
+#[derive(Error, Debug)]
+pub enum DownloadError {
+    #[error(transparent)]
+    Archive(#[from] ArchiveError),
+
+    #[error("Checksum mismatch for {url}: Cargo.lock has {expected}, downloaded file has {actual}")]
+    Checksum {
+        url: String,
+        expected: String,
+        actual: String,
+    },
+
+    #[error("No files in {url}")]
+    Empty { url: String },
+}
+
+/// Result of downloading a crate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrateDownload {
+    /// URL the crate was downloaded from
+    pub url: String,
+    /// File name of the downloaded crate (e.g., `serde-1.0.200.crate`)
+    pub file_name: String,
+    /// SHA-256 of the downloaded file
+    pub checksum: String,
+    /// Commit the crate was packaged from, if recorded
+    pub commit: Option<String>,
+}
+
+/// Contents of `.cargo_vcs_info.json`
+#[derive(Debug, Deserialize)]
+struct VcsInfo {
+    git: Option<VcsGit>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VcsGit {
+    sha1: Option<String>,
+}
+
+/// Pick the download location: config first, then crates.io
+pub fn download_url(configured: Option<&str>) -> String {
+    configured
+        .unwrap_or(DEFAULT_DOWNLOAD_URL)
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// Download `name@version` from `base` and unpack it into `dest`
+///
+/// `name` must be spelled as published (download paths are case-sensitive).
+/// If `expected_checksum` is given (from Cargo.lock), the file must match it.
+pub fn download_crate(
+    base: &str,
+    name: &str,
+    version: &str,
+    expected_checksum: Option<&str>,
+    dest: &Path,
+) -> Result<CrateDownload, DownloadError> {
+    let file_name = format!("{}-{}.crate", name, version);
+    let url = format!("{}/{}/{}", base.trim_end_matches('/'), name, file_name);
+
+    let body = archive::fetch(&url)?;
+    let checksum = format!("{:x}", Sha256::digest(&body));
+    if let Some(expected) = expected_checksum
+        && !expected.eq_ignore_ascii_case(&checksum)
+    {
+        return Err(DownloadError::Checksum {
+            url,
+            expected: expected.to_string(),
+            actual: checksum,
+        });
+    }
+
+    // The archive is rooted at `<name>-<version>/`
+    let unpacked =
+        archive::unpack_tar_gz(&body, 1, dest).map_err(|source| ArchiveError::Unpack {
+            url: url.clone(),
+            source,
+        })?;
+    if unpacked == 0 {
+        return Err(DownloadError::Empty { url });
+    }
+
+    Ok(CrateDownload {
+        url,
+        file_name,
+        checksum,
+        commit: read_vcs_commit(dest),
+    })
+}
+
+/// Commit recorded in an unpacked crate's `.cargo_vcs_info.json`
+fn read_vcs_commit(dir: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(dir.join(".cargo_vcs_info.json")).ok()?;
+    let info: VcsInfo = serde_json::from_str(&content).ok()?;
+    info.git?.sha1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archive::fixtures::tar_gz;
+    use crate::test_http;
+
+    #[test]
+    fn test_download_url() {
+        assert_eq!(download_url(None), DEFAULT_DOWNLOAD_URL);
+        assert_eq!(
+            download_url(Some("http://localhost:8080/crates/")),
+            "http://localhost:8080/crates"
+        );
+    }
+
+    #[test]
+    fn test_download_crate_from_local_server() {
+        let body = tar_gz(
+            "foo-1.2.3",
+            &[
+                ("Cargo.toml", "[package]\nname = \"foo\"\n"),
+                ("src/lib.rs", "pub fn foo() {}\n"),
+                (
+                    ".cargo_vcs_info.json",
+                    r#"{"git":{"sha1":"0123456789abcdef0123456789abcdef01234567"},"path_in_vcs":"foo"}"#,
+                ),
+            ],
+        );
+        let checksum = format!("{:x}", Sha256::digest(&body));
+        let server = test_http::serve(vec![("/foo/foo-1.2.3.crate", body)]);
+
+        let dest = std::env::temp_dir().join("dotdeps_crate_download_test");
+        let _ = std::fs::remove_dir_all(&dest);
+
+        let downloaded =
+            download_crate(&server.url, "foo", "1.2.3", Some(&checksum), &dest).unwrap();
+        assert_eq!(downloaded.file_name, "foo-1.2.3.crate");
+        assert_eq!(downloaded.checksum, checksum);
+        assert_eq!(
+            downloaded.commit.as_deref(),
+            Some("0123456789abcdef0123456789abcdef01234567")
+        );
+        assert_eq!(
+            std::fs::read_to_string(dest.join("src").join("lib.rs")).unwrap(),
+            "pub fn foo() {}\n"
+        );
+
+        let _ = std::fs::remove_dir_all(&dest);
+    }
+
+    #[test]
+    fn test_download_crate_checksum_mismatch() {
+        let body = tar_gz("bar-0.1.0", &[("src/lib.rs", "")]);
+        let server = test_http::serve(vec![("/bar/bar-0.1.0.crate", body)]);
+
+        let dest = std::env::temp_dir().join("dotdeps_crate_checksum_test");
+        let _ = std::fs::remove_dir_all(&dest);
+
+        let result = download_crate(&server.url, "bar", "0.1.0", Some(&"0".repeat(64)), &dest);
+        assert!(matches!(result, Err(DownloadError::Checksum { .. })));
+        assert!(!dest.join("src").exists());
+
+        let missing = download_crate(&server.url, "baz", "0.1.0", None, &dest);
+        assert!(matches!(
+            missing,
+            Err(DownloadError::Archive(ArchiveError::Fetch { .. }))
+        ));
+
+        // Files outside the `<name>-<version>/` directory: nothing to unpack
+        let body = tar_gz("", &[("lib.rs", "")]);
+        let server = test_http::serve(vec![("/qux/qux-0.1.0.crate", body)]);
+        let empty = download_crate(&server.url, "qux", "0.1.0", None, &dest);
+        assert!(matches!(empty, Err(DownloadError::Empty { .. })));
+
+        let _ = std::fs::remove_dir_all(&dest);
+    }
+}
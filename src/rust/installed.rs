@@ -0,0 +1,65 @@
+//! Crates already unpacked by Cargo
+//!
+//! Cargo unpacks every downloaded crate into
+//! `$CARGO_HOME/registry/src/<registry>/<name>-<version>/` (CARGO_HOME defaults
+//! to `~/.cargo`) and writes `.cargo-ok` once the unpack is complete.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::installed::InstalledSource;
+
+/// Find `name@version` in Cargo's unpacked registry sources
+pub fn find_installed(name: &str, version: &str) -> Option<InstalledSource> {
+    let cargo_home = std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|h| h.join(".cargo")))?;
+    find_in_registry_src(&cargo_home.join("registry").join("src"), name, version)
+}
+
+/// Look for `<name>-<version>` under each registry directory of `registry_src`
+fn find_in_registry_src(registry_src: &Path, name: &str, version: &str) -> Option<InstalledSource> {
+    let mut registries: Vec<PathBuf> = fs::read_dir(registry_src)
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .collect();
+    registries.sort();
+
+    registries
+        .into_iter()
+        .map(|registry| registry.join(format!("{}-{}", name, version)))
+        .find(|dir| dir.join(".cargo-ok").is_file())
+        .map(InstalledSource::dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_in_registry_src() {
+        let root = std::env::temp_dir().join("dotdeps_rust_installed_test");
+        let _ = fs::remove_dir_all(&root);
+        let registry = root.join("index.crates.io-6f17d22bba15001f");
+
+        let serde = registry.join("serde-1.0.200");
+        fs::create_dir_all(&serde).unwrap();
+        fs::write(serde.join(".cargo-ok"), "{\"v\":1}").unwrap();
+        // Unpack interrupted before `.cargo-ok` was written
+        fs::create_dir_all(registry.join("tokio-1.37.0")).unwrap();
+
+        assert_eq!(
+            find_in_registry_src(&root, "serde", "1.0.200"),
+            Some(InstalledSource::dir(serde))
+        );
+        assert_eq!(find_in_registry_src(&root, "serde", "1.0.199"), None);
+        assert_eq!(find_in_registry_src(&root, "tokio", "1.37.0"), None);
+        assert_eq!(
+            find_in_registry_src(&root.join("missing"), "serde", "1.0.200"),
+            None
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -2,9 +2,11 @@
 //!
 //! Supports finding package versions from Cargo.lock
 //!
-//! Note: Cargo.lock can have git dependencies with `source = "git+..."` but
-//! this implementation currently only returns version strings. Git dependency
-//! detection could be added by parsing the source field.
+//! The `source` field of each package decides how it is fetched:
+//! - `registry+https://github.com/rust-lang/crates.io-index` (or `sparse+https://index.crates.io/`)
+//!   -> crates.io version
+//! - `git+https://github.com/org/repo?rev=abc#<sha>` -> git dependency at the pinned commit
+//! - any other registry -> error, since crates.io metadata would describe a different crate
 
 use crate::cli::VersionInfo;
 use crate::lockfile::find_nearest_file;
@@ -31,8 +33,19 @@ pub enum LockfileError {
 
     #[error("Failed to parse {path}: {details}")]
     Parse { path: PathBuf, details: String },
+
+    #[error(
+        "'{package}' comes from the registry {registry}, not crates.io. Specify version explicitly and add a repo override to ~/.config/dotdeps/config.json"
+    )]
+    AlternativeRegistry { package: String, registry: String },
 }
 
+/// Source identifiers of crates.io in Cargo.lock
+const CRATES_IO_SOURCES: &[&str] = &[
+    "registry+https://github.com/rust-lang/crates.io-index",
+    "sparse+https://index.crates.io/",
+];
+
 /// Find the version of a crate by searching Cargo.lock
 ///
 /// Searches upward from the current directory for Cargo.lock
@@ -41,6 +54,23 @@ pub fn find_version(package: &str) -> Result<VersionInfo, LockfileError> {
     parse_version_from_lockfile(&lockfile, package)
 }
 
+/// A crate version as Cargo.lock records it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockedCrate {
+    /// Name as published, with its capitalization (e.g., `Inflector`)
+    pub name: String,
+    /// SHA-256 of the published `.crate` file (registry packages only)
+    pub checksum: Option<String>,
+}
+
+/// Find the entry Cargo.lock records for `package` at `version`
+///
+/// Returns None if there is no Cargo.lock or it doesn't list that version.
+pub fn find_locked_crate(package: &str, version: &str) -> Option<LockedCrate> {
+    let lockfile = find_lockfile_path().ok()?;
+    parse_locked_crate_from_lockfile(&lockfile, package, version)
+}
+
 /// Find the nearest Cargo.lock by walking up from current directory
 pub fn find_lockfile_path() -> Result<PathBuf, LockfileError> {
     find_nearest_file(&["Cargo.lock"]).ok_or(LockfileError::NotFound)
@@ -84,6 +114,10 @@ struct CargoLockfile {
 struct CargoPackage {
     name: String,
     version: String,
+    /// Where the package comes from (absent for path dependencies and workspace members)
+    source: Option<String>,
+    /// SHA-256 of the published `.crate` file (registry packages only)
+    checksum: Option<String>,
 }
 
 /// Parse version from Cargo.lock
@@ -104,7 +138,7 @@ fn parse_version_from_lockfile(path: &Path, package: &str) -> Result<VersionInfo
     // Crate names are case-insensitive and use - or _ interchangeably
     for pkg in packages {
         if normalize_crate_name(&pkg.name) == normalized_package {
-            return Ok(VersionInfo::Version(pkg.version));
+            return version_info_from_source(&pkg);
         }
     }
 
@@ -113,6 +147,59 @@ fn parse_version_from_lockfile(path: &Path, package: &str) -> Result<VersionInfo
     })
 }
 
+/// Parse the entry of `package` at `version` from Cargo.lock
+fn parse_locked_crate_from_lockfile(
+    path: &Path,
+    package: &str,
+    version: &str,
+) -> Option<LockedCrate> {
+    let content = fs::read_to_string(path).ok()?;
+    let lockfile: CargoLockfile = toml::from_str(&content).ok()?;
+    let normalized_package = normalize_crate_name(package);
+
+    lockfile
+        .package?
+        .into_iter()
+        .find(|pkg| normalize_crate_name(&pkg.name) == normalized_package && pkg.version == version)
+        .map(|pkg| LockedCrate {
+            name: pkg.name,
+            checksum: pkg.checksum,
+        })
+}
+
+/// Build the VersionInfo for a Cargo.lock package from its `source`
+fn version_info_from_source(pkg: &CargoPackage) -> Result<VersionInfo, LockfileError> {
+    let Some(source) = pkg.source.as_deref() else {
+        return Ok(VersionInfo::Version(pkg.version.clone()));
+    };
+
+    if let Some(git) = source.strip_prefix("git+") {
+        // git+<url>[?branch=..|?tag=..|?rev=..]#<commit>
+        if let Some((location, commit)) = git.split_once('#') {
+            let url = location.split('?').next().unwrap_or(location);
+            return Ok(VersionInfo::Git {
+                url: url.to_string(),
+                commit: commit.to_string(),
+            });
+        }
+        // Malformed git source without a commit, fall back to version
+        return Ok(VersionInfo::Version(pkg.version.clone()));
+    }
+
+    if CRATES_IO_SOURCES.contains(&source) {
+        return Ok(VersionInfo::Version(pkg.version.clone()));
+    }
+
+    let registry = source
+        .strip_prefix("registry+")
+        .or_else(|| source.strip_prefix("sparse+"))
+        .unwrap_or(source);
+    Err(LockfileError::AlternativeRegistry {
+        package: pkg.name.clone(),
+        registry: registry.to_string(),
+    })
+}
+
 fn parse_cargo_toml_dependencies(path: &Path) -> Result<Vec<String>, LockfileError> {
     let content = fs::read_to_string(path).map_err(|source| LockfileError::ReadFile {
         path: path.to_path_buf(),
@@ -206,7 +293,7 @@ fn list_packages_from_cargo_lock(path: &Path) -> Result<Vec<String>, LockfileErr
 /// Normalize crate name for comparison
 ///
 /// Crate names are case-insensitive and treat - and _ as equivalent
-fn normalize_crate_name(name: &str) -> String {
+pub(super) fn normalize_crate_name(name: &str) -> String {
     name.to_lowercase().replace('-', "_")
 }
 
@@ -306,4 +393,125 @@ libc = "0.2"
         assert!(deps.contains(&"libc".to_string()));
         assert!(!deps.contains(&"local".to_string()));
     }
+
+    #[test]
+    fn test_parse_version_from_lockfile_sources() {
+        let content = r#"
+version = 4
+
+[[package]]
+name = "serde"
+version = "1.0.228"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "tokio"
+version = "1.40.0"
+source = "sparse+https://index.crates.io/"
+
+[[package]]
+name = "patched"
+version = "0.3.0"
+source = "git+https://github.com/org/patched?rev=0e322af#0e322af87745eff34caffe4df68456ebc20d9068"
+
+[[package]]
+name = "on-branch"
+version = "0.1.0"
+source = "git+https://github.com/org/on-branch.git?branch=main#abc123def4567890abc123def4567890abc123de"
+
+[[package]]
+name = "internal"
+version = "2.0.0"
+source = "sparse+https://crates.example.com/index/"
+
+[[package]]
+name = "my-app"
+version = "0.1.0"
+"#;
+        let path = write_temp_file("Cargo.lock", content);
+
+        assert_eq!(
+            parse_version_from_lockfile(&path, "serde").unwrap(),
+            VersionInfo::Version("1.0.228".to_string())
+        );
+        assert_eq!(
+            parse_version_from_lockfile(&path, "tokio").unwrap(),
+            VersionInfo::Version("1.40.0".to_string())
+        );
+        assert_eq!(
+            parse_version_from_lockfile(&path, "patched").unwrap(),
+            VersionInfo::Git {
+                url: "https://github.com/org/patched".to_string(),
+                commit: "0e322af87745eff34caffe4df68456ebc20d9068".to_string(),
+            }
+        );
+        assert_eq!(
+            parse_version_from_lockfile(&path, "on_branch").unwrap(),
+            VersionInfo::Git {
+                url: "https://github.com/org/on-branch.git".to_string(),
+                commit: "abc123def4567890abc123def4567890abc123de".to_string(),
+            }
+        );
+        assert_eq!(
+            parse_version_from_lockfile(&path, "my-app").unwrap(),
+            VersionInfo::Version("0.1.0".to_string())
+        );
+
+        match parse_version_from_lockfile(&path, "internal") {
+            Err(LockfileError::AlternativeRegistry { package, registry }) => {
+                assert_eq!(package, "internal");
+                assert_eq!(registry, "https://crates.example.com/index/");
+            }
+            other => panic!("expected AlternativeRegistry, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_locked_crate_from_lockfile() {
+        let content = r#"
+version = 4
+
+[[package]]
+name = "serde_json"
+version = "1.0.100"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "f1e14e89be7aa4c4b78bdbdc9eb5bf8517829a600ae8eaa39a6e1d960b5185c"
+
+[[package]]
+name = "serde_json"
+version = "1.0.140"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "20068b6e96dc6c9bd23e01df8827e6c7e1f2fddd43c21810382803c136b99373"
+
+[[package]]
+name = "Inflector"
+version = "0.11.4"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "fe438c63458706e03479442743baae6c88256498e6431708f6dfc520a26515d3"
+
+[[package]]
+name = "my-app"
+version = "0.1.0"
+"#;
+        let path = write_temp_file("Cargo.lock", content);
+
+        let locked = parse_locked_crate_from_lockfile(&path, "serde-json", "1.0.140").unwrap();
+        assert_eq!(locked.name, "serde_json");
+        assert_eq!(
+            locked.checksum.as_deref(),
+            Some("20068b6e96dc6c9bd23e01df8827e6c7e1f2fddd43c21810382803c136b99373")
+        );
+        assert_eq!(
+            parse_locked_crate_from_lockfile(&path, "serde_json", "1.0.0"),
+            None
+        );
+        assert_eq!(
+            parse_locked_crate_from_lockfile(&path, "inflector", "0.11.4")
+                .unwrap()
+                .name,
+            "Inflector"
+        );
+        let app = parse_locked_crate_from_lockfile(&path, "my-app", "0.1.0").unwrap();
+        assert_eq!(app.checksum, None);
+    }
 }
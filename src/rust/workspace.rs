@@ -0,0 +1,153 @@
+//! Locating a crate inside a cloned repository
+//!
+//! Crates such as `tokio-util`, `serde_derive` or `bevy_ecs` are members of a
+//! Cargo workspace and live in a subdirectory of their repository. The member
+//! is found by its `Cargo.toml`:
+//!
+//! ```toml
+//! [package]
+//! name = "tokio-util"
+//! ```
+
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+use super::lockfile::normalize_crate_name;
+
+/// How deep to search below the repository root (e.g. `sdk/s3` in aws-sdk-rust)
+const MAX_DEPTH: usize = 4;
+
+/// Directories that never hold workspace members
+const SKIPPED_DIRS: &[&str] = &["target", "node_modules", "vendor"];
+
+#[derive(Deserialize)]
+struct Manifest {
+    package: Option<ManifestPackage>,
+}
+
+#[derive(Deserialize)]
+struct ManifestPackage {
+    name: String,
+}
+
+/// Find the directory of the crate `package` in a checkout
+///
+/// Returns the path relative to `checkout` (with `/` separators), or None if
+/// the crate is at the root or no member declares it. Shallower members win.
+pub fn find_crate_dir(checkout: &Path, package: &str) -> Option<String> {
+    let wanted = normalize_crate_name(package);
+    let mut level = vec![checkout.to_path_buf()];
+
+    for _ in 0..=MAX_DEPTH {
+        let mut next = Vec::new();
+        for dir in &level {
+            if manifest_name(dir).is_some_and(|name| normalize_crate_name(&name) == wanted) {
+                return relative_subdir(checkout, dir);
+            }
+            next.extend(child_dirs(dir));
+        }
+        next.sort();
+        level = next;
+    }
+
+    None
+}
+
+/// Read `[package] name` from a directory's Cargo.toml
+fn manifest_name(dir: &Path) -> Option<String> {
+    let content = fs::read_to_string(dir.join("Cargo.toml")).ok()?;
+    let manifest: Manifest = toml::from_str(&content).ok()?;
+    manifest.package.map(|p| p.name)
+}
+
+/// Subdirectories worth searching (no hidden, build or vendored directories)
+fn child_dirs(dir: &Path) -> Vec<std::path::PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            !name.starts_with('.') && !SKIPPED_DIRS.contains(&name.as_ref())
+        })
+        .map(|entry| entry.path())
+        .collect()
+}
+
+/// `dir` relative to `root`, or None if they are the same
+fn relative_subdir(root: &Path, dir: &Path) -> Option<String> {
+    let relative = dir.strip_prefix(root).ok()?;
+    let parts: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_manifest(root: &Path, dir: &str, content: &str) {
+        let dir = root.join(dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("Cargo.toml"), content).unwrap();
+    }
+
+    #[test]
+    fn test_find_crate_dir() {
+        let root = std::env::temp_dir().join("dotdeps_rust_find_crate_dir_test");
+        let _ = fs::remove_dir_all(&root);
+
+        write_manifest(
+            &root,
+            "",
+            "[workspace]\nmembers = [\"tokio\", \"tokio-util\", \"crates/*\"]\n",
+        );
+        write_manifest(&root, "tokio", "[package]\nname = \"tokio\"\n");
+        write_manifest(
+            &root,
+            "tokio-util",
+            "[package]\nname = \"tokio-util\"\nversion.workspace = true\n",
+        );
+        write_manifest(&root, "crates/bevy_ecs", "[package]\nname = \"bevy_ecs\"\n");
+        write_manifest(
+            &root,
+            "target/package/tokio",
+            "[package]\nname = \"tokio\"\n",
+        );
+        write_manifest(&root, ".git/x", "[package]\nname = \"hidden\"\n");
+        write_manifest(&root, "broken", "[package\n");
+
+        assert_eq!(
+            find_crate_dir(&root, "tokio-util"),
+            Some("tokio-util".to_string())
+        );
+        assert_eq!(
+            find_crate_dir(&root, "bevy-ecs"),
+            Some("crates/bevy_ecs".to_string())
+        );
+        assert_eq!(find_crate_dir(&root, "tokio"), Some("tokio".to_string()));
+        assert_eq!(find_crate_dir(&root, "hidden"), None);
+        assert_eq!(find_crate_dir(&root, "missing"), None);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_find_crate_dir_at_root() {
+        let root = std::env::temp_dir().join("dotdeps_rust_find_crate_root_test");
+        let _ = fs::remove_dir_all(&root);
+
+        write_manifest(&root, "", "[package]\nname = \"serde_json\"\n");
+        write_manifest(&root, "nested", "[package]\nname = \"serde_json\"\n");
+
+        assert_eq!(find_crate_dir(&root, "serde_json"), None);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,279 @@
+//! Resolved state of .deps/
+//!
+//! Records how every `.deps/` entry was resolved in `.deps/.dotdeps-state.json`:
+//!
+//! ```json
+//! {
+//!   "dependencies": [
+//!     {
+//!       "ecosystem": "python",
+//!       "package": "requests",
+//!       "version": "2.31.0",
+//!       "resolved_ref": "v2.31.0",
+//!       "commit": "a1b2c3d4e5f6...",
+//!       "repo_url": "https://github.com/psf/requests.git",
+//!       "lockfile": "/path/to/project/poetry.lock"
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! `list` and `context` read it to report real provenance instead of guessing
+//! the version from the symlink target's directory name.
+
+use crate::cli::Ecosystem;
+use crate::deps;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// State filename inside `.deps/`
+pub const STATE_FILE: &str = ".dotdeps-state.json";
+
+#[derive(Error, Debug)]
+pub enum StateError {
+    #[error("Failed to read {path}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Failed to parse {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+
+    #[error("Failed to write {path}: {source}")]
+    Write {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+/// Resolution record for a single `.deps/` entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateEntry {
+    pub ecosystem: String,
+    pub package: String,
+    /// Version given on the command line or in `.deps.toml` (None if from lockfile)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requested_version: Option<String>,
+    /// Resolved version, as used for the cache path
+    pub version: String,
+    /// Tag, commit, or "default branch" that was checked out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_ref: Option<String>,
+    /// Full commit SHA of the checkout
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// Repository the checkout was cloned from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo_url: Option<String>,
+    /// Lockfile the version was read from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lockfile: Option<String>,
+}
+
+impl StateEntry {
+    /// Short human-readable provenance, e.g. `v2.31.0, commit 0e322af87745`
+    pub fn provenance(&self) -> Option<String> {
+        let commit = self
+            .commit
+            .as_deref()
+            .map(|c| format!("commit {}", &c[..c.len().min(12)]));
+        match (self.resolved_ref.as_deref(), commit) {
+            (Some(r), Some(c)) if self.commit.as_deref() != Some(r) => {
+                Some(format!("{}, {}", r, c))
+            }
+            (_, Some(c)) => Some(c),
+            (Some(r), None) => Some(r.to_string()),
+            (None, None) => None,
+        }
+    }
+
+    fn matches(&self, ecosystem: Ecosystem, package: &str) -> bool {
+        self.ecosystem == ecosystem.to_string() && self.package == package
+    }
+}
+
+/// Contents of `.deps/.dotdeps-state.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct State {
+    #[serde(default)]
+    pub dependencies: Vec<StateEntry>,
+}
+
+impl State {
+    /// Load the state file, or return an empty state if it does not exist
+    pub fn load() -> Result<Self, StateError> {
+        let path = state_path();
+        if !path.exists() {
+            return Ok(State::default());
+        }
+
+        let content = fs::read_to_string(&path).map_err(|source| StateError::Read {
+            path: path.clone(),
+            source,
+        })?;
+
+        serde_json::from_str(&content).map_err(|source| StateError::Parse { path, source })
+    }
+
+    /// Load the state file, warning and returning an empty state if it can't be read
+    ///
+    /// A corrupt or hand-edited file shouldn't stop `list`, `remove` or `context`;
+    /// like [`State::save`] failures after an add, it is only worth a warning.
+    pub fn load_or_default() -> Self {
+        State::load().unwrap_or_else(|e| {
+            eprintln!("Warning: {}. Ignoring recorded state.", e);
+            State::default()
+        })
+    }
+
+    /// Write the state file atomically (temp file + rename)
+    ///
+    /// Does nothing if `.deps/` does not exist.
+    pub fn save(&self) -> Result<(), StateError> {
+        let path = state_path();
+        if !deps::deps_dir().is_dir() {
+            return Ok(());
+        }
+
+        let json = serde_json::to_string_pretty(self).expect("state is always serializable");
+        let temp_path = path.with_extension(format!("json.tmp-{}", std::process::id()));
+
+        fs::write(&temp_path, json + "\n")
+            .and_then(|_| fs::rename(&temp_path, &path))
+            .map_err(|source| {
+                let _ = fs::remove_file(&temp_path);
+                StateError::Write { path, source }
+            })
+    }
+
+    /// Look up the entry for an ecosystem/package pair
+    pub fn get(&self, ecosystem: Ecosystem, package: &str) -> Option<&StateEntry> {
+        self.dependencies
+            .iter()
+            .find(|e| e.matches(ecosystem, package))
+    }
+
+    /// Insert or replace the entry for `entry`'s ecosystem/package pair
+    pub fn upsert(&mut self, entry: StateEntry) {
+        self.dependencies
+            .retain(|e| !(e.ecosystem == entry.ecosystem && e.package == entry.package));
+        self.dependencies.push(entry);
+        self.dependencies.sort_by(|a, b| {
+            a.ecosystem
+                .cmp(&b.ecosystem)
+                .then_with(|| a.package.cmp(&b.package))
+        });
+    }
+
+    /// Remove the entry for an ecosystem/package pair. Returns true if it existed.
+    pub fn remove(&mut self, ecosystem: Ecosystem, package: &str) -> bool {
+        let before = self.dependencies.len();
+        self.dependencies.retain(|e| !e.matches(ecosystem, package));
+        self.dependencies.len() != before
+    }
+}
+
+/// Returns the state file path: `.deps/.dotdeps-state.json`
+pub fn state_path() -> PathBuf {
+    deps::deps_dir().join(STATE_FILE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(ecosystem: &str, package: &str, version: &str) -> StateEntry {
+        StateEntry {
+            ecosystem: ecosystem.to_string(),
+            package: package.to_string(),
+            requested_version: None,
+            version: version.to_string(),
+            resolved_ref: None,
+            commit: None,
+            repo_url: None,
+            lockfile: None,
+        }
+    }
+
+    #[test]
+    fn test_upsert_replaces_and_sorts() {
+        let mut state = State::default();
+        state.upsert(entry("python", "requests", "2.30.0"));
+        state.upsert(entry("node", "lodash", "4.17.21"));
+        state.upsert(entry("python", "requests", "2.31.0"));
+
+        assert_eq!(state.dependencies.len(), 2);
+        assert_eq!(state.dependencies[0].package, "lodash");
+        assert_eq!(
+            state.get(Ecosystem::Python, "requests").unwrap().version,
+            "2.31.0"
+        );
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut state = State::default();
+        state.upsert(entry("python", "requests", "2.31.0"));
+
+        assert!(state.remove(Ecosystem::Python, "requests"));
+        assert!(!state.remove(Ecosystem::Python, "requests"));
+        assert!(state.get(Ecosystem::Python, "requests").is_none());
+    }
+
+    #[test]
+    fn test_parse_state_file() {
+        let json = r#"{
+            "dependencies": [
+                {
+                    "ecosystem": "python",
+                    "package": "requests",
+                    "version": "2.31.0",
+                    "resolved_ref": "v2.31.0",
+                    "commit": "0e322af87745eff34caffe4df68456ebc20d9068",
+                    "repo_url": "https://github.com/psf/requests.git",
+                    "lockfile": "poetry.lock"
+                }
+            ]
+        }"#;
+
+        let state: State = serde_json::from_str(json).unwrap();
+        let entry = state.get(Ecosystem::Python, "requests").unwrap();
+        assert_eq!(entry.resolved_ref.as_deref(), Some("v2.31.0"));
+        assert_eq!(entry.requested_version, None);
+        assert_eq!(entry.lockfile.as_deref(), Some("poetry.lock"));
+    }
+
+    #[test]
+    fn test_provenance() {
+        let mut e = entry("python", "requests", "2.31.0");
+        assert_eq!(e.provenance(), None);
+
+        e.resolved_ref = Some("v2.31.0".to_string());
+        assert_eq!(e.provenance().as_deref(), Some("v2.31.0"));
+
+        e.commit = Some("0e322af87745eff34caffe4df68456ebc20d9068".to_string());
+        assert_eq!(
+            e.provenance().as_deref(),
+            Some("v2.31.0, commit 0e322af87745")
+        );
+
+        // Git dependencies resolve to the commit itself
+        e.resolved_ref = e.commit.clone();
+        assert_eq!(e.provenance().as_deref(), Some("commit 0e322af87745"));
+    }
+
+    #[test]
+    fn test_serialize_skips_missing_fields() {
+        let json = serde_json::to_string(&entry("node", "lodash", "4.17.21")).unwrap();
+        assert_eq!(
+            json,
+            r#"{"ecosystem":"node","package":"lodash","version":"4.17.21"}"#
+        );
+    }
+}
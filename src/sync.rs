@@ -0,0 +1,244 @@
+//! Sync planning for `dotdeps sync`
+//!
+//! Compares the dependencies listed in `.deps.toml` against the entries
+//! currently linked in `.deps/` and produces the actions needed to make
+//! them match:
+//! - missing entries are added
+//! - entries linked at a different (or evicted) version are re-linked
+//! - entries not listed in the manifest are removed
+
+use crate::cli::{Ecosystem, VersionInfo};
+use crate::deps::DepEntry;
+
+/// What sync will do for a single dependency
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncAction {
+    /// Listed in the manifest but missing from .deps/
+    Add,
+    /// Linked at a different version than the manifest resolves to
+    Relink { from: String },
+    /// Present in .deps/ but not listed in the manifest
+    Remove,
+    /// Already linked at the resolved version
+    Unchanged,
+    /// Local path dependency, never fetched
+    Skip { path: String },
+    /// Version could not be resolved
+    Failed { message: String },
+}
+
+impl SyncAction {
+    /// Short machine-readable name used in JSON output
+    pub fn name(&self) -> &'static str {
+        match self {
+            SyncAction::Add => "add",
+            SyncAction::Relink { .. } => "relink",
+            SyncAction::Remove => "remove",
+            SyncAction::Unchanged => "unchanged",
+            SyncAction::Skip { .. } => "skip",
+            SyncAction::Failed { .. } => "failed",
+        }
+    }
+}
+
+/// A manifest entry with its resolved version
+pub struct DesiredDep {
+    pub ecosystem: Ecosystem,
+    pub package: String,
+    /// Resolved version, or the resolution error message
+    pub resolved: Result<VersionInfo, String>,
+}
+
+/// A planned action for a single dependency
+#[derive(Debug, Clone)]
+pub struct PlannedAction {
+    pub ecosystem: Ecosystem,
+    pub package: String,
+    /// The version the dependency resolves to (None for removals and failures)
+    pub version_info: Option<VersionInfo>,
+    pub action: SyncAction,
+}
+
+impl PlannedAction {
+    /// Version string shown to the user for this action
+    pub fn version(&self) -> Option<&str> {
+        self.version_info.as_ref().and_then(|v| v.cache_version())
+    }
+}
+
+/// Build the list of actions needed to make `.deps/` match the manifest
+///
+/// Manifest entries come first (in manifest order), followed by removals.
+pub fn plan(desired: Vec<DesiredDep>, current: &[DepEntry]) -> Vec<PlannedAction> {
+    let mut actions = Vec::new();
+
+    for dep in &desired {
+        let existing = current
+            .iter()
+            .find(|e| e.ecosystem == dep.ecosystem && e.package == dep.package);
+
+        let (version_info, action) = match &dep.resolved {
+            Err(message) => (
+                None,
+                SyncAction::Failed {
+                    message: message.clone(),
+                },
+            ),
+            Ok(info) => {
+                let action = match (info, existing) {
+                    (VersionInfo::LocalPath { path }, _) => SyncAction::Skip { path: path.clone() },
+                    (_, None) => SyncAction::Add,
+                    (_, Some(entry))
+                        if entry.is_broken
+                            || Some(entry.version.as_str()) != info.cache_version() =>
+                    {
+                        SyncAction::Relink {
+                            from: entry.version.clone(),
+                        }
+                    }
+                    (_, Some(_)) => SyncAction::Unchanged,
+                };
+                (Some(info.clone()), action)
+            }
+        };
+
+        actions.push(PlannedAction {
+            ecosystem: dep.ecosystem,
+            package: dep.package.clone(),
+            version_info,
+            action,
+        });
+    }
+
+    for entry in current {
+        let listed = desired
+            .iter()
+            .any(|d| d.ecosystem == entry.ecosystem && d.package == entry.package);
+        if !listed {
+            actions.push(PlannedAction {
+                ecosystem: entry.ecosystem,
+                package: entry.package.clone(),
+                version_info: None,
+                action: SyncAction::Remove,
+            });
+        }
+    }
+
+    actions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn entry(ecosystem: Ecosystem, package: &str, version: &str, is_broken: bool) -> DepEntry {
+        DepEntry {
+            ecosystem,
+            package: package.to_string(),
+            version: version.to_string(),
+            path: PathBuf::new(),
+            target: PathBuf::new(),
+            is_broken,
+            resolution: None,
+        }
+    }
+
+    fn desired(ecosystem: Ecosystem, package: &str, version: &str) -> DesiredDep {
+        DesiredDep {
+            ecosystem,
+            package: package.to_string(),
+            resolved: Ok(VersionInfo::Version(version.to_string())),
+        }
+    }
+
+    #[test]
+    fn test_plan_add_relink_remove_unchanged() {
+        let current = vec![
+            entry(Ecosystem::Python, "requests", "2.30.0", false),
+            entry(Ecosystem::Node, "lodash", "4.17.21", false),
+            entry(Ecosystem::Rust, "serde", "1.0.0", false),
+        ];
+        let wanted = vec![
+            desired(Ecosystem::Python, "requests", "2.31.0"),
+            desired(Ecosystem::Node, "lodash", "4.17.21"),
+            desired(Ecosystem::Go, "github.com/gin-gonic/gin", "1.9.1"),
+        ];
+
+        let actions = plan(wanted, &current);
+        let summary: Vec<(&str, &str)> = actions
+            .iter()
+            .map(|a| (a.package.as_str(), a.action.name()))
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                ("requests", "relink"),
+                ("lodash", "unchanged"),
+                ("github.com/gin-gonic/gin", "add"),
+                ("serde", "remove"),
+            ]
+        );
+        assert_eq!(
+            actions[0].action,
+            SyncAction::Relink {
+                from: "2.30.0".to_string()
+            }
+        );
+        assert_eq!(actions[0].version(), Some("2.31.0"));
+    }
+
+    #[test]
+    fn test_plan_relinks_broken_entry() {
+        let current = vec![entry(Ecosystem::Python, "requests", "2.31.0", true)];
+        let wanted = vec![desired(Ecosystem::Python, "requests", "2.31.0")];
+
+        let actions = plan(wanted, &current);
+        assert_eq!(actions[0].action.name(), "relink");
+    }
+
+    #[test]
+    fn test_plan_git_dependency_uses_short_commit() {
+        let current = vec![entry(Ecosystem::Python, "pkg", "abc123def456", false)];
+        let wanted = vec![DesiredDep {
+            ecosystem: Ecosystem::Python,
+            package: "pkg".to_string(),
+            resolved: Ok(VersionInfo::Git {
+                url: "https://github.com/org/pkg.git".to_string(),
+                commit: "abc123def4567890abc123def4567890abc123de".to_string(),
+            }),
+        }];
+
+        let actions = plan(wanted, &current);
+        assert_eq!(actions[0].action, SyncAction::Unchanged);
+    }
+
+    #[test]
+    fn test_plan_local_path_and_failure() {
+        let wanted = vec![
+            DesiredDep {
+                ecosystem: Ecosystem::Python,
+                package: "local".to_string(),
+                resolved: Ok(VersionInfo::LocalPath {
+                    path: "../local".to_string(),
+                }),
+            },
+            DesiredDep {
+                ecosystem: Ecosystem::Python,
+                package: "missing".to_string(),
+                resolved: Err("Version not found".to_string()),
+            },
+        ];
+
+        let actions = plan(wanted, &[]);
+        assert_eq!(
+            actions[0].action,
+            SyncAction::Skip {
+                path: "../local".to_string()
+            }
+        );
+        assert_eq!(actions[1].action.name(), "failed");
+        assert_eq!(actions[1].version(), None);
+    }
+}
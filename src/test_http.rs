@@ -0,0 +1,69 @@
+//! Minimal local HTTP server for tests
+//!
+//! Serves fixed responses by request path (query string included) so registry
+//! and discovery code can be exercised without network access.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+
+/// A running test server
+pub struct TestServer {
+    /// Base URL, e.g. `http://127.0.0.1:12345`
+    pub url: String,
+    /// Paths requested so far, in order
+    pub requests: Arc<Mutex<Vec<String>>>,
+}
+
+/// Start a server that answers `routes` (path -> body) with 200 and anything else with 404
+///
+/// The server thread runs until the test process exits.
+pub fn serve(routes: Vec<(&str, Vec<u8>)>) -> TestServer {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind test server");
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let routes: HashMap<String, Vec<u8>> = routes
+        .into_iter()
+        .map(|(path, body)| (path.to_string(), body))
+        .collect();
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let seen = Arc::clone(&requests);
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut request_line = String::new();
+            if reader.read_line(&mut request_line).is_err() {
+                continue;
+            }
+            // Drain headers
+            let mut line = String::new();
+            while reader.read_line(&mut line).is_ok_and(|n| n > 2) {
+                line.clear();
+            }
+
+            let path = request_line
+                .split_whitespace()
+                .nth(1)
+                .unwrap_or("/")
+                .to_string();
+            seen.lock().unwrap().push(path.clone());
+
+            let (status, body) = match routes.get(&path) {
+                Some(body) => ("200 OK", body.clone()),
+                None => ("404 Not Found", b"not found".to_vec()),
+            };
+            let header = format!(
+                "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                status,
+                body.len()
+            );
+            let _ = stream.write_all(header.as_bytes());
+            let _ = stream.write_all(&body);
+        }
+    });
+
+    TestServer { url, requests }
+}
@@ -90,7 +90,7 @@ fn latest_release_tag() -> Result<String, Box<dyn std::error::Error>> {
         "{}/{}/{}/releases/latest",
         GITHUB_BASE_URL, REPO_OWNER, REPO_NAME
     );
-    let response = ureq::get(&url).header("User-Agent", "dotdeps").call()?;
+    let response = crate::network::get(&url)?;
     let final_url = response.get_uri().to_string();
 
     let marker = "/releases/tag/";
@@ -194,7 +194,7 @@ fn download_to_path(
         eprintln!("Downloading {}", url);
     }
 
-    let response = ureq::get(url).header("User-Agent", "dotdeps").call()?;
+    let response = crate::network::get(url)?;
     let mut reader = response.into_body().into_reader();
     let mut out = fs::File::create(dest)?;
     io::copy(&mut reader, &mut out)?;
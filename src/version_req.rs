@@ -0,0 +1,369 @@
+//! Version constraints, for version-scoped overrides
+//!
+//! A constraint is one or more comparisons separated by commas or spaces, all
+//! of which must hold: `<2.0`, `>=3.1, <4`, `!=1.2.3`. A bare version means
+//! `=`. Versions are compared with the ecosystem's own ordering: PEP 440 for
+//! Python, `Gem::Version` for Ruby, semver for the others (with missing
+//! components counting as zero, so `<2.0` excludes `2.0.0`).
+
+use crate::cli::Ecosystem;
+use std::cmp::Ordering;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum VersionReqError {
+    #[error("Empty version constraint")]
+    Empty,
+
+    #[error("Invalid version constraint \"{constraint}\": unknown operator \"{operator}\"")]
+    UnknownOperator {
+        constraint: String,
+        operator: String,
+    },
+
+    #[error("Invalid version constraint \"{constraint}\": missing version after \"{operator}\"")]
+    MissingVersion {
+        constraint: String,
+        operator: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl Op {
+    fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            Op::Lt => ordering.is_lt(),
+            Op::Le => ordering.is_le(),
+            Op::Gt => ordering.is_gt(),
+            Op::Ge => ordering.is_ge(),
+            Op::Eq => ordering.is_eq(),
+            Op::Ne => ordering.is_ne(),
+        }
+    }
+}
+
+/// A parsed version constraint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionReq {
+    comparators: Vec<(Op, String)>,
+}
+
+impl FromStr for VersionReq {
+    type Err = VersionReqError;
+
+    fn from_str(constraint: &str) -> Result<Self, Self::Err> {
+        let is_separator = |c: char| c == ',' || c.is_whitespace();
+        let is_operator = |c: char| matches!(c, '<' | '>' | '=' | '!');
+
+        let mut comparators = Vec::new();
+        let mut rest = constraint.trim_start_matches(is_separator);
+        while !rest.is_empty() {
+            let operator_len = rest.find(|c| !is_operator(c)).unwrap_or(rest.len());
+            let (operator, after) = rest.split_at(operator_len);
+            let op = match operator {
+                "<" => Op::Lt,
+                "<=" => Op::Le,
+                ">" => Op::Gt,
+                ">=" => Op::Ge,
+                "" | "=" | "==" => Op::Eq,
+                "!=" => Op::Ne,
+                _ => {
+                    return Err(VersionReqError::UnknownOperator {
+                        constraint: constraint.to_string(),
+                        operator: operator.to_string(),
+                    });
+                }
+            };
+
+            let after = after.trim_start();
+            // `!` only ends a version as part of `!=`: PEP 440 epochs use it (`1!2.0`)
+            let version_len = after
+                .char_indices()
+                .find(|&(i, c)| {
+                    is_separator(c) || matches!(c, '<' | '>' | '=') || after[i..].starts_with("!=")
+                })
+                .map_or(after.len(), |(i, _)| i);
+            if version_len == 0 {
+                return Err(VersionReqError::MissingVersion {
+                    constraint: constraint.to_string(),
+                    operator: operator.to_string(),
+                });
+            }
+            let (version, after) = after.split_at(version_len);
+            // Versions start with a letter or digit; anything else is an
+            // operator from another syntax (`~>`, `^`)
+            if !version.starts_with(|c: char| c.is_ascii_alphanumeric()) {
+                return Err(VersionReqError::UnknownOperator {
+                    constraint: constraint.to_string(),
+                    operator: version
+                        .chars()
+                        .take_while(|c| !c.is_ascii_alphanumeric())
+                        .collect(),
+                });
+            }
+            comparators.push((op, version.to_string()));
+            rest = after.trim_start_matches(is_separator);
+        }
+
+        if comparators.is_empty() {
+            return Err(VersionReqError::Empty);
+        }
+        Ok(Self { comparators })
+    }
+}
+
+impl VersionReq {
+    /// Whether `version` satisfies every comparison
+    pub fn matches(&self, ecosystem: Ecosystem, version: &str) -> bool {
+        self.comparators
+            .iter()
+            .all(|(op, bound)| op.holds(compare_versions(ecosystem, version, bound)))
+    }
+
+    /// Compare how narrowly two constraints pin down a version (Greater: `self` is narrower)
+    ///
+    /// An exact version is the narrowest, then constraints with more
+    /// comparisons (`>=1.5, <2.0` over `<2.0`); between equally many, the
+    /// lower upper bound and then the higher lower bound (`<2.0` over `<3.0`).
+    pub fn cmp_specificity(&self, other: &VersionReq, ecosystem: Ecosystem) -> Ordering {
+        let exact = |req: &VersionReq| req.comparators.iter().any(|(op, _)| *op == Op::Eq);
+        let cmp = |a: &String, b: &String| compare_versions(ecosystem, a, b);
+
+        // A missing bound is the widest
+        let upper = match (self.upper_bound(ecosystem), other.upper_bound(ecosystem)) {
+            (Some(a), Some(b)) => cmp(b, a),
+            (a, b) => a.is_some().cmp(&b.is_some()),
+        };
+        let lower = match (self.lower_bound(ecosystem), other.lower_bound(ecosystem)) {
+            (Some(a), Some(b)) => cmp(a, b),
+            (a, b) => a.is_some().cmp(&b.is_some()),
+        };
+
+        exact(self)
+            .cmp(&exact(other))
+            .then(self.comparators.len().cmp(&other.comparators.len()))
+            .then(upper)
+            .then(lower)
+    }
+
+    /// The lowest `<`/`<=` bound
+    fn upper_bound(&self, ecosystem: Ecosystem) -> Option<&String> {
+        self.comparators
+            .iter()
+            .filter(|(op, _)| matches!(op, Op::Lt | Op::Le))
+            .map(|(_, bound)| bound)
+            .min_by(|a, b| compare_versions(ecosystem, a, b))
+    }
+
+    /// The highest `>`/`>=` bound
+    fn lower_bound(&self, ecosystem: Ecosystem) -> Option<&String> {
+        self.comparators
+            .iter()
+            .filter(|(op, _)| matches!(op, Op::Gt | Op::Ge))
+            .map(|(_, bound)| bound)
+            .max_by(|a, b| compare_versions(ecosystem, a, b))
+    }
+}
+
+/// Compare two versions with an ecosystem's ordering
+pub fn compare_versions(ecosystem: Ecosystem, a: &str, b: &str) -> Ordering {
+    match ecosystem {
+        Ecosystem::Python => crate::python::compare_versions(a, b),
+        Ecosystem::Ruby => crate::ruby::compare_versions(a, b),
+        Ecosystem::Node | Ecosystem::Go | Ecosystem::Rust | Ecosystem::Swift => {
+            compare_semver(a, b)
+        }
+    }
+}
+
+/// Semver precedence; a leading `v` and build metadata (`+incompatible`) are ignored
+///
+/// Also orders Go module versions, pseudo-versions included.
+pub fn compare_semver(a: &str, b: &str) -> Ordering {
+    fn split(version: &str) -> (Vec<u64>, Option<&str>) {
+        let version = version.trim();
+        let version = version.strip_prefix('v').unwrap_or(version);
+        let version = version.split_once('+').map_or(version, |(v, _)| v);
+        let (core, pre) = match version.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (version, None),
+        };
+        let mut numbers: Vec<u64> = core.split('.').map(|n| n.parse().unwrap_or(0)).collect();
+        while numbers.len() > 1 && numbers.last() == Some(&0) {
+            numbers.pop();
+        }
+        (numbers, pre)
+    }
+
+    let (a_core, a_pre) = split(a);
+    let (b_core, b_pre) = split(b);
+    a_core.cmp(&b_core).then_with(|| match (a_pre, b_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => compare_prerelease(a, b),
+    })
+}
+
+/// Dot-separated identifiers: numbers numerically and below alphanumerics
+fn compare_prerelease(a: &str, b: &str) -> Ordering {
+    let mut a_ids = a.split('.');
+    let mut b_ids = b.split('.');
+    loop {
+        let ordering = match (a_ids.next(), b_ids.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => Ordering::Less,
+            (Some(_), None) => Ordering::Greater,
+            (Some(a), Some(b)) => match (a.parse::<u64>(), b.parse::<u64>()) {
+                (Ok(a), Ok(b)) => a.cmp(&b),
+                (Ok(_), Err(_)) => Ordering::Less,
+                (Err(_), Ok(_)) => Ordering::Greater,
+                (Err(_), Err(_)) => a.cmp(b),
+            },
+        };
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn req(constraint: &str) -> VersionReq {
+        constraint.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            req(">=3.1, <4").comparators,
+            vec![(Op::Ge, "3.1".to_string()), (Op::Lt, "4".to_string())]
+        );
+        assert_eq!(req(">= 3.1 <4"), req(">=3.1,<4"));
+        assert_eq!(req("1.2.3"), req("==1.2.3"));
+        assert_eq!(
+            req(">=1!2.0!=1!2.1").comparators,
+            vec![(Op::Ge, "1!2.0".to_string()), (Op::Ne, "1!2.1".to_string())]
+        );
+
+        assert_eq!("".parse::<VersionReq>(), Err(VersionReqError::Empty));
+        assert_eq!(
+            "=<2.0".parse::<VersionReq>(),
+            Err(VersionReqError::UnknownOperator {
+                constraint: "=<2.0".to_string(),
+                operator: "=<".to_string(),
+            })
+        );
+        assert!(matches!(
+            ">=".parse::<VersionReq>(),
+            Err(VersionReqError::MissingVersion { .. })
+        ));
+        assert!("~> 2.0".parse::<VersionReq>().is_err());
+        assert!("^2.0".parse::<VersionReq>().is_err());
+    }
+
+    #[test]
+    fn test_matches_semver() {
+        let legacy = req("<2.0");
+        assert!(legacy.matches(Ecosystem::Node, "1.9.9"));
+        assert!(legacy.matches(Ecosystem::Node, "2.0.0-beta.1"));
+        assert!(!legacy.matches(Ecosystem::Node, "2.0.0"));
+        assert!(legacy.matches(Ecosystem::Go, "v1.18.0"));
+        assert!(!legacy.matches(Ecosystem::Go, "v2.0.0+incompatible"));
+
+        let range = req(">=3.1, <4");
+        assert!(range.matches(Ecosystem::Rust, "3.1.0"));
+        assert!(range.matches(Ecosystem::Rust, "3.10.2"));
+        assert!(!range.matches(Ecosystem::Rust, "3.0.9"));
+        assert!(!range.matches(Ecosystem::Rust, "4.0.0"));
+
+        assert!(req("!=1.2.3").matches(Ecosystem::Swift, "1.2.4"));
+        assert!(!req("1.2").matches(Ecosystem::Swift, "1.2.1"));
+    }
+
+    #[test]
+    fn test_compare_prerelease() {
+        let ordered = [
+            "1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+        ];
+        for pair in ordered.windows(2) {
+            assert_eq!(compare_semver(pair[0], pair[1]), Ordering::Less);
+        }
+    }
+
+    #[test]
+    fn test_compare_semver_go_versions() {
+        assert_eq!(compare_semver("v1.9.1", "1.10.0"), Ordering::Less);
+        assert_eq!(
+            compare_semver("v2.0.0+incompatible", "v2.0.0"),
+            Ordering::Equal
+        );
+        assert_eq!(
+            compare_semver("v1.2.0-rc.2", "v1.2.0-rc.10"),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_semver(
+                "v0.0.0-20240101000000-abc123def456",
+                "v0.0.0-20231215123456-abc123def456"
+            ),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn test_cmp_specificity() {
+        let node = Ecosystem::Node;
+        assert_eq!(
+            req(">=1.5, <2.0").cmp_specificity(&req("<2.0"), node),
+            Ordering::Greater
+        );
+        assert_eq!(
+            req("=1.9.0").cmp_specificity(&req(">=1.5, <2.0"), node),
+            Ordering::Greater
+        );
+        assert_eq!(
+            req("<2.0").cmp_specificity(&req("<3.0"), node),
+            Ordering::Greater
+        );
+        assert_eq!(
+            req(">=1.5").cmp_specificity(&req(">=1.0"), node),
+            Ordering::Greater
+        );
+        assert_eq!(
+            req("<2.0").cmp_specificity(&req(">=1.0"), node),
+            Ordering::Greater
+        );
+        assert_eq!(
+            req("<2.0").cmp_specificity(&req("<2.0.0"), node),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn test_matches_uses_ecosystem_ordering() {
+        // 2.0rc1 and 2.0.0.rc1 are prereleases of 2.0 in their ecosystems
+        assert!(req("<2.0").matches(Ecosystem::Python, "2.0rc1"));
+        assert!(!req("<2.0").matches(Ecosystem::Python, "2.0.post1"));
+        assert!(req("<2.0").matches(Ecosystem::Ruby, "2.0.0.rc1"));
+        assert!(req(">=3.1").matches(Ecosystem::Ruby, "3.1"));
+    }
+}
@@ -2,7 +2,8 @@ use std::path::Path;
 use std::process::Command;
 
 use super::harness::{
-    CommandOutput, TestContext, TestEnv, ensure_dir, parse_json, read_file, symlink_dir, write_file,
+    CommandOutput, TestContext, TestEnv, ensure_dir, parse_json, read_file, serve_http,
+    symlink_dir, write_file,
 };
 
 pub struct Scenario {
@@ -48,6 +49,14 @@ pub fn scenarios() -> Vec<Scenario> {
             name: "symlink_list_remove_clean",
             run: scenario_symlink_list_remove_clean,
         },
+        Scenario {
+            name: "corrupt_state_list_remove",
+            run: scenario_corrupt_state_list_remove,
+        },
+        Scenario {
+            name: "sync_manifest",
+            run: scenario_sync_manifest,
+        },
         Scenario {
             name: "init_fresh_directory",
             run: scenario_init_fresh,
@@ -132,6 +141,10 @@ pub fn scenarios() -> Vec<Scenario> {
             name: "multi_ecosystem_context",
             run: scenario_multi_ecosystem_context,
         },
+        Scenario {
+            name: "local_registry_mirror",
+            run: scenario_local_registry_mirror,
+        },
     ]
 }
 
@@ -243,6 +256,98 @@ fn scenario_symlink_list_remove_clean(ctx: &TestContext) -> Result<(), String> {
     Ok(())
 }
 
+fn scenario_corrupt_state_list_remove(ctx: &TestContext) -> Result<(), String> {
+    let env = ctx.create_env("corrupt-state")?;
+    let cache_path = env
+        .xdg_cache
+        .join("dotdeps")
+        .join("python")
+        .join("requests")
+        .join("2.31.0");
+    ensure_dir(&cache_path)?;
+    write_file(&cache_path.join("README.md"), "fake")?;
+
+    let deps_path = env.root.join(".deps").join("python").join("requests");
+    ensure_dir(deps_path.parent().unwrap())?;
+    symlink_dir(&cache_path, &deps_path)?;
+    write_file(
+        &env.root.join(".deps").join(".dotdeps-state.json"),
+        "{ not json",
+    )?;
+
+    let list = ctx.run_dotdeps(&env, &["list"], &env.root)?;
+    list.assert_success()?;
+    list.assert_stdout_contains("python:requests@2.31.0")?;
+    list.assert_stderr_contains("Warning:")?;
+
+    let remove = ctx.run_dotdeps(&env, &["remove", "python:requests"], &env.root)?;
+    remove.assert_success()?;
+    if deps_path.symlink_metadata().is_ok() {
+        return Err("Expected .deps/python/requests to be removed".to_string());
+    }
+    Ok(())
+}
+
+fn scenario_sync_manifest(ctx: &TestContext) -> Result<(), String> {
+    let env = ctx.create_env("sync-manifest")?;
+    let cache_root = env.xdg_cache.join("dotdeps");
+    let requests_cache = cache_root.join("python").join("requests").join("2.31.0");
+    ensure_dir(&requests_cache.join(".git"))?;
+    write_file(&requests_cache.join("README.md"), "fake")?;
+    let lodash_cache = cache_root.join("node").join("lodash").join("4.17.21");
+    ensure_dir(&lodash_cache.join(".git"))?;
+
+    let lodash_link = env.root.join(".deps").join("node").join("lodash");
+    ensure_dir(lodash_link.parent().unwrap())?;
+    symlink_dir(&lodash_cache, &lodash_link)?;
+
+    write_file(
+        &env.root.join(".deps.toml"),
+        "dependencies = [\"python:requests@2.31.0\"]\n",
+    )?;
+
+    let plan = ctx.run_dotdeps(&env, &["sync", "--dry-run", "--json"], &env.root)?;
+    plan.assert_success()?;
+    let value = parse_json(&plan.stdout)?;
+    let actions = value
+        .get("actions")
+        .and_then(|v| v.as_array())
+        .ok_or("Expected actions array")?;
+    let summary: Vec<(String, String)> = actions
+        .iter()
+        .map(|a| {
+            (
+                a["package"].as_str().unwrap_or("").to_string(),
+                a["action"].as_str().unwrap_or("").to_string(),
+            )
+        })
+        .collect();
+    let expected = vec![
+        ("requests".to_string(), "add".to_string()),
+        ("lodash".to_string(), "remove".to_string()),
+    ];
+    if summary != expected {
+        return Err(format!("Unexpected sync plan: {:?}", summary));
+    }
+    if lodash_link.symlink_metadata().is_err() {
+        return Err("Dry run should not remove .deps/node/lodash".to_string());
+    }
+
+    let sync = ctx.run_dotdeps(&env, &["sync"], &env.root)?;
+    sync.assert_success()?;
+    sync.assert_stdout_contains("Using cached requests 2.31.0")?;
+
+    let list = ctx.run_dotdeps(&env, &["list"], &env.root)?;
+    list.assert_success()?;
+    list.assert_stdout_contains("python:requests@2.31.0")?;
+    list.assert_stdout_not_contains("node:lodash")?;
+
+    let again = ctx.run_dotdeps(&env, &["sync"], &env.root)?;
+    again.assert_success()?;
+    again.assert_stdout_contains("python:requests@2.31.0 is up to date")?;
+    Ok(())
+}
+
 // =============================================================================
 // Init command scenarios
 // =============================================================================
@@ -796,6 +901,68 @@ dependencies = ["requests==2.31.0"]
     Ok(())
 }
 
+/// Registry lookups and clones against local stand-ins: a registry mirror
+/// served on localhost and a `file://` repository reached through `git_rewrites`
+fn scenario_local_registry_mirror(ctx: &TestContext) -> Result<(), String> {
+    require_cmd(ctx, "git")?;
+    let env = ctx.create_env("local-registry-mirror")?;
+    let proj = env.root.join("proj");
+    ensure_dir(&proj)?;
+
+    let repo = env.root.join("repos").join("leftpad.git");
+    ensure_dir(&repo)?;
+    write_file(&repo.join("index.js"), "module.exports = leftpad;\n")?;
+    run_cmd(ctx, &env, "git", &["init", "-q"], &repo)?;
+    run_cmd(ctx, &env, "git", &["add", "."], &repo)?;
+    run_cmd(
+        ctx,
+        &env,
+        "git",
+        &[
+            "-c",
+            "user.name=dotdeps",
+            "-c",
+            "user.email=dotdeps@example.com",
+            "commit",
+            "-q",
+            "-m",
+            "Release 1.0.0",
+        ],
+        &repo,
+    )?;
+    run_cmd(ctx, &env, "git", &["tag", "v1.0.0"], &repo)?;
+
+    let metadata =
+        r#"{"repository": {"type": "git", "url": "git+https://github.com/example/leftpad.git"}}"#;
+    let registry = serve_http(vec![("/leftpad".to_string(), metadata.as_bytes().to_vec())])?;
+
+    let config = serde_json::json!({
+        "registry_url": {"node": registry},
+        "git_rewrites": {
+            "https://github.com/example/": format!("file://{}/", env.root.join("repos").display())
+        }
+    });
+    write_file(
+        &env.xdg_config.join("dotdeps").join("config.json"),
+        &config.to_string(),
+    )?;
+
+    let add = ctx.run_dotdeps(&env, &["add", "node:leftpad@1.0.0", "--json"], &proj)?;
+    add.assert_success()?;
+    let json = parse_json(&add.stdout)?;
+    if json["cloned_ref"] != "v1.0.0" {
+        return Err(format!(
+            "Expected cloned_ref v1.0.0, got {}",
+            json["cloned_ref"]
+        ));
+    }
+    let linked = read_file(&proj.join(".deps/node/leftpad/index.js"))?;
+    if !linked.contains("leftpad") {
+        return Err("Expected index.js from the local repository".to_string());
+    }
+    Ok(())
+}
+
 fn require_cmd(ctx: &TestContext, cmd: &str) -> Result<(), String> {
     if ctx.command_available(cmd) {
         Ok(())